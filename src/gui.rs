@@ -35,6 +35,12 @@ pub struct SubWindowManager {
     pub export_message: ExportMessage,
     pub import_message: ImportMessage,
     pub section_message: SectionMessage,
+    pub relocation_message: RelocationMessage,
+    pub exception_message: ExceptionMessage,
+    pub dotnet_message: DotNetMessage,
+    pub resource_message: ResourceMessage,
+    pub symbol_message: SymbolMessage,
+    pub archive_message: ArchiveMessage,
     pub window_message: WindowMessage,
     pub toasts: Vec<Toast>,
 }
@@ -64,10 +70,54 @@ pub struct ImportMessage {
     pub search_string: String,
 }
 
+/// 重定位信息管理器
+#[derive(Default)]
+pub struct RelocationMessage {
+    selected_block_index: Option<usize>,
+    pub search_string: String,
+}
+
+/// 异常目录信息管理器
+#[derive(Default)]
+pub struct ExceptionMessage {
+    selected_function_index: Option<usize>,
+    pub search_string: String,
+}
+
+/// .NET元数据信息管理器
+#[derive(Default)]
+pub struct DotNetMessage {
+    selected_table_index: usize,
+    pub search_string: String,
+}
+
+/// 资源信息管理器
+#[derive(Default)]
+pub struct ResourceMessage {
+    selected_tab_index: usize,
+    selected_language_index: usize,
+    pub search_string: String,
+}
+
+/// 符号表信息管理器
+#[derive(Default)]
+pub struct SymbolMessage {
+    pub search_string: String,
+}
+
+/// 静态库信息管理器
+#[derive(Default)]
+pub struct ArchiveMessage {
+    selected_tab_index: usize,
+    pub search_string: String,
+}
+
 #[derive(Default)]
 pub struct SectionMessage {
     pub selected_section_index: Option<usize>,
     section_flag: Option<SectionFlag>,
+    /// 显示重定位项与COMDAT信息的节
+    pub selected_relocation_index: Option<usize>,
 }
 
 #[derive(Default)]
@@ -231,10 +281,47 @@ impl ImportMessage {
         self.search_string = String::new();
     }
 }
+impl RelocationMessage {
+    pub fn clear(&mut self) {
+        self.selected_block_index = None;
+        self.search_string = String::new();
+    }
+}
+impl ExceptionMessage {
+    pub fn clear(&mut self) {
+        self.selected_function_index = None;
+        self.search_string = String::new();
+    }
+}
+impl DotNetMessage {
+    pub fn clear(&mut self) {
+        self.selected_table_index = 0;
+        self.search_string = String::new();
+    }
+}
+impl ResourceMessage {
+    pub fn clear(&mut self) {
+        self.selected_tab_index = 0;
+        self.selected_language_index = 0;
+        self.search_string = String::new();
+    }
+}
+impl SymbolMessage {
+    pub fn clear(&mut self) {
+        self.search_string = String::new();
+    }
+}
+impl ArchiveMessage {
+    pub fn clear(&mut self) {
+        self.selected_tab_index = 0;
+        self.search_string = String::new();
+    }
+}
 impl SectionMessage {
     pub fn clear(&mut self) {
         self.selected_section_index = None;
         self.section_flag = None;
+        self.selected_relocation_index = None;
     }
     pub fn get_image_scn_cnt_code(&mut self) -> &mut bool {
         &mut self.section_flag.as_mut().unwrap().image_scn_cnt_code
@@ -375,6 +462,12 @@ impl SubWindowManager {
         self.export_message.clear();
         self.import_message.clear();
         self.section_message.clear();
+        self.relocation_message.clear();
+        self.exception_message.clear();
+        self.dotnet_message.clear();
+        self.resource_message.clear();
+        self.symbol_message.clear();
+        self.archive_message.clear();
     }
 }
 
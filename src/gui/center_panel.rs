@@ -1,12 +1,22 @@
+mod anomaly;
+mod archive;
+mod debug;
 mod dos_header;
 mod dos_stub;
+mod dotnet;
+mod exception;
 mod export_table;
 mod import_table;
+mod load_config;
 mod nt_header;
+mod relocation;
+mod resource;
 mod section;
+mod signature;
+mod symbol;
+mod tls;
 use crate::gui::FileManager;
 use crate::i18n;
-use crate::tools_api::calc::get_hash_info;
 use crate::tools_api::file_system::open_file_location;
 use crate::tools_api::load_file_info;
 use crate::tools_api::{FileInfo, Page};
@@ -16,6 +26,15 @@ const CENTER_PANEL_FILL_COLOR: Color32 = Color32::from_rgb(30, 31, 34);
 const CENTER_PANEL_BOTTOM_FILL_COLOR: Color32 = Color32::from_rgb(43, 45, 48);
 const CENTER_PANEL_TITLE_COLOR: Color32 = Color32::from_rgb(255, 255, 255);
 const CENTER_PANEL_TITLE_SIZE: f32 = 32.0;
+/// 底部信息栏中显示的版本信息字段
+const VERSION_INFO_KEYS: [&str; 6] = [
+    "OriginalFilename",
+    "FileVersion",
+    "ProductVersion",
+    "ProductName",
+    "CompanyName",
+    "FileDescription",
+];
 
 impl FileManager {
     /// 信息栏一级标题
@@ -51,14 +70,25 @@ impl FileManager {
                     }
                     Ok(())
                 });
+                if let Some(archive_parent) = &file.archive_parent {
+                    ui.label(format!("Archive: {}", archive_parent.to_string_lossy()));
+                }
                 ui.label(format!("File Size: {}B", file.file_size));
-                if file.file_hash.is_none() {
-                    file.file_hash = get_hash_info(file.file_path.clone());
+                let anomaly_count = file.anomalies.borrow().len();
+                if anomaly_count != 0 {
+                    ui.label(i18n::ANOMALY_COUNT.replace("{}", &anomaly_count.to_string()));
                 }
+                file.update_file_hash();
                 if let Some(file_hash) = &file.file_hash {
                     ui.horizontal(|ui| {
                         ui.label(format!("File MD5: {}", file_hash.md5));
                         ui.label(format!("File SHA1: {}", file_hash.sha1));
+                        if let Some(imphash) = &file_hash.imphash {
+                            ui.label(format!("Imphash: {}", imphash));
+                        }
+                        if let Some(exphash) = &file_hash.exphash {
+                            ui.label(format!("Exphash: {}", exphash));
+                        }
                     });
                 } else {
                     ui.horizontal(|ui| {
@@ -66,6 +96,24 @@ impl FileManager {
                         ui.label(format!("File SHA1: {}", "-"));
                     });
                 }
+                if let Some(version_info) = &file.version_info {
+                    ui.horizontal_wrapped(|ui| {
+                        for key in VERSION_INFO_KEYS {
+                            if let Some(value) = version_info.get_string(key) {
+                                ui.label(format!("{}: {}", key, value));
+                            }
+                        }
+                        // 没有字符串表时退回到 VS_FIXEDFILEINFO 中的版本号
+                        if version_info.get_string("FileVersion").is_none()
+                            && let Some(fixed_file_info) = &version_info.fixed_file_info
+                        {
+                            ui.label(format!(
+                                "FileVersion: {}",
+                                fixed_file_info.get_file_version()
+                            ));
+                        }
+                    });
+                }
             });
         Ok(())
     }
@@ -92,12 +140,45 @@ impl FileManager {
                     if let Err(e) = Self::show_bottom_panel(file, ctx) {
                         self.sub_window_manager.show_error(&e.to_string());
                     }
+                    let is_coff_object = file.is_coff_object;
+                    let is_archive = file.archive.is_some();
+                    // 目标文件没有DOS头与数据目录，切换到仍然有效的页面
+                    if is_coff_object && !self.page.is_coff_object_page() {
+                        self.page = Page::SectionHead;
+                    }
+                    // 静态库只有成员列表页面
+                    if is_archive {
+                        self.page = Page::Archive;
+                    } else if matches!(self.page, Page::Archive) {
+                        self.page = Page::default();
+                    }
                     CentralPanel::default()
                         .frame(Frame::new().fill(CENTER_PANEL_FILL_COLOR))
                         .show(ctx, |_ui| {
                             Area::new(Id::from("area")).movable(false).show(ctx, |ui| {
                                 eframe::egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
                                     ui.horizontal(|ui| {
+                                        if is_archive {
+                                            if ui.button("Archive").clicked() {
+                                                self.page = Page::Archive
+                                            }
+                                            return;
+                                        }
+                                        if is_coff_object {
+                                            if ui.button("COFF header").clicked() {
+                                                self.page = Page::NtHead
+                                            }
+                                            if ui.button("Section header").clicked() {
+                                                self.page = Page::SectionHead
+                                            }
+                                            if ui.button("Symbol").clicked() {
+                                                self.page = Page::Symbol
+                                            }
+                                            if ui.button("Anomalies").clicked() {
+                                                self.page = Page::Anomaly
+                                            }
+                                            return;
+                                        }
                                         if ui.button("Dos head").clicked() {
                                             self.page = Page::DosHead
                                         }
@@ -116,6 +197,36 @@ impl FileManager {
                                         if ui.button("Export table").clicked() {
                                             self.page = Page::Export
                                         }
+                                        if ui.button("Relocation table").clicked() {
+                                            self.page = Page::Relocation
+                                        }
+                                        if ui.button("TLS").clicked() {
+                                            self.page = Page::Tls
+                                        }
+                                        if ui.button("Load config").clicked() {
+                                            self.page = Page::LoadConfig
+                                        }
+                                        if ui.button("Debug").clicked() {
+                                            self.page = Page::Debug
+                                        }
+                                        if ui.button("Exception").clicked() {
+                                            self.page = Page::Exception
+                                        }
+                                        if ui.button("Signature").clicked() {
+                                            self.page = Page::Signature
+                                        }
+                                        if ui.button(".NET").clicked() {
+                                            self.page = Page::DotNet
+                                        }
+                                        if ui.button("Resource").clicked() {
+                                            self.page = Page::Resource
+                                        }
+                                        if ui.button("Symbol").clicked() {
+                                            self.page = Page::Symbol
+                                        }
+                                        if ui.button("Anomalies").clicked() {
+                                            self.page = Page::Anomaly
+                                        }
                                     });
                                 });
                                 match self.page {
@@ -141,6 +252,53 @@ impl FileManager {
                                     Page::Export => {
                                         self.export_panel(ui);
                                     }
+                                    Page::Relocation => {
+                                        if let Err(e) = self.relocation_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Tls => {
+                                        if let Err(e) = self.tls_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::LoadConfig => {
+                                        if let Err(e) = self.load_config_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Debug => {
+                                        if let Err(e) = self.debug_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Exception => {
+                                        if let Err(e) = self.exception_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Signature => {
+                                        if let Err(e) = self.signature_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::DotNet => {
+                                        if let Err(e) = self.dotnet_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Resource => {
+                                        if let Err(e) = self.resource_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Symbol => {
+                                        if let Err(e) = self.symbol_panel(ui) {
+                                            self.sub_window_manager.show_error(&e.to_string());
+                                        }
+                                    }
+                                    Page::Archive => self.archive_panel(ui),
+                                    Page::Anomaly => self.anomaly_panel(ui),
                                 }
                             });
                         });
@@ -0,0 +1,66 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::read_file::{Anomaly, AnomalySeverity};
+use eframe::egui::{Color32, Label, RichText, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const ANOMALY_COLUMNS: usize = 4;
+const INFO_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
+const WARNING_COLOR: Color32 = Color32::from_rgb(234, 198, 118);
+const ERROR_COLOR: Color32 = Color32::from_rgb(220, 80, 80);
+
+impl FileManager {
+    pub(crate) fn anomaly_panel(&mut self, ui: &mut Ui) {
+        let file = &self.files[self.current_index];
+        let anomalies = file.anomalies.borrow();
+        if anomalies.is_empty() {
+            ui.add(Label::new(i18n::NO_ANOMALIES));
+            return;
+        }
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, i18n::ANOMALY_LIST);
+            // 严重程度较高的排在前面，同级别按偏移排列
+            let mut sorted: Vec<&Anomaly> = anomalies.iter().collect();
+            sorted.sort_by_key(|anomaly| (std::cmp::Reverse(anomaly.severity), anomaly.offset));
+            let col_width = ui.available_width() / ANOMALY_COLUMNS as f32;
+            let row_height = ui.spacing().interact_size.y + SPACING.y;
+            ScrollArea::vertical()
+                .id_salt("anomaly_table")
+                .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                .auto_shrink([false, false])
+                .show_rows(ui, row_height, sorted.len() + 1, |ui, range| {
+                    eframe::egui::Grid::new("anomaly_table")
+                        .striped(true)
+                        .spacing(SPACING)
+                        .num_columns(ANOMALY_COLUMNS)
+                        .min_col_width(col_width)
+                        .show(ui, |ui| {
+                            ui.strong(i18n::ANOMALY_SEVERITY);
+                            ui.strong(i18n::ANOMALY_FIELD);
+                            ui.strong(i18n::OFFSET);
+                            ui.strong(i18n::ANOMALY_DESCRIPTION);
+                            ui.end_row();
+                            let start = range.start.saturating_sub(1);
+                            let end = range.end.saturating_sub(1).min(sorted.len());
+                            for anomaly in &sorted[start..end.max(start)] {
+                                Self::show_anomaly_row(ui, anomaly);
+                            }
+                        });
+                });
+        });
+    }
+
+    fn show_anomaly_row(ui: &mut Ui, anomaly: &Anomaly) {
+        let color = match anomaly.severity {
+            AnomalySeverity::Info => INFO_COLOR,
+            AnomalySeverity::Warning => WARNING_COLOR,
+            AnomalySeverity::Error => ERROR_COLOR,
+        };
+        ui.label(RichText::new(anomaly.severity.get_name()).color(color));
+        ui.label(&anomaly.field);
+        ui.label(format!("0x{:08X}", anomaly.offset));
+        ui.label(&anomaly.description);
+        ui.end_row();
+    }
+}
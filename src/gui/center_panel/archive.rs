@@ -0,0 +1,248 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::load_file_info;
+use crate::tools_api::read_file::{ArchiveInfo, ArchiveMemberKind};
+use crate::tools_api::search;
+use eframe::egui::{Color32, RichText, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const HEADER_COLUMNS: usize = 2;
+const MEMBER_COLUMNS: usize = 7;
+const IMPORT_COLUMNS: usize = 7;
+const SYMBOL_COLUMNS: usize = 2;
+const ADD_SPACE: f32 = 10.0;
+const ARCHIVE_TABS: [&str; 3] = [
+    i18n::ARCHIVE_MEMBERS,
+    i18n::ARCHIVE_IMPORTS,
+    i18n::ARCHIVE_SYMBOLS,
+];
+const COFF_OBJECT_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
+
+impl FileManager {
+    pub(crate) fn archive_panel(&mut self, ui: &mut Ui) {
+        let mut open_member = None;
+        let file = &self.files[self.current_index];
+        let Some(archive) = &file.archive else {
+            return;
+        };
+        let message = &mut self.sub_window_manager.archive_message;
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Archive");
+            Self::show_archive_header(ui, archive);
+            ui.add_space(ADD_SPACE);
+            ui.horizontal(|ui| {
+                for (index, name) in ARCHIVE_TABS.iter().enumerate() {
+                    ui.selectable_value(&mut message.selected_tab_index, index, *name);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.text_edit_singleline(&mut message.search_string);
+            });
+            ui.add_space(ADD_SPACE);
+            match message.selected_tab_index {
+                0 => open_member = Self::show_archive_members(ui, archive, &message.search_string),
+                1 => Self::show_archive_imports(ui, archive, &message.search_string),
+                _ => Self::show_archive_symbols(ui, archive, &message.search_string),
+            }
+        });
+        if let Some(member_index) = open_member
+            && let Err(e) = self.open_archive_member(self.current_index, member_index)
+        {
+            self.sub_window_manager.show_error(&e.to_string());
+        }
+    }
+
+    fn show_archive_header(ui: &mut Ui, archive: &ArchiveInfo) {
+        let col_width = ui.available_width() / HEADER_COLUMNS as f32;
+        eframe::egui::Grid::new("archive_header")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(HEADER_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.label(i18n::ARCHIVE_MEMBERS);
+                ui.label(format!("{}", archive.members.len()));
+                ui.end_row();
+                ui.label(i18n::ARCHIVE_IMPORTS);
+                ui.label(format!("{}", archive.get_import_objects().count()));
+                ui.end_row();
+                ui.label(i18n::ARCHIVE_SYMBOLS);
+                ui.label(format!("{}", archive.symbols.len()));
+                ui.end_row();
+            });
+    }
+
+    /// 成员列表，COFF目标文件可以直接打开，返回被点击打开的成员索引
+    fn show_archive_members(
+        ui: &mut Ui,
+        archive: &ArchiveInfo,
+        search_string: &str,
+    ) -> Option<usize> {
+        let mut open_member = None;
+        let filtered: Vec<usize> = archive
+            .members
+            .iter()
+            .enumerate()
+            .filter(|(_, member)| search(&member.name, search_string))
+            .map(|(index, _)| index)
+            .collect();
+        let col_width = ui.available_width() / (2 * MEMBER_COLUMNS) as f32;
+        let row_height = ui.spacing().interact_size.y + SPACING.y;
+        ScrollArea::vertical()
+            .id_salt("archive_members")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, filtered.len() + 1, |ui, range| {
+                eframe::egui::Grid::new("archive_members")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(MEMBER_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::MEMBER_NAME);
+                        ui.strong(i18n::MEMBER_KIND);
+                        ui.strong(i18n::FILE_OFFSET);
+                        ui.strong(i18n::SIZE);
+                        ui.strong(i18n::MEMBER_DATE);
+                        ui.strong("Mode");
+                        ui.strong(i18n::OPERATION);
+                        ui.end_row();
+                        let start = range.start.saturating_sub(1);
+                        let end = range.end.saturating_sub(1).min(filtered.len());
+                        for &index in &filtered[start..end.max(start)] {
+                            let member = &archive.members[index];
+                            let is_coff_object = member.kind == ArchiveMemberKind::CoffObject;
+                            if is_coff_object {
+                                ui.label(
+                                    RichText::new(member.get_display_name())
+                                        .color(COFF_OBJECT_COLOR),
+                                )
+                                .on_hover_text(&member.name);
+                            } else {
+                                ui.label(member.get_display_name())
+                                    .on_hover_text(&member.name);
+                            }
+                            ui.label(member.get_kind_name());
+                            ui.label(format!("0x{:08X}", member.header_offset));
+                            ui.label(format!("0x{:X}", member.size));
+                            ui.label(format!("{}", member.date));
+                            ui.label(&member.mode);
+                            if is_coff_object && ui.button(i18n::OPEN_BUTTON).clicked() {
+                                open_member = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        open_member
+    }
+
+    /// 导入库中的短格式导入项
+    fn show_archive_imports(ui: &mut Ui, archive: &ArchiveInfo, search_string: &str) {
+        let filtered: Vec<_> = archive
+            .get_import_objects()
+            .filter(|import| {
+                search(&import.symbol_name, search_string)
+                    || search(&import.dll_name, search_string)
+            })
+            .collect();
+        let col_width = ui.available_width() / (2 * IMPORT_COLUMNS) as f32;
+        let row_height = ui.spacing().interact_size.y + SPACING.y;
+        ScrollArea::vertical()
+            .id_salt("archive_imports")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, filtered.len() + 1, |ui, range| {
+                eframe::egui::Grid::new("archive_imports")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(IMPORT_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::DLL_NAME);
+                        ui.strong(i18n::IMPORT_NAME);
+                        ui.strong(i18n::SYMBOL_NAME);
+                        ui.strong(i18n::IMPORT_TYPE);
+                        ui.strong(i18n::NAME_TYPE);
+                        ui.strong(i18n::ORDINAL_OR_HINT);
+                        ui.strong(i18n::MACHINE_TYPE);
+                        ui.end_row();
+                        let start = range.start.saturating_sub(1);
+                        let end = range.end.saturating_sub(1).min(filtered.len());
+                        for import in &filtered[start..end.max(start)] {
+                            ui.label(&import.dll_name);
+                            ui.label(import.get_import_name());
+                            ui.label(&import.symbol_name);
+                            ui.label(import.get_import_type_name());
+                            ui.label(import.get_name_type_name());
+                            ui.label(format!("{}", import.ordinal_or_hint));
+                            ui.label(import.get_machine_name());
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// 链接器成员中的符号索引
+    fn show_archive_symbols(ui: &mut Ui, archive: &ArchiveInfo, search_string: &str) {
+        let filtered: Vec<_> = archive
+            .symbols
+            .iter()
+            .filter(|symbol| search(&symbol.name, search_string))
+            .collect();
+        let col_width = ui.available_width() / (2 * SYMBOL_COLUMNS) as f32;
+        let row_height = ui.spacing().interact_size.y + SPACING.y;
+        ScrollArea::vertical()
+            .id_salt("archive_symbols")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, filtered.len() + 1, |ui, range| {
+                eframe::egui::Grid::new("archive_symbols")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(SYMBOL_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::SYMBOL_NAME);
+                        ui.strong(i18n::MEMBER_NAME);
+                        ui.end_row();
+                        let start = range.start.saturating_sub(1);
+                        let end = range.end.saturating_sub(1).min(filtered.len());
+                        for symbol in &filtered[start..end.max(start)] {
+                            ui.label(&symbol.name);
+                            ui.label(
+                                symbol
+                                    .member_index
+                                    .and_then(|index| archive.members.get(index))
+                                    .map(|member| member.get_display_name())
+                                    .unwrap_or("-"),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// 打开静态库中的成员，已经打开过时直接切换到该成员
+    pub(crate) fn open_archive_member(
+        &mut self,
+        archive_index: usize,
+        member_index: usize,
+    ) -> anyhow::Result<()> {
+        let archive_file = &self.files[archive_index];
+        let path = archive_file.extract_archive_member(member_index)?;
+        match self.files.iter().position(|file| file.file_path == path) {
+            Some(index) => self.current_index = index,
+            None => {
+                let mut file_info = load_file_info(path)?;
+                file_info.archive_parent = Some(archive_file.file_path.clone());
+                self.files.push(file_info);
+                self.current_index = self.files.len() - 1;
+            }
+        }
+        self.sub_window_manager.clear_data();
+        Ok(())
+    }
+}
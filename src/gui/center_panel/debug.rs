@@ -0,0 +1,184 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::read_file::{
+    CodeViewInfo, DebugData, DebugEntry, DebugTable, PogoInfo, VcFeatureInfo,
+};
+use eframe::egui::{Label, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const ENTRY_COLUMNS: usize = 6;
+const DETAIL_COLUMNS: usize = 2;
+const POGO_COLUMNS: usize = 3;
+const ADD_SPACE: f32 = 10.0;
+
+impl FileManager {
+    pub(crate) fn debug_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let debug = self.debug()?;
+        if debug.0.borrow().is_empty() {
+            ui.add(Label::new(i18n::NO_DEBUG_DIRECTORY));
+            return Ok(());
+        }
+        let entries = debug.0.borrow();
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Debug Directory");
+            eframe::egui::ScrollArea::vertical()
+                .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                .show(ui, |ui| {
+                    let width = ui.available_width();
+                    Self::show_debug_entry_table(ui, &entries, width);
+                    for (index, entry) in entries.iter().enumerate() {
+                        ui.add_space(ADD_SPACE);
+                        Self::show_debug_data(ui, index, entry, width);
+                    }
+                });
+        });
+        Ok(())
+    }
+
+    /// 获取调试目录的引用
+    pub(crate) fn debug(&mut self) -> anyhow::Result<DebugTable> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.debug.0.borrow().is_empty() {
+            file.debug = file.get_debug()?;
+        }
+        Ok(file.debug.fclone())
+    }
+
+    fn show_debug_entry_table(ui: &mut Ui, entries: &[DebugEntry], width: f32) {
+        let col_width = width / ENTRY_COLUMNS as f32;
+        eframe::egui::Grid::new("debug_entry_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(ENTRY_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong(i18n::SEQUENCE_NUMBER);
+                ui.strong(i18n::DEBUG_TYPE);
+                ui.strong(i18n::TIMESTAMP);
+                ui.strong(i18n::DEBUG_SIZE_OF_DATA);
+                ui.strong(i18n::DEBUG_ADDRESS_OF_RAW_DATA);
+                ui.strong(i18n::DEBUG_POINTER_TO_RAW_DATA);
+                ui.end_row();
+
+                for (index, entry) in entries.iter().enumerate() {
+                    ui.label(format!("{}", index + 1));
+                    ui.label(format!("{} ({})", entry.get_type_name(), entry.debug_type));
+                    ui.label(format!("0x{:08X}", entry.time_date_stamp));
+                    ui.label(format!("0x{:X}", entry.size_of_data));
+                    ui.label(format!("0x{:08X}", entry.address_of_raw_data));
+                    ui.label(format!("0x{:08X}", entry.pointer_to_raw_data));
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// 按类型显示解码后的调试数据
+    fn show_debug_data(ui: &mut Ui, index: usize, entry: &DebugEntry, width: f32) {
+        let title = format!("{} {}", index + 1, entry.get_type_name());
+        let id = format!("debug_data_grid_{}", index);
+        match &entry.data {
+            DebugData::CodeView(code_view) => {
+                Self::show_sub_title(ui, &title);
+                Self::show_code_view(ui, &id, code_view, width);
+            }
+            DebugData::Pogo(pogo) => {
+                Self::show_sub_title(ui, &title);
+                Self::show_pogo(ui, &id, pogo, width);
+            }
+            DebugData::VcFeature(vc_feature) => {
+                Self::show_sub_title(ui, &title);
+                Self::show_vc_feature(ui, &id, vc_feature, width);
+            }
+            DebugData::Repro(repro) => {
+                Self::show_sub_title(ui, &title);
+                if repro.hash.is_empty() {
+                    ui.label(i18n::REPRO_NO_HASH);
+                } else {
+                    ui.label(format!("{}: {}", i18n::REPRO_HASH, repro.hash));
+                }
+            }
+            DebugData::ExDllCharacteristics(value) => {
+                Self::show_sub_title(ui, &title);
+                let names = entry.get_ex_dll_characteristics_names();
+                ui.label(format!("0x{:08X} {}", value, names.join(" | ")));
+            }
+            DebugData::Unknown => {}
+        }
+    }
+
+    fn show_code_view(ui: &mut Ui, id: &str, code_view: &CodeViewInfo, width: f32) {
+        let col_width = width / DETAIL_COLUMNS as f32;
+        eframe::egui::Grid::new(id)
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.label(i18n::CODEVIEW_SIGNATURE);
+                ui.label(&code_view.signature);
+                ui.end_row();
+                if code_view.guid.is_empty() {
+                    ui.label(i18n::TIMESTAMP);
+                    ui.label(format!("0x{:08X}", code_view.nb10_signature));
+                } else {
+                    ui.label("GUID");
+                    ui.label(&code_view.guid);
+                }
+                ui.end_row();
+                ui.label(i18n::CODEVIEW_AGE);
+                ui.label(format!("{}", code_view.age));
+                ui.end_row();
+                ui.label(i18n::PDB_PATH);
+                ui.label(&code_view.pdb_path);
+                ui.end_row();
+                ui.label(i18n::SYMBOL_SERVER_KEY);
+                ui.label(code_view.get_symbol_server_key());
+                ui.end_row();
+            });
+    }
+
+    fn show_pogo(ui: &mut Ui, id: &str, pogo: &PogoInfo, width: f32) {
+        ui.label(format!("{}: {}", i18n::CODEVIEW_SIGNATURE, pogo.signature));
+        let col_width = width / POGO_COLUMNS as f32;
+        eframe::egui::Grid::new(id)
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(POGO_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong("RVA");
+                ui.strong(i18n::SIZE);
+                ui.strong(i18n::SECTION_NAME);
+                ui.end_row();
+                for pogo_entry in &pogo.entries {
+                    ui.label(format!("0x{:08X}", pogo_entry.rva));
+                    ui.label(format!("0x{:X}", pogo_entry.size));
+                    ui.label(&pogo_entry.name);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_vc_feature(ui: &mut Ui, id: &str, vc_feature: &VcFeatureInfo, width: f32) {
+        let col_width = width / DETAIL_COLUMNS as f32;
+        eframe::egui::Grid::new(id)
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                for (name, value) in [
+                    ("Pre-VC++ 11.00", vc_feature.pre_vc11),
+                    ("C/C++", vc_feature.c_cpp),
+                    ("/GS", vc_feature.gs),
+                    ("/sdl", vc_feature.sdl),
+                    ("guardN", vc_feature.guard_n),
+                ] {
+                    ui.label(name);
+                    ui.label(format!("{}", value));
+                    ui.end_row();
+                }
+            });
+    }
+}
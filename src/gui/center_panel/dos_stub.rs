@@ -1,16 +1,23 @@
 use crate::gui::FileManager;
 use crate::i18n;
-use eframe::egui::Vec2;
+use crate::tools_api::read_file::RichHeader;
+use eframe::egui::{Color32, RichText, Ui, Vec2};
 
 const MIN_SCROLLED_HEIGHT: f32 = 400.0;
 const CHUNK_SIZE: usize = 16;
 const SPACING: Vec2 = Vec2::new(20.0, 8.0);
 const COLUMNS: usize = 3;
+const DETAIL_COLUMNS: usize = 2;
+const RICH_ENTRY_COLUMNS: usize = 5;
+const ADD_SPACE: f32 = 10.0;
+const CHECKSUM_VALID_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
+const CHECKSUM_INVALID_COLOR: Color32 = Color32::from_rgb(220, 80, 80);
 impl FileManager {
     /// dos_stub windows
     pub(crate) fn dos_stub_panel(&self, ui: &mut eframe::egui::Ui) {
         eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
-            let stub = &self.files.get(self.current_index).unwrap().dos_stub.buffer;
+            let file = self.files.get(self.current_index).unwrap();
+            let stub = &file.dos_stub.buffer;
             let width = ui.available_width();
             let col_width = width / COLUMNS as f32;
             Self::show_main_title(ui, "DOS Stub");
@@ -23,6 +30,10 @@ impl FileManager {
             eframe::egui::ScrollArea::vertical()
                 .min_scrolled_height(MIN_SCROLLED_HEIGHT)
                 .show(ui, |ui| {
+                    if let Some(rich_header) = &file.rich_header {
+                        Self::show_rich_header(ui, rich_header, width);
+                        ui.add_space(ADD_SPACE);
+                    }
                     // 使用表格样式显示十六进制数据
                     eframe::egui::Grid::new("dos_stub_grid")
                         .min_col_width(col_width)
@@ -59,4 +70,59 @@ impl FileManager {
                 });
         });
     }
+
+    fn show_rich_header(ui: &mut Ui, rich_header: &RichHeader, width: f32) {
+        Self::show_sub_title(ui, i18n::RICH_HEADER);
+        eframe::egui::Grid::new("rich_header_grid")
+            .min_col_width(width / DETAIL_COLUMNS as f32)
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .show(ui, |ui| {
+                ui.label(i18n::FILE_OFFSET);
+                ui.label(format!("0x{:08X}", rich_header.file_offset));
+                ui.end_row();
+                ui.label(i18n::SIZE);
+                ui.label(format!("0x{:X}", rich_header.size));
+                ui.end_row();
+                ui.label(i18n::RICH_XOR_KEY);
+                ui.label(format!("0x{:08X}", rich_header.xor_key));
+                ui.end_row();
+                ui.label(i18n::CHECKSUM);
+                let (text, color) = if rich_header.checksum_valid {
+                    (i18n::RICH_CHECKSUM_VALID, CHECKSUM_VALID_COLOR)
+                } else {
+                    (i18n::RICH_CHECKSUM_INVALID, CHECKSUM_INVALID_COLOR)
+                };
+                ui.label(
+                    RichText::new(format!("0x{:08X} {}", rich_header.checksum, text)).color(color),
+                );
+                ui.end_row();
+                ui.label(i18n::RICH_HEADER_HASH);
+                ui.label(&rich_header.hash);
+                ui.end_row();
+            });
+        ui.add_space(ADD_SPACE);
+        eframe::egui::Grid::new("rich_entry_grid")
+            .min_col_width(width / RICH_ENTRY_COLUMNS as f32)
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(RICH_ENTRY_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong(i18n::PRODUCT_ID);
+                ui.strong(i18n::BUILD_NUMBER);
+                ui.strong(i18n::USE_COUNT);
+                ui.strong(i18n::PRODUCT_NAME);
+                ui.strong(i18n::VS_VERSION);
+                ui.end_row();
+                for entry in &rich_header.entries {
+                    ui.label(format!("0x{:04X}", entry.product_id));
+                    ui.label(format!("{}", entry.build));
+                    ui.label(format!("{}", entry.count));
+                    ui.label(&entry.product_name);
+                    ui.label(&entry.vs_version);
+                    ui.end_row();
+                }
+            });
+    }
 }
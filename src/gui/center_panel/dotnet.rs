@@ -0,0 +1,396 @@
+use crate::tools_api::read_file::DotNetInfo;
+use crate::{gui::FileManager, i18n, tools_api::search};
+use eframe::egui::{Label, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const DETAIL_COLUMNS: usize = 2;
+const STREAM_COLUMNS: usize = 3;
+const ADD_SPACE: f32 = 10.0;
+const TABLE_PAGES: [&str; 8] = [
+    "Module",
+    "TypeRef",
+    "TypeDef",
+    "MethodDef",
+    "MemberRef",
+    "AssemblyRef",
+    "ManifestResource",
+    "#US",
+];
+
+impl FileManager {
+    pub(crate) fn dotnet_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let dotnet = match self.dotnet()? {
+            Some(dotnet) => dotnet,
+            None => {
+                ui.add(Label::new(i18n::NO_DOTNET_DIRECTORY));
+                return Ok(());
+            }
+        };
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, ".NET Directory");
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_max_width(ui.available_width() / 3.0);
+                    ScrollArea::vertical()
+                        .id_salt("dotnet_header")
+                        .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                        .show(ui, |ui| {
+                            Self::show_cli_header(ui, &dotnet);
+                            ui.add_space(ADD_SPACE);
+                            Self::show_metadata_streams(ui, &dotnet);
+                        });
+                });
+                ui.separator();
+                ui.vertical(|ui| {
+                    self.show_metadata_table(ui, &dotnet);
+                });
+            });
+        });
+        Ok(())
+    }
+
+    /// 获取.NET元数据，非托管文件返回None
+    pub(crate) fn dotnet(&mut self) -> anyhow::Result<Option<DotNetInfo>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.dotnet.is_none() {
+            file.dotnet = file.get_dotnet()?;
+        }
+        Ok(file.dotnet.clone())
+    }
+
+    fn show_cli_header(ui: &mut Ui, dotnet: &DotNetInfo) {
+        let header = &dotnet.cli_header;
+        Self::show_sub_title(ui, i18n::CLI_HEADER);
+        eframe::egui::Grid::new("cli_header_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .show(ui, |ui| {
+                let fields = [
+                    ("cb", format!("0x{:X}", header.cb)),
+                    (
+                        "RuntimeVersion",
+                        format!(
+                            "{}.{}",
+                            header.major_runtime_version, header.minor_runtime_version
+                        ),
+                    ),
+                    (
+                        "MetaData",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.metadata_rva, header.metadata_size
+                        ),
+                    ),
+                    (
+                        "Flags",
+                        format!("0x{:08X} {}", header.flags, header.get_flag_names()),
+                    ),
+                    (
+                        "EntryPointToken",
+                        format!("0x{:08X}", header.entry_point_token),
+                    ),
+                    (
+                        "Resources",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.resources_rva, header.resources_size
+                        ),
+                    ),
+                    (
+                        "StrongNameSignature",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.strong_name_signature_rva, header.strong_name_signature_size
+                        ),
+                    ),
+                    (
+                        "CodeManagerTable",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.code_manager_table_rva, header.code_manager_table_size
+                        ),
+                    ),
+                    (
+                        "VTableFixups",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.vtable_fixups_rva, header.vtable_fixups_size
+                        ),
+                    ),
+                    (
+                        "ExportAddressTableJumps",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.export_address_table_jumps_rva,
+                            header.export_address_table_jumps_size
+                        ),
+                    ),
+                    (
+                        "ManagedNativeHeader",
+                        format!(
+                            "0x{:08X} (0x{:X})",
+                            header.managed_native_header_rva, header.managed_native_header_size
+                        ),
+                    ),
+                ];
+                for (name, value) in fields {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::METADATA_ROOT);
+        eframe::egui::Grid::new("metadata_root_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .show(ui, |ui| {
+                let fields = [
+                    (
+                        i18n::FILE_OFFSET,
+                        format!("0x{:08X}", dotnet.metadata_file_offset),
+                    ),
+                    (
+                        "Version",
+                        format!(
+                            "{} ({}.{})",
+                            dotnet.metadata_version,
+                            dotnet.metadata_major_version,
+                            dotnet.metadata_minor_version
+                        ),
+                    ),
+                    (
+                        "TablesVersion",
+                        format!(
+                            "{}.{}",
+                            dotnet.tables_major_version, dotnet.tables_minor_version
+                        ),
+                    ),
+                    ("HeapSizes", format!("0x{:02X}", dotnet.heap_sizes)),
+                    ("Valid", format!("0x{:016X}", dotnet.valid_tables)),
+                    ("Sorted", format!("0x{:016X}", dotnet.sorted_tables)),
+                ];
+                for (name, value) in fields {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_metadata_streams(ui: &mut Ui, dotnet: &DotNetInfo) {
+        Self::show_sub_title(ui, i18n::METADATA_STREAMS);
+        eframe::egui::Grid::new("metadata_stream_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(STREAM_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong(i18n::STREAM_NAME);
+                ui.strong(i18n::OFFSET);
+                ui.strong(i18n::SIZE);
+                ui.end_row();
+                for stream in &dotnet.streams {
+                    ui.label(&stream.name);
+                    ui.label(format!("0x{:08X}", stream.offset));
+                    ui.label(format!("0x{:X}", stream.size));
+                    ui.end_row();
+                }
+            });
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::METADATA_TABLES);
+        eframe::egui::Grid::new("metadata_table_rows_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(STREAM_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong("ID");
+                ui.strong(i18n::TABLE_NAME);
+                ui.strong(i18n::ROW_COUNT);
+                ui.end_row();
+                for table in &dotnet.table_rows {
+                    ui.label(format!("0x{:02X}", table.table));
+                    ui.label(&table.name);
+                    ui.label(format!("{}", table.rows));
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// 选择一张核心表并按搜索字符串过滤显示
+    fn show_metadata_table(&mut self, ui: &mut Ui, dotnet: &DotNetInfo) {
+        let message = &mut self.sub_window_manager.dotnet_message;
+        ui.horizontal_wrapped(|ui| {
+            for (index, name) in TABLE_PAGES.iter().enumerate() {
+                ui.selectable_value(&mut message.selected_table_index, index, *name);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut message.search_string);
+        });
+        let (headers, rows) = Self::get_table_rows(dotnet, message.selected_table_index);
+        ScrollArea::both()
+            .id_salt("dotnet_metadata_table")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| {
+                eframe::egui::Grid::new("dotnet_metadata_table")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(headers.len() + 1)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::SEQUENCE_NUMBER);
+                        for header in &headers {
+                            ui.strong(*header);
+                        }
+                        ui.end_row();
+                        for (index, row) in rows.iter().enumerate() {
+                            if !row.iter().any(|cell| search(cell, &message.search_string)) {
+                                continue;
+                            }
+                            ui.label(format!("{}", index + 1));
+                            for cell in row {
+                                ui.label(cell);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// 将选中的表转换为列名与字符串行
+    fn get_table_rows(
+        dotnet: &DotNetInfo,
+        table_index: usize,
+    ) -> (Vec<&'static str>, Vec<Vec<String>>) {
+        match table_index {
+            0 => (
+                vec!["Generation", "Name", "Mvid"],
+                dotnet
+                    .modules
+                    .iter()
+                    .map(|row| {
+                        vec![
+                            format!("{}", row.generation),
+                            row.name.clone(),
+                            row.mvid.clone(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            1 => (
+                vec!["ResolutionScope", "Namespace", "Name"],
+                dotnet
+                    .type_refs
+                    .iter()
+                    .map(|row| {
+                        vec![
+                            row.resolution_scope.clone(),
+                            row.namespace.clone(),
+                            row.name.clone(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            2 => (
+                vec![
+                    "Flags",
+                    "Namespace",
+                    "Name",
+                    "Extends",
+                    "FieldList",
+                    "MethodList",
+                ],
+                dotnet
+                    .type_defs
+                    .iter()
+                    .map(|row| {
+                        vec![
+                            format!("0x{:08X}", row.flags),
+                            row.namespace.clone(),
+                            row.name.clone(),
+                            row.extends.clone(),
+                            format!("{}", row.field_list),
+                            format!("{}", row.method_list),
+                        ]
+                    })
+                    .collect(),
+            ),
+            3 => (
+                vec![
+                    "RVA",
+                    "ImplFlags",
+                    "Flags",
+                    "Type",
+                    "Name",
+                    "Signature",
+                    "ParamList",
+                ],
+                dotnet
+                    .method_defs
+                    .iter()
+                    .map(|row| {
+                        vec![
+                            format!("0x{:08X}", row.rva),
+                            format!("0x{:04X}", row.impl_flags),
+                            format!("0x{:04X}", row.flags),
+                            row.type_name.clone(),
+                            row.name.clone(),
+                            row.signature.clone(),
+                            format!("{}", row.param_list),
+                        ]
+                    })
+                    .collect(),
+            ),
+            4 => (
+                vec!["Class", "Name", "Signature"],
+                dotnet
+                    .member_refs
+                    .iter()
+                    .map(|row| vec![row.class.clone(), row.name.clone(), row.signature.clone()])
+                    .collect(),
+            ),
+            5 => (
+                vec!["Name", "Version", "Culture", "Flags", "PublicKeyOrToken"],
+                dotnet
+                    .assembly_refs
+                    .iter()
+                    .map(|row| {
+                        vec![
+                            row.name.clone(),
+                            row.version.clone(),
+                            row.culture.clone(),
+                            format!("0x{:08X}", row.flags),
+                            row.public_key_or_token.clone(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            6 => (
+                vec!["Name", "Offset", "Flags", "Implementation"],
+                dotnet
+                    .manifest_resources
+                    .iter()
+                    .map(|row| {
+                        vec![
+                            row.name.clone(),
+                            format!("0x{:08X}", row.offset),
+                            format!("0x{:08X}", row.flags),
+                            row.implementation.clone(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            _ => (
+                vec![i18n::VALUE],
+                dotnet
+                    .user_strings
+                    .iter()
+                    .map(|user_string| vec![user_string.clone()])
+                    .collect(),
+            ),
+        }
+    }
+}
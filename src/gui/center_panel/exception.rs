@@ -0,0 +1,198 @@
+use crate::tools_api::read_file::{ExceptionTable, RuntimeFunction, UnwindInfo};
+use crate::{gui::FileManager, i18n, tools_api::search};
+use eframe::egui::{Label, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const FUNCTION_COLUMNS: usize = 5;
+const DETAIL_COLUMNS: usize = 2;
+const CODE_COLUMNS: usize = 3;
+const ADD_SPACE: f32 = 10.0;
+
+impl FileManager {
+    pub(crate) fn exception_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let exception = self.exception()?;
+        if exception.0.borrow().is_empty() {
+            ui.add(Label::new(i18n::NO_EXCEPTION_DIRECTORY));
+            return Ok(());
+        }
+        let selected_index = self
+            .sub_window_manager
+            .exception_message
+            .selected_function_index;
+
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Exception Directory");
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(i18n::RUNTIME_FUNCTION_LIST);
+                    self.show_runtime_function_table(ui, &exception.0.borrow());
+                });
+
+                ui.separator();
+                ui.vertical(|ui| {
+                    ui.label(i18n::UNWIND_INFO);
+                    match selected_index.and_then(|index| exception.0.borrow().get(index).cloned())
+                    {
+                        Some(function) => Self::show_unwind_info(ui, &function),
+                        None => {
+                            ui.label(i18n::SELECT_FUNCTION_PROMPT);
+                        }
+                    }
+                });
+            });
+        });
+        Ok(())
+    }
+
+    /// 获取异常目录函数表的引用
+    pub(crate) fn exception(&mut self) -> anyhow::Result<ExceptionTable> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.exception.0.borrow().is_empty() {
+            file.exception = file.get_exception()?;
+        }
+        Ok(file.exception.fclone())
+    }
+
+    fn show_runtime_function_table(&mut self, ui: &mut Ui, functions: &[RuntimeFunction]) {
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut self.sub_window_manager.exception_message.search_string);
+        });
+        ScrollArea::vertical()
+            .id_salt("runtime_function_table")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| {
+                let width = ui.available_width();
+                let col_width = width / (2 * FUNCTION_COLUMNS) as f32;
+                eframe::egui::Grid::new("runtime_function_table")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(FUNCTION_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::FUNCTION_BEGIN_ADDRESS);
+                        ui.strong(i18n::FUNCTION_END_ADDRESS);
+                        ui.strong(i18n::SIZE);
+                        ui.strong(i18n::SECTION_NAME);
+                        ui.strong(i18n::OPERATION);
+                        ui.end_row();
+
+                        for (index, function) in functions.iter().enumerate() {
+                            let begin_address = format!("0x{:08X}", function.begin_address);
+                            if !search(
+                                &begin_address,
+                                &self.sub_window_manager.exception_message.search_string,
+                            ) {
+                                continue;
+                            }
+                            ui.label(begin_address);
+                            ui.label(format!("0x{:08X}", function.end_address));
+                            ui.label(format!(
+                                "0x{:X}",
+                                function.end_address.wrapping_sub(function.begin_address)
+                            ));
+                            ui.label(&function.section_name);
+                            if ui.button(i18n::SELECT_BUTTON).clicked() {
+                                self.sub_window_manager
+                                    .exception_message
+                                    .selected_function_index = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn show_unwind_info(ui: &mut Ui, function: &RuntimeFunction) {
+        let unwind_info = match &function.unwind_info {
+            Some(unwind_info) => unwind_info,
+            None => {
+                ui.label(i18n::NOT_FOUND);
+                return;
+            }
+        };
+        ScrollArea::vertical()
+            .id_salt("unwind_info")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| {
+                let width = ui.available_width();
+                Self::show_unwind_info_fields(ui, function, unwind_info, width);
+
+                ui.add_space(ADD_SPACE);
+                Self::show_sub_title(ui, i18n::UNWIND_CODES);
+                let col_width = width / CODE_COLUMNS as f32;
+                eframe::egui::Grid::new("unwind_code_table")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(CODE_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::CODE_OFFSET);
+                        ui.strong(i18n::UNWIND_OP);
+                        ui.strong(i18n::OPERAND);
+                        ui.end_row();
+                        for code in &unwind_info.unwind_codes {
+                            ui.label(format!("0x{:02X}", code.code_offset));
+                            ui.label(code.get_op_name());
+                            ui.label(code.get_operand_description());
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn show_unwind_info_fields(
+        ui: &mut Ui,
+        function: &RuntimeFunction,
+        unwind_info: &UnwindInfo,
+        width: f32,
+    ) {
+        let col_width = width / DETAIL_COLUMNS as f32;
+        eframe::egui::Grid::new("unwind_info_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.label("UnwindInfoAddress");
+                ui.label(format!("0x{:08X}", function.unwind_info_address));
+                ui.end_row();
+                ui.label("Version");
+                ui.label(format!("{}", unwind_info.version));
+                ui.end_row();
+                ui.label("Flags");
+                ui.label(format!(
+                    "0x{:X} {}",
+                    unwind_info.flags,
+                    unwind_info.get_flag_names().join(" | ")
+                ));
+                ui.end_row();
+                ui.label("SizeOfProlog");
+                ui.label(format!("0x{:X}", unwind_info.size_of_prolog));
+                ui.end_row();
+                ui.label("CountOfCodes");
+                ui.label(format!("{}", unwind_info.count_of_codes));
+                ui.end_row();
+                ui.label("FrameRegister");
+                ui.label(unwind_info.get_frame_register_name());
+                ui.end_row();
+                ui.label("FrameOffset");
+                ui.label(format!("0x{:X}", unwind_info.frame_offset as u32 * 16));
+                ui.end_row();
+                if let Some(handler_rva) = unwind_info.handler_rva {
+                    ui.label(i18n::EXCEPTION_HANDLER);
+                    ui.label(format!("0x{:08X}", handler_rva));
+                    ui.end_row();
+                }
+                if let Some(chained) = &unwind_info.chained_function {
+                    ui.label(i18n::CHAINED_FUNCTION);
+                    ui.label(format!(
+                        "0x{:08X} - 0x{:08X} (0x{:08X})",
+                        chained.begin_address, chained.end_address, chained.unwind_info_address
+                    ));
+                    ui.end_row();
+                }
+            });
+    }
+}
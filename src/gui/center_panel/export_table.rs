@@ -1,5 +1,5 @@
 use crate::i18n;
-use eframe::egui::{Ui, Vec2};
+use eframe::egui::{Color32, RichText, Ui, Vec2};
 
 use crate::{
     gui::FileManager,
@@ -7,12 +7,15 @@ use crate::{
 };
 
 const MIN_SCROLLED_HEIGHT: f32 = 400.0;
-const DESIGN_SIZE_FUNC_NAME: Vec2 = Vec2::new(400.0 * 0.5, 0.0);
-const DESIGN_SIZE_FUNC_ADDR: Vec2 = Vec2::new(400.0 * 0.25, 0.0);
-const DESIGN_SIZE_FUNC_OPERATE: Vec2 = Vec2::new(400.0 * 0.25, 0.0);
+const DESIGN_SIZE_FUNC_ORDINAL: Vec2 = Vec2::new(400.0 * 0.1, 0.0);
+const DESIGN_SIZE_FUNC_NAME: Vec2 = Vec2::new(400.0 * 0.4, 0.0);
+const DESIGN_SIZE_FUNC_ADDR: Vec2 = Vec2::new(400.0 * 0.2, 0.0);
+const DESIGN_SIZE_FUNC_SECTION: Vec2 = Vec2::new(400.0 * 0.1, 0.0);
+const DESIGN_SIZE_FUNC_OPERATE: Vec2 = Vec2::new(400.0 * 0.2, 0.0);
 const SPACING: Vec2 = Vec2::new(20.0, 8.0);
-const COLUMNS: usize = 3;
+const COLUMNS: usize = 5;
 const MAX_FUNC_NAME_LENGTH: usize = 50;
+const FORWARDER_COLOR: Color32 = Color32::from_rgb(234, 198, 118);
 impl FileManager {
     pub(crate) fn export_panel(&mut self, ui: &mut Ui) {
         // 预先获取数据，避免在渲染循环中重复调用
@@ -50,38 +53,78 @@ impl FileManager {
                                 },
                             );
                             ui.end_row();
+                            ui.allocate_ui(DESIGN_SIZE_FUNC_ORDINAL, |ui| {
+                                ui.strong(i18n::EXPORT_ORDINAL);
+                            });
                             ui.allocate_ui(DESIGN_SIZE_FUNC_NAME, |ui| {
                                 ui.strong(i18n::EXPORT_FUNCTION_NAME);
                             });
                             ui.allocate_ui(DESIGN_SIZE_FUNC_ADDR, |ui| {
                                 ui.strong(i18n::EXPORT_FUNCTION_VIRTUAL_ADDRESS);
                             });
+                            ui.allocate_ui(DESIGN_SIZE_FUNC_SECTION, |ui| {
+                                ui.strong(i18n::EXPORT_SECTION);
+                            });
                             ui.allocate_ui(DESIGN_SIZE_FUNC_OPERATE, |ui| {
                                 ui.strong(i18n::EXPORT_OPERATION);
                             });
                             ui.end_row();
 
                             for (index, item) in export_data_clone.0.borrow().iter().enumerate() {
-                                if !search(
-                                    &item.name,
-                                    &self.sub_window_manager.export_message.search_string,
-                                ) {
+                                let search_string =
+                                    &self.sub_window_manager.export_message.search_string;
+                                if !search(&item.name, search_string)
+                                    && !search(&item.ordinal.to_string(), search_string)
+                                    && !item
+                                        .forwarder
+                                        .as_ref()
+                                        .is_some_and(|forwarder| search(forwarder, search_string))
+                                {
                                     continue;
                                 }
+                                ui.allocate_ui(DESIGN_SIZE_FUNC_ORDINAL, |ui| {
+                                    ui.label(format!("{}", item.ordinal));
+                                });
                                 ui.allocate_ui(DESIGN_SIZE_FUNC_NAME, |ui| {
-                                    let display_name = if item.name.len() > MAX_FUNC_NAME_LENGTH {
-                                        format!("{}...", &item.name[..MAX_FUNC_NAME_LENGTH - 3])
+                                    // 空位与只按序号导出的函数没有名称
+                                    if item.is_empty_slot() {
+                                        ui.label(
+                                            RichText::new(i18n::EXPORT_EMPTY_SLOT)
+                                                .color(Color32::GRAY),
+                                        );
+                                    } else if !item.is_named() {
+                                        ui.label(
+                                            RichText::new(i18n::EXPORT_ORDINAL_ONLY)
+                                                .color(Color32::GRAY),
+                                        );
                                     } else {
-                                        item.name.clone()
-                                    };
-                                    ui.label(display_name);
+                                        let display_name = if item.name.len() > MAX_FUNC_NAME_LENGTH
+                                        {
+                                            format!("{}...", &item.name[..MAX_FUNC_NAME_LENGTH - 3])
+                                        } else {
+                                            item.name.clone()
+                                        };
+                                        ui.label(display_name);
+                                    }
                                 });
 
-                                ui.allocate_ui(DESIGN_SIZE_FUNC_ADDR, |ui| {
-                                    let addr_display = format!("0x{:X}", item.function);
-                                    ui.label(addr_display);
+                                ui.allocate_ui(DESIGN_SIZE_FUNC_ADDR, |ui| match &item.forwarder {
+                                    Some(forwarder) => {
+                                        ui.label(
+                                            RichText::new(format!("-> {}", forwarder))
+                                                .color(FORWARDER_COLOR),
+                                        )
+                                        .on_hover_text(i18n::EXPORT_FORWARDER);
+                                    }
+                                    None => {
+                                        let addr_display = format!("0x{:X}", item.function);
+                                        ui.label(addr_display);
+                                    }
                                 });
 
+                                ui.allocate_ui(DESIGN_SIZE_FUNC_SECTION, |ui| {
+                                    ui.label(&item.section);
+                                });
                                 ui.allocate_ui(DESIGN_SIZE_FUNC_OPERATE, |ui| {
                                     ui.horizontal(|ui| {
                                         if ui.button(i18n::EXPORT_DETAIL_BUTTON).clicked() {
@@ -103,8 +146,13 @@ impl FileManager {
                 eframe::egui::TopBottomPanel::bottom("export_detail_window").show(ui.ctx(), |ui| {
                     ui.label(i18n::EXPORT_FUNCTION_DETAILS);
                     ui.horizontal(|ui| {
-                        ui.label(i18n::FUNCTION_NAME);
-                        ui.text_edit_singleline(&mut export_table_ref[selected_index].name);
+                        ui.label(i18n::EXPORT_ORDINAL);
+                        ui.label(format!("{}", export_table_ref[selected_index].ordinal));
+                        // 没有名称字符串的函数不能修改名称
+                        if export_table_ref[selected_index].is_named() {
+                            ui.label(i18n::FUNCTION_NAME);
+                            ui.text_edit_singleline(&mut export_table_ref[selected_index].name);
+                        }
                         ui.label(i18n::TARGET_VIRTUAL_ADDRESS);
 
                         // 将 u32 地址转换为字符串进行编辑
@@ -122,6 +170,10 @@ impl FileManager {
                                     .show_error(i18n::INVALID_HEX_ADDRESS_FORMAT);
                             }
                         }
+                        if let Some(forwarder) = &export_table_ref[selected_index].forwarder {
+                            ui.label(i18n::EXPORT_FORWARDER);
+                            ui.label(RichText::new(forwarder).color(FORWARDER_COLOR));
+                        }
                         if ui.button("X").clicked() {
                             self.sub_window_manager.export_message.selected_export_index = None;
                         }
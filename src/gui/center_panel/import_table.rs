@@ -1,5 +1,5 @@
 use crate::tools_api::file_system::{self, get_dll_folder};
-use crate::tools_api::read_file::ImportDll;
+use crate::tools_api::read_file::{DelayImportInfo, ImportDll};
 
 use crate::DANGEROUS_FUNCTION_TOML_PATH;
 use crate::tools_api::read_file::ImportTable;
@@ -11,6 +11,7 @@ const MIN_SCROLLED_HEIGHT: f32 = 400.0;
 const SPACING: Vec2 = Vec2::new(20.0, 8.0);
 const COLUMNS: usize = 3;
 const MAX_DLL_NAME_LENGTH: usize = 20;
+const STALE_BINDING_COLOR: Color32 = Color32::from_rgb(234, 198, 118);
 
 impl FileManager {
     fn truncate_text(text: &str, max_length: usize) -> String {
@@ -48,6 +49,11 @@ impl FileManager {
                     ui.label(i18n::FUNCTION_LIST);
                     if let Some(selected_index) = selected_index {
                         if let Some(selected_dll) = imports_clone.0.borrow().get(selected_index) {
+                            if let Some(delay_load) = &selected_dll.delay_load {
+                                Self::show_delay_load_info(ui, delay_load);
+                            } else if selected_dll.time_date_stamp != 0 {
+                                Self::show_bound_import_info(ui, selected_dll);
+                            }
                             self.show_function_table(ui, selected_dll);
                         } else {
                             ui.label(i18n::SELECT_DLL_PROMPT);
@@ -67,10 +73,14 @@ impl FileManager {
                     let mut import_dll = self.files[self.current_index].import_dll.0.borrow_mut();
                     ui.horizontal(|ui| {
                         ui.label(i18n::FUNCTION_NAME);
-                        ui.text_edit_singleline(
-                            &mut import_dll[selected_index].function_info[selected_function_index]
-                                .name,
-                        );
+                        let function =
+                            &mut import_dll[selected_index].function_info[selected_function_index];
+                        // 按序号导入的函数没有名称字符串，不能修改
+                        if function.ordinal.is_some() {
+                            ui.label(function.get_display_name());
+                        } else {
+                            ui.text_edit_singleline(&mut function.name);
+                        }
                         if ui.button("X").clicked() {
                             self.sub_window_manager
                                 .import_message
@@ -114,7 +124,29 @@ impl FileManager {
                         for (index, dll) in imports.iter().enumerate() {
                             let truncated_dll_name =
                                 Self::truncate_text(&dll.name, MAX_DLL_NAME_LENGTH);
-                            ui.label(&truncated_dll_name);
+                            if dll.delay_load.is_some() {
+                                ui.label(format!(
+                                    "{} [{}]",
+                                    truncated_dll_name,
+                                    i18n::DELAY_LOADED
+                                ))
+                                .on_hover_text(&dll.name);
+                            } else if dll.stale_binding {
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{} [{}]",
+                                        truncated_dll_name,
+                                        i18n::STALE_BINDING
+                                    ))
+                                    .color(STALE_BINDING_COLOR),
+                                )
+                                .on_hover_text(&dll.name);
+                            } else if dll.time_date_stamp != 0 {
+                                ui.label(format!("{} [{}]", truncated_dll_name, i18n::BOUND))
+                                    .on_hover_text(&dll.name);
+                            } else {
+                                ui.label(&truncated_dll_name);
+                            }
 
                             ui.label(format!("{}", dll.function_info.len()));
 
@@ -143,6 +175,74 @@ impl FileManager {
             });
     }
 
+    /// 延迟加载描述符信息
+    fn show_delay_load_info(ui: &mut Ui, delay_load: &DelayImportInfo) {
+        eframe::egui::Grid::new("delay_load_info")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Attributes");
+                let format = if delay_load.is_rva_based { "RVA" } else { "VA" };
+                ui.label(format!("0x{:X} ({})", delay_load.attributes, format));
+                ui.end_row();
+                for (name, rva) in [
+                    ("ModuleHandleRVA", delay_load.module_handle_rva),
+                    ("ImportAddressTableRVA", delay_load.import_address_table_rva),
+                    ("ImportNameTableRVA", delay_load.import_name_table_rva),
+                    (
+                        "BoundImportAddressTableRVA",
+                        delay_load.bound_import_address_table_rva,
+                    ),
+                    (
+                        "UnloadInformationTableRVA",
+                        delay_load.unload_information_table_rva,
+                    ),
+                    ("TimeDateStamp", delay_load.time_date_stamp),
+                ] {
+                    ui.label(name);
+                    ui.label(format!("0x{:08X}", rva));
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+    }
+
+    /// 绑定导入信息，包括转发引用的DLL
+    fn show_bound_import_info(ui: &mut Ui, dll: &ImportDll) {
+        eframe::egui::Grid::new("bound_import_info")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("TimeDateStamp");
+                ui.label(format!("0x{:08X}", dll.time_date_stamp));
+                ui.end_row();
+                ui.label("ForwarderChain");
+                ui.label(format!("0x{:08X}", dll.forwarder_chain));
+                ui.end_row();
+                if let Some(bound) = &dll.bound_import {
+                    ui.label(i18n::BOUND_TIMESTAMP);
+                    ui.label(format!("0x{:08X}", bound.time_date_stamp));
+                    ui.end_row();
+                    for forwarder in &bound.forwarder_refs {
+                        ui.label(i18n::FORWARDER_REF);
+                        ui.label(format!(
+                            "{} (0x{:08X})",
+                            forwarder.module_name, forwarder.time_date_stamp
+                        ));
+                        ui.end_row();
+                    }
+                }
+                if dll.stale_binding {
+                    ui.label(RichText::new(i18n::STALE_BINDING).color(STALE_BINDING_COLOR));
+                    ui.label(i18n::STALE_BINDING_HINT);
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+    }
+
     fn show_function_table(&mut self, ui: &mut Ui, dll: &ImportDll) {
         ScrollArea::vertical()
             .id_salt("function_table")
@@ -201,8 +301,9 @@ impl FileManager {
                                 .b,
                         );
                         for (index, function) in dll.function_info.iter().enumerate() {
+                            let display_name = function.get_display_name();
                             if !search(
-                                &function.name,
+                                &display_name,
                                 &self.sub_window_manager.import_message.search_string,
                             ) {
                                 continue;
@@ -221,7 +322,7 @@ impl FileManager {
                             } else {
                                 Color32::GRAY
                             };
-                            let truncated_function_name = Self::truncate_text(&function.name, 40);
+                            let truncated_function_name = Self::truncate_text(&display_name, 40);
                             ui.label(RichText::new(&truncated_function_name).color(name_color));
 
                             ui.horizontal(|ui| {
@@ -0,0 +1,376 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::read_file::{ChpeCodeType, ChpeMetadata, GuardTableEntry, LoadConfigInfo};
+use eframe::egui::{Color32, Label, RichText, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const COLUMNS: usize = 3;
+const TABLE_COLUMNS: usize = 3;
+const ADD_SPACE: f32 = 10.0;
+const CHPE_FIELD_COLUMNS: usize = 2;
+const CHPE_CODE_RANGE_COLUMNS: usize = 5;
+const ARM64_CODE_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
+const ARM64EC_CODE_COLOR: Color32 = Color32::from_rgb(234, 198, 118);
+
+impl FileManager {
+    pub(crate) fn load_config_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let load_config = match self.load_config()? {
+            Some(load_config) => load_config,
+            None => {
+                ui.add(Label::new(i18n::NO_LOAD_CONFIG));
+                return Ok(());
+            }
+        };
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Load Config Directory");
+            eframe::egui::ScrollArea::vertical()
+                .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                .show(ui, |ui| {
+                    let width = ui.available_width();
+                    Self::show_load_config_fields(ui, &load_config, width);
+
+                    ui.add_space(ADD_SPACE);
+                    Self::show_sub_title(ui, i18n::GUARD_FLAGS);
+                    let flag_names = load_config.get_guard_flag_names();
+                    if flag_names.is_empty() {
+                        ui.label(i18n::NO_GUARD_FLAGS);
+                    } else {
+                        ui.label(flag_names.join(" | "));
+                    }
+
+                    ui.add_space(ADD_SPACE);
+                    Self::show_sub_title(ui, i18n::SE_HANDLER_TABLE);
+                    if load_config.se_handlers.is_empty() {
+                        ui.label(i18n::EMPTY_TABLE);
+                    } else {
+                        let col_width = width / TABLE_COLUMNS as f32;
+                        eframe::egui::Grid::new("se_handler_grid")
+                            .striped(true)
+                            .spacing(SPACING)
+                            .num_columns(2)
+                            .min_col_width(col_width)
+                            .show(ui, |ui| {
+                                ui.strong(i18n::SEQUENCE_NUMBER);
+                                ui.strong("RVA");
+                                ui.end_row();
+                                for (index, rva) in load_config.se_handlers.iter().enumerate() {
+                                    ui.label(format!("{}", index + 1));
+                                    ui.label(format!("0x{:08X}", rva));
+                                    ui.end_row();
+                                }
+                            });
+                    }
+
+                    Self::show_guard_table(
+                        ui,
+                        "guard_cf_function_grid",
+                        i18n::GUARD_CF_FUNCTION_TABLE,
+                        &load_config.guard_cf_functions,
+                        width,
+                    );
+                    Self::show_guard_table(
+                        ui,
+                        "guard_iat_grid",
+                        i18n::GUARD_IAT_TABLE,
+                        &load_config.guard_address_taken_iat_entries,
+                        width,
+                    );
+                    Self::show_guard_table(
+                        ui,
+                        "guard_long_jump_grid",
+                        i18n::GUARD_LONG_JUMP_TABLE,
+                        &load_config.guard_long_jump_targets,
+                        width,
+                    );
+                    Self::show_guard_table(
+                        ui,
+                        "guard_eh_continuation_grid",
+                        i18n::GUARD_EH_CONTINUATION_TABLE,
+                        &load_config.guard_eh_continuations,
+                        width,
+                    );
+                    if let Some(chpe_metadata) = &load_config.chpe_metadata {
+                        Self::show_chpe_metadata(ui, chpe_metadata, width);
+                    }
+                });
+        });
+        Ok(())
+    }
+
+    /// 获取加载配置信息
+    pub(crate) fn load_config(&mut self) -> anyhow::Result<Option<LoadConfigInfo>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.load_config.is_none() {
+            file.load_config = file.get_load_config()?;
+        }
+        Ok(file.load_config.clone())
+    }
+
+    fn show_load_config_fields(ui: &mut Ui, load_config: &LoadConfigInfo, width: f32) {
+        let col_width = width / COLUMNS as f32;
+        let fields: [(&str, String, &str); 18] = [
+            (
+                "Size",
+                format!("0x{:X}", load_config.size),
+                i18n::LOAD_CONFIG_SIZE,
+            ),
+            (
+                "TimeDateStamp",
+                format!("0x{:08X}", load_config.time_date_stamp),
+                i18n::TIMESTAMP,
+            ),
+            (
+                "Version",
+                format!(
+                    "{}.{}",
+                    load_config.major_version, load_config.minor_version
+                ),
+                i18n::LOAD_CONFIG_VERSION,
+            ),
+            (
+                "GlobalFlagsClear",
+                format!("0x{:08X}", load_config.global_flags_clear),
+                i18n::GLOBAL_FLAGS_CLEAR,
+            ),
+            (
+                "GlobalFlagsSet",
+                format!("0x{:08X}", load_config.global_flags_set),
+                i18n::GLOBAL_FLAGS_SET,
+            ),
+            (
+                "DependentLoadFlags",
+                format!("0x{:04X}", load_config.dependent_load_flags),
+                i18n::DEPENDENT_LOAD_FLAGS,
+            ),
+            (
+                "SecurityCookie",
+                format!("0x{:X}", load_config.security_cookie),
+                i18n::SECURITY_COOKIE,
+            ),
+            (
+                "SEHandlerTable",
+                format!("0x{:X}", load_config.se_handler_table),
+                i18n::SE_HANDLER_TABLE,
+            ),
+            (
+                "SEHandlerCount",
+                format!("{}", load_config.se_handler_count),
+                i18n::SE_HANDLER_COUNT,
+            ),
+            (
+                "GuardCFCheckFunctionPointer",
+                format!("0x{:X}", load_config.guard_cf_check_function_pointer),
+                i18n::GUARD_CF_CHECK_FUNCTION_POINTER,
+            ),
+            (
+                "GuardCFDispatchFunctionPointer",
+                format!("0x{:X}", load_config.guard_cf_dispatch_function_pointer),
+                i18n::GUARD_CF_DISPATCH_FUNCTION_POINTER,
+            ),
+            (
+                "GuardCFFunctionTable",
+                format!("0x{:X}", load_config.guard_cf_function_table),
+                i18n::GUARD_CF_FUNCTION_TABLE,
+            ),
+            (
+                "GuardCFFunctionCount",
+                format!("{}", load_config.guard_cf_function_count),
+                i18n::GUARD_CF_FUNCTION_COUNT,
+            ),
+            (
+                "GuardFlags",
+                format!("0x{:08X}", load_config.guard_flags),
+                i18n::GUARD_FLAGS,
+            ),
+            (
+                "GuardAddressTakenIatEntryTable",
+                format!("0x{:X}", load_config.guard_address_taken_iat_entry_table),
+                i18n::GUARD_IAT_TABLE,
+            ),
+            (
+                "GuardLongJumpTargetTable",
+                format!("0x{:X}", load_config.guard_long_jump_target_table),
+                i18n::GUARD_LONG_JUMP_TABLE,
+            ),
+            (
+                "DynamicValueRelocTable",
+                format!("0x{:X}", load_config.dynamic_value_reloc_table),
+                i18n::DYNAMIC_VALUE_RELOC_TABLE,
+            ),
+            (
+                "DynamicValueRelocTableOffset",
+                format!(
+                    "0x{:X} ({})",
+                    load_config.dynamic_value_reloc_table_offset,
+                    load_config.dynamic_value_reloc_table_section
+                ),
+                i18n::DYNAMIC_VALUE_RELOC_TABLE_OFFSET,
+            ),
+        ];
+        eframe::egui::Grid::new("load_config_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong(i18n::FIELD_NAME);
+                ui.strong(i18n::VALUE);
+                ui.strong(i18n::DESCRIPTION);
+                ui.end_row();
+                for (name, value, description) in fields {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.label(description);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_guard_table(
+        ui: &mut Ui,
+        id: &str,
+        title: &str,
+        entries: &[GuardTableEntry],
+        width: f32,
+    ) {
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, title);
+        if entries.is_empty() {
+            ui.label(i18n::EMPTY_TABLE);
+            return;
+        }
+        let col_width = width / TABLE_COLUMNS as f32;
+        eframe::egui::Grid::new(id)
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(TABLE_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong(i18n::SEQUENCE_NUMBER);
+                ui.strong("RVA");
+                ui.strong(i18n::GUARD_ENTRY_FLAGS);
+                ui.end_row();
+                for (index, entry) in entries.iter().enumerate() {
+                    ui.label(format!("{}", index + 1));
+                    ui.label(format!("0x{:08X}", entry.rva));
+                    ui.label(format!("0x{:02X}", entry.flags));
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// 混合映像中各段代码的架构，ARM64X 的两种视图共用同一份代码映射
+    fn show_chpe_metadata(ui: &mut Ui, chpe_metadata: &ChpeMetadata, width: f32) {
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::CHPE_METADATA);
+        let col_width = width / CHPE_FIELD_COLUMNS as f32;
+        eframe::egui::Grid::new("chpe_metadata_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(CHPE_FIELD_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong(i18n::FIELD_NAME);
+                ui.strong(i18n::VALUE);
+                ui.end_row();
+                for (name, value) in chpe_metadata
+                    .get_field_names()
+                    .iter()
+                    .zip(&chpe_metadata.fields)
+                {
+                    ui.label(*name);
+                    ui.label(format!("0x{:08X}", value));
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::CHPE_CODE_RANGES);
+        if chpe_metadata.code_ranges.is_empty() {
+            ui.label(i18n::EMPTY_TABLE);
+        } else {
+            let col_width = width / CHPE_CODE_RANGE_COLUMNS as f32;
+            eframe::egui::Grid::new("chpe_code_range_grid")
+                .striped(true)
+                .spacing(SPACING)
+                .num_columns(CHPE_CODE_RANGE_COLUMNS)
+                .min_col_width(col_width)
+                .show(ui, |ui| {
+                    ui.strong(i18n::SEQUENCE_NUMBER);
+                    ui.strong(i18n::START_RVA);
+                    ui.strong(i18n::END_RVA);
+                    ui.strong(i18n::SIZE);
+                    ui.strong(i18n::CODE_TYPE);
+                    ui.end_row();
+                    for (index, range) in chpe_metadata.code_ranges.iter().enumerate() {
+                        ui.label(format!("{}", index + 1));
+                        ui.label(format!("0x{:08X}", range.start_rva));
+                        ui.label(format!(
+                            "0x{:08X}",
+                            range.start_rva.wrapping_add(range.length)
+                        ));
+                        ui.label(format!("0x{:X}", range.length));
+                        let code_type = RichText::new(range.code_type.get_name());
+                        ui.label(match range.code_type {
+                            ChpeCodeType::Arm64 => code_type.color(ARM64_CODE_COLOR),
+                            ChpeCodeType::Arm64Ec => code_type.color(ARM64EC_CODE_COLOR),
+                            _ => code_type,
+                        });
+                        ui.end_row();
+                    }
+                });
+        }
+        if chpe_metadata.is_x86 {
+            return;
+        }
+
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::CHPE_ENTRY_POINTS);
+        if chpe_metadata.entry_points.is_empty() {
+            ui.label(i18n::EMPTY_TABLE);
+        } else {
+            let col_width = width / TABLE_COLUMNS as f32;
+            eframe::egui::Grid::new("chpe_entry_point_grid")
+                .striped(true)
+                .spacing(SPACING)
+                .num_columns(TABLE_COLUMNS)
+                .min_col_width(col_width)
+                .show(ui, |ui| {
+                    ui.strong(i18n::START_RVA);
+                    ui.strong(i18n::END_RVA);
+                    ui.strong(i18n::ENTRY_POINT);
+                    ui.end_row();
+                    for entry_point in &chpe_metadata.entry_points {
+                        ui.label(format!("0x{:08X}", entry_point.start_rva));
+                        ui.label(format!("0x{:08X}", entry_point.end_rva));
+                        ui.label(format!("0x{:08X}", entry_point.entry_point));
+                        ui.end_row();
+                    }
+                });
+        }
+
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::CHPE_REDIRECTIONS);
+        if chpe_metadata.redirections.is_empty() {
+            ui.label(i18n::EMPTY_TABLE);
+        } else {
+            let col_width = width / CHPE_FIELD_COLUMNS as f32;
+            eframe::egui::Grid::new("chpe_redirection_grid")
+                .striped(true)
+                .spacing(SPACING)
+                .num_columns(CHPE_FIELD_COLUMNS)
+                .min_col_width(col_width)
+                .show(ui, |ui| {
+                    ui.strong(i18n::REDIRECTION_SOURCE);
+                    ui.strong(i18n::REDIRECTION_DESTINATION);
+                    ui.end_row();
+                    for redirection in &chpe_metadata.redirections {
+                        ui.label(format!("0x{:08X}", redirection.source));
+                        ui.label(format!("0x{:08X}", redirection.destination));
+                        ui.end_row();
+                    }
+                });
+        }
+    }
+}
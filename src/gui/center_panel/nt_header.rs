@@ -1,11 +1,15 @@
 use crate::gui::FileManager;
 use crate::i18n;
-use eframe::egui::{Ui, Vec2};
+use crate::tools_api::read_file::ManifestInfo;
+use eframe::egui::{Color32, RichText, Ui, Vec2};
+use log::debug;
 
 const SPACING: Vec2 = Vec2::new(20.0, 8.0);
 const COLUMNS: usize = 3;
 const MIN_SCROLLED_HEIGHT: f32 = 400.0;
 const ADD_SPACE: f32 = 10.0;
+const DEPENDENCY_COLUMNS: usize = 5;
+const ELEVATION_COLOR: Color32 = Color32::from_rgb(220, 80, 80);
 const DATA_DIRECTORY_NAME: [&str; 16] = [
     "Export Table",
     "Import Table",
@@ -29,32 +33,43 @@ impl FileManager {
     pub(crate) fn nt_header_panel(&mut self, ui: &mut Ui) {
         let width = ui.available_width();
         let col_width = width / COLUMNS as f32;
+        let is_coff_object = self.get_file().is_coff_object;
         eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
-            Self::show_main_title(ui, "NT Headers");
+            Self::show_main_title(
+                ui,
+                if is_coff_object {
+                    "COFF File Header"
+                } else {
+                    "NT Headers"
+                },
+            );
             eframe::egui::ScrollArea::vertical()
                 .min_scrolled_height(MIN_SCROLLED_HEIGHT)
                 // .auto_shrink([true, false])
                 .show(ui, |ui| {
-                    // 签名部分
-                    Self::show_sub_title(ui, "Signature");
-                    eframe::egui::Grid::new("signature_grid")
-                        .striped(true)
-                        .spacing(SPACING)
-                        .num_columns(COLUMNS)
-                        .min_col_width(col_width)
-                        .show(ui, |ui| {
-                            ui.strong(i18n::FIELD_NAME);
-                            ui.strong(i18n::VALUE);
-                            ui.strong(i18n::DESCRIPTION);
-                            ui.end_row();
+                    // 目标文件没有签名与可选头
+                    if !is_coff_object {
+                        // 签名部分
+                        Self::show_sub_title(ui, "Signature");
+                        eframe::egui::Grid::new("signature_grid")
+                            .striped(true)
+                            .spacing(SPACING)
+                            .num_columns(COLUMNS)
+                            .min_col_width(col_width)
+                            .show(ui, |ui| {
+                                ui.strong(i18n::FIELD_NAME);
+                                ui.strong(i18n::VALUE);
+                                ui.strong(i18n::DESCRIPTION);
+                                ui.end_row();
 
-                            ui.label("Signature");
-                            ui.label(self.get_signature());
-                            ui.label(i18n::FILE_HEADER_SIGNATURE);
-                            ui.end_row();
-                        });
+                                ui.label("Signature");
+                                ui.label(self.get_signature());
+                                ui.label(i18n::FILE_HEADER_SIGNATURE);
+                                ui.end_row();
+                            });
 
-                    ui.add_space(ADD_SPACE);
+                        ui.add_space(ADD_SPACE);
+                    }
 
                     // File Header 部分
                     Self::show_sub_title(ui, "File Header");
@@ -108,6 +123,9 @@ impl FileManager {
                             ui.end_row();
                         });
 
+                    if is_coff_object {
+                        return;
+                    }
                     ui.add_space(ADD_SPACE);
 
                     // Optional Header 部分
@@ -306,9 +324,116 @@ impl FileManager {
                                 ui.end_row();
                             }
                         });
+
+                    ui.add_space(ADD_SPACE);
+
+                    // 应用程序清单部分
+                    Self::show_sub_title(ui, i18n::MANIFEST);
+                    match self.manifest() {
+                        Ok(Some(manifest)) => Self::show_manifest(ui, &manifest),
+                        Ok(None) => {
+                            ui.label(i18n::NO_MANIFEST);
+                        }
+                        Err(e) => {
+                            debug!("manifest: {}", e);
+                            ui.label(i18n::NO_MANIFEST);
+                        }
+                    }
                 });
         });
     }
+
+    /// 获取应用程序清单，不含清单时返回None
+    pub(crate) fn manifest(&mut self) -> anyhow::Result<Option<ManifestInfo>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.manifest.is_none() {
+            file.manifest = file.get_manifest()?;
+        }
+        Ok(file.manifest.clone())
+    }
+
+    fn show_manifest(ui: &mut Ui, manifest: &ManifestInfo) {
+        eframe::egui::Grid::new("manifest_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .min_col_width(ui.ctx().used_size().x / COLUMNS as f32)
+            .num_columns(COLUMNS)
+            .show(ui, |ui| {
+                ui.strong(i18n::FIELD_NAME);
+                ui.strong(i18n::VALUE);
+                ui.strong(i18n::DESCRIPTION);
+                ui.end_row();
+
+                ui.label("Resource");
+                ui.label(&manifest.resource_name);
+                ui.label(i18n::MANIFEST_RESOURCE);
+                ui.end_row();
+
+                ui.label("requestedExecutionLevel");
+                if manifest.requires_elevation() {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} ({})",
+                            manifest.requested_execution_level,
+                            i18n::MANIFEST_REQUIRES_ELEVATION
+                        ))
+                        .color(ELEVATION_COLOR),
+                    );
+                } else {
+                    ui.label(&manifest.requested_execution_level);
+                }
+                ui.label(i18n::MANIFEST_EXECUTION_LEVEL);
+                ui.end_row();
+
+                ui.label("uiAccess");
+                ui.label(&manifest.ui_access);
+                ui.label(i18n::MANIFEST_UI_ACCESS);
+                ui.end_row();
+
+                ui.label("dpiAware");
+                ui.label(&manifest.dpi_aware);
+                ui.label(i18n::MANIFEST_DPI_AWARE);
+                ui.end_row();
+
+                ui.label("dpiAwareness");
+                ui.label(&manifest.dpi_awareness);
+                ui.label(i18n::MANIFEST_DPI_AWARENESS);
+                ui.end_row();
+
+                for os in &manifest.supported_os {
+                    ui.label("supportedOS");
+                    ui.label(&os.id);
+                    ui.label(&os.name);
+                    ui.end_row();
+                }
+            });
+
+        if manifest.dependencies.is_empty() {
+            return;
+        }
+        ui.add_space(ADD_SPACE);
+        Self::show_sub_title(ui, i18n::DEPENDENT_ASSEMBLIES);
+        eframe::egui::Grid::new("manifest_dependency_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DEPENDENCY_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong("Name");
+                ui.strong("Version");
+                ui.strong("ProcessorArchitecture");
+                ui.strong("PublicKeyToken");
+                ui.strong("Language");
+                ui.end_row();
+                for dependency in &manifest.dependencies {
+                    ui.label(&dependency.name);
+                    ui.label(&dependency.version);
+                    ui.label(&dependency.processor_architecture);
+                    ui.label(&dependency.public_key_token);
+                    ui.label(&dependency.language);
+                    ui.end_row();
+                }
+            });
+    }
     pub(crate) fn get_signature(&self) -> &str {
         self.files
             .get(self.current_index)
@@ -640,8 +765,7 @@ impl FileManager {
                 .get(self.current_index)
                 .unwrap()
                 .data_directory
-                .get_data_directory_size(index)
-                .unwrap()
+                .get_data_directory_size(index as usize)
         )
     }
     pub(crate) fn get_data_directory_virtual_address(&self, index: u32) -> String {
@@ -651,8 +775,7 @@ impl FileManager {
                 .get(self.current_index)
                 .unwrap()
                 .data_directory
-                .get_data_directory_virtual_address(index)
-                .unwrap()
+                .get_data_directory_virtual_address(index as usize)
         )
     }
 }
@@ -0,0 +1,143 @@
+use crate::tools_api::read_file::{RelocationBlock, RelocationTable};
+use crate::{gui::FileManager, i18n, tools_api::search};
+use eframe::egui::{Label, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const BLOCK_COLUMNS: usize = 4;
+const ENTRY_COLUMNS: usize = 5;
+
+impl FileManager {
+    pub(crate) fn relocation_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let relocations = self.relocation()?;
+        if relocations.0.borrow().is_empty() {
+            ui.add(Label::new(i18n::NO_RELOCATIONS));
+            return Ok(());
+        }
+        let selected_index = self
+            .sub_window_manager
+            .relocation_message
+            .selected_block_index;
+
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Base Relocation");
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(i18n::RELOCATION_BLOCK_LIST);
+                    self.show_relocation_block_table(ui, &relocations.0.borrow());
+                });
+
+                ui.separator();
+                ui.vertical(|ui| {
+                    ui.label(i18n::RELOCATION_ENTRY_LIST);
+                    match selected_index
+                        .and_then(|index| relocations.0.borrow().get(index).cloned())
+                    {
+                        Some(block) => self.show_relocation_entry_table(ui, &block),
+                        None => {
+                            ui.label(i18n::SELECT_BLOCK_PROMPT);
+                        }
+                    }
+                });
+            });
+        });
+        Ok(())
+    }
+
+    /// 获取重定位表的引用
+    pub(crate) fn relocation(&mut self) -> anyhow::Result<RelocationTable> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.relocation.0.borrow().is_empty() {
+            file.relocation = file.get_relocations()?;
+        }
+        Ok(file.relocation.fclone())
+    }
+
+    fn show_relocation_block_table(&mut self, ui: &mut Ui, blocks: &[RelocationBlock]) {
+        ScrollArea::vertical()
+            .id_salt("relocation_block_table")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| {
+                let width = ui.available_width();
+                let col_width = width / (2 * BLOCK_COLUMNS) as f32;
+                eframe::egui::Grid::new("relocation_block_table")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(BLOCK_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.strong(i18n::PAGE_RVA);
+                        ui.strong(i18n::SECTION_NAME);
+                        ui.strong(i18n::ENTRY_COUNT);
+                        ui.strong(i18n::OPERATION);
+                        ui.end_row();
+
+                        for (index, block) in blocks.iter().enumerate() {
+                            ui.label(format!("0x{:08X}", block.page_rva));
+                            ui.label(&block.section_name);
+                            ui.label(format!("{}", block.entries.len()));
+                            if ui.button(i18n::SELECT_BUTTON).clicked() {
+                                self.sub_window_manager
+                                    .relocation_message
+                                    .selected_block_index = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn show_relocation_entry_table(&mut self, ui: &mut Ui, block: &RelocationBlock) {
+        ScrollArea::vertical()
+            .id_salt("relocation_entry_table")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| {
+                let width = ui.available_width();
+                let col_width = width / ENTRY_COLUMNS as f32;
+                eframe::egui::Grid::new("relocation_entry_table")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(ENTRY_COLUMNS)
+                    .min_col_width(col_width)
+                    .show(ui, |ui| {
+                        ui.label("🔍");
+                        ui.text_edit_singleline(
+                            &mut self.sub_window_manager.relocation_message.search_string,
+                        );
+                        ui.end_row();
+                        ui.strong(i18n::SEQUENCE_NUMBER);
+                        ui.strong(i18n::RELOCATION_TYPE);
+                        ui.strong(i18n::OFFSET);
+                        ui.strong(i18n::TARGET_RVA);
+                        ui.strong(i18n::FILE_OFFSET);
+                        ui.end_row();
+
+                        for (index, entry) in block.entries.iter().enumerate() {
+                            let target_rva = format!("0x{:08X}", entry.target_rva);
+                            if !search(
+                                &target_rva,
+                                &self.sub_window_manager.relocation_message.search_string,
+                            ) {
+                                continue;
+                            }
+                            ui.label(format!("{}", index + 1));
+                            match entry.parameter {
+                                Some(parameter) => ui.label(format!(
+                                    "{} (0x{:04X})",
+                                    entry.get_type_name(),
+                                    parameter
+                                )),
+                                None => ui.label(entry.get_type_name()),
+                            };
+                            ui.label(format!("0x{:03X}", entry.offset));
+                            ui.label(target_rva);
+                            match entry.file_offset {
+                                Some(fo) => ui.label(format!("0x{:08X}", fo)),
+                                None => ui.label(i18n::NOT_FOUND),
+                            };
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
@@ -0,0 +1,292 @@
+use crate::gui::ResourceMessage;
+use crate::tools_api::read_file::{
+    AcceleratorTable, DialogResource, MenuItem, MenuResource, ResourceStringTable, ResourceStrings,
+    UiResources,
+};
+use crate::{gui::FileManager, i18n, tools_api::search};
+use eframe::egui::{CollapsingHeader, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const STRING_COLUMNS: usize = 2;
+const DETAIL_COLUMNS: usize = 2;
+const CONTROL_COLUMNS: usize = 5;
+const ACCELERATOR_COLUMNS: usize = 3;
+const ADD_SPACE: f32 = 10.0;
+const RESOURCE_TABS: [&str; 5] = [
+    i18n::STRING_TABLE,
+    i18n::MESSAGE_TABLE,
+    i18n::DIALOG,
+    i18n::MENU,
+    i18n::ACCELERATOR,
+];
+
+impl FileManager {
+    pub(crate) fn resource_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let resource_strings = self.resource_strings()?.unwrap_or_default();
+        let ui_resources = self.ui_resources()?.unwrap_or_default();
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Resource Directory");
+            let message = &mut self.sub_window_manager.resource_message;
+            ui.horizontal(|ui| {
+                for (index, name) in RESOURCE_TABS.iter().enumerate() {
+                    if ui
+                        .selectable_value(&mut message.selected_tab_index, index, *name)
+                        .clicked()
+                    {
+                        message.selected_language_index = 0;
+                    }
+                }
+            });
+            ui.add_space(ADD_SPACE);
+            match message.selected_tab_index {
+                0 => Self::show_resource_strings(ui, message, &resource_strings.string_tables),
+                1 => Self::show_resource_strings(ui, message, &resource_strings.message_tables),
+                _ => Self::show_ui_resources(ui, message.selected_tab_index, &ui_resources),
+            }
+        });
+        Ok(())
+    }
+
+    /// 获取字符串表与消息表，两者都不存在时返回None
+    pub(crate) fn resource_strings(&mut self) -> anyhow::Result<Option<ResourceStrings>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.resource_strings.is_none() {
+            file.resource_strings = file.get_resource_strings()?;
+        }
+        Ok(file.resource_strings.clone())
+    }
+
+    /// 获取对话框、菜单与快捷键表，都不存在时返回None
+    pub(crate) fn ui_resources(&mut self) -> anyhow::Result<Option<UiResources>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.ui_resources.is_none() {
+            file.ui_resources = file.get_ui_resources()?;
+        }
+        Ok(file.ui_resources.clone())
+    }
+
+    fn show_resource_strings(
+        ui: &mut Ui,
+        message: &mut ResourceMessage,
+        tables: &[ResourceStringTable],
+    ) {
+        if tables.is_empty() {
+            ui.label(i18n::NO_RESOURCE_STRINGS);
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n::RESOURCE_LANGUAGE);
+            for (index, table) in tables.iter().enumerate() {
+                ui.selectable_value(
+                    &mut message.selected_language_index,
+                    index,
+                    format!("0x{:04X}", table.language),
+                );
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut message.search_string);
+        });
+        ui.add_space(ADD_SPACE);
+        if let Some(table) = tables.get(message.selected_language_index) {
+            // 消息ID通常是错误码，按十六进制显示
+            let hex_id = message.selected_tab_index != 0;
+            Self::show_string_table(ui, table, &message.search_string, hex_id);
+        }
+    }
+
+    fn show_string_table(
+        ui: &mut Ui,
+        table: &ResourceStringTable,
+        search_string: &str,
+        hex_id: bool,
+    ) {
+        ScrollArea::vertical()
+            .id_salt("resource_string_table")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| {
+                eframe::egui::Grid::new("resource_string_grid")
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(STRING_COLUMNS)
+                    .show(ui, |ui| {
+                        ui.strong("ID");
+                        ui.strong(i18n::VALUE);
+                        ui.end_row();
+                        for string in &table.strings {
+                            let id = if hex_id {
+                                format!("0x{:08X}", string.id)
+                            } else {
+                                format!("{}", string.id)
+                            };
+                            if !search(&id, search_string) && !search(&string.text, search_string) {
+                                continue;
+                            }
+                            ui.label(id);
+                            ui.label(&string.text);
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn show_ui_resources(ui: &mut Ui, tab_index: usize, ui_resources: &UiResources) {
+        let is_empty = match tab_index {
+            2 => ui_resources.dialogs.is_empty(),
+            3 => ui_resources.menus.is_empty(),
+            _ => ui_resources.accelerators.is_empty(),
+        };
+        if is_empty {
+            ui.label(i18n::NO_UI_RESOURCES);
+            return;
+        }
+        ScrollArea::vertical()
+            .id_salt("ui_resources")
+            .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+            .show(ui, |ui| match tab_index {
+                2 => ui_resources
+                    .dialogs
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, dialog)| Self::show_dialog(ui, index, dialog)),
+                3 => ui_resources
+                    .menus
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, menu)| Self::show_menu(ui, index, menu)),
+                _ => ui_resources
+                    .accelerators
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, table)| Self::show_accelerators(ui, index, table)),
+            });
+    }
+
+    fn show_dialog(ui: &mut Ui, index: usize, dialog: &DialogResource) {
+        let title = format!(
+            "{} [0x{:04X}] {}",
+            dialog.resource_name, dialog.language, dialog.title
+        );
+        CollapsingHeader::new(title)
+            .id_salt(("dialog", index))
+            .show(ui, |ui| {
+                eframe::egui::Grid::new(("dialog_detail_grid", index))
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(DETAIL_COLUMNS)
+                    .show(ui, |ui| {
+                        let fields = [
+                            (
+                                "Template",
+                                if dialog.extended {
+                                    "DLGTEMPLATEEX".to_string()
+                                } else {
+                                    "DLGTEMPLATE".to_string()
+                                },
+                            ),
+                            (
+                                "Style",
+                                format!("0x{:08X} {}", dialog.style, dialog.get_style_names()),
+                            ),
+                            ("ExStyle", format!("0x{:08X}", dialog.ex_style)),
+                            (
+                                "Position",
+                                format!("{}, {}, {}, {}", dialog.x, dialog.y, dialog.cx, dialog.cy),
+                            ),
+                            ("Menu", dialog.menu.clone()),
+                            ("Class", dialog.class.clone()),
+                            ("Font", dialog.font.clone()),
+                        ];
+                        for (name, value) in fields {
+                            ui.label(name);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(ADD_SPACE);
+                eframe::egui::Grid::new(("dialog_control_grid", index))
+                    .striped(true)
+                    .spacing(SPACING)
+                    .num_columns(CONTROL_COLUMNS)
+                    .show(ui, |ui| {
+                        ui.strong("ID");
+                        ui.strong("Class");
+                        ui.strong("Text");
+                        ui.strong("Style");
+                        ui.strong("Position");
+                        ui.end_row();
+                        for control in &dialog.controls {
+                            ui.label(format!("{}", control.id));
+                            ui.label(&control.class);
+                            ui.label(&control.text);
+                            ui.label(format!(
+                                "0x{:08X} {}",
+                                control.style,
+                                control.get_style_names()
+                            ));
+                            ui.label(format!(
+                                "{}, {}, {}, {}",
+                                control.x, control.y, control.cx, control.cy
+                            ));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn show_menu(ui: &mut Ui, index: usize, menu: &MenuResource) {
+        let title = format!(
+            "{} [0x{:04X}] {}",
+            menu.resource_name,
+            menu.language,
+            if menu.extended { "MENUEX" } else { "MENU" }
+        );
+        CollapsingHeader::new(title)
+            .id_salt(("menu", index))
+            .show(ui, |ui| {
+                Self::show_menu_items(ui, &menu.items, menu.extended);
+            });
+    }
+
+    fn show_menu_items(ui: &mut Ui, items: &[MenuItem], extended: bool) {
+        for (index, item) in items.iter().enumerate() {
+            let flags = item.get_flag_names(extended);
+            if item.children.is_empty() {
+                ui.label(format!("{}  (ID {})  {}", item.text, item.id, flags));
+            } else {
+                CollapsingHeader::new(format!("{}  {}", item.text, flags))
+                    .id_salt((ui.id(), index))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        Self::show_menu_items(ui, &item.children, extended);
+                    });
+            }
+        }
+    }
+
+    fn show_accelerators(ui: &mut Ui, index: usize, table: &AcceleratorTable) {
+        Self::show_sub_title(
+            ui,
+            &format!("{} [0x{:04X}]", table.resource_name, table.language),
+        );
+        eframe::egui::Grid::new(("accelerator_grid", index))
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(ACCELERATOR_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong("Key");
+                ui.strong("ID");
+                ui.strong("Flags");
+                ui.end_row();
+                for entry in &table.entries {
+                    ui.label(&entry.key_name);
+                    ui.label(format!("{}", entry.id));
+                    ui.label(format!("0x{:04X}", entry.flags));
+                    ui.end_row();
+                }
+            });
+        ui.add_space(ADD_SPACE);
+    }
+}
@@ -1,10 +1,14 @@
 use crate::gui::{FileManager, SectionFlag};
 use crate::i18n;
 use crate::tools_api::read_file::section_headers::SectionCharacteristics;
+use crate::tools_api::read_file::{CoffSectionDetail, OverlayInfo};
 use eframe::egui::{Label, Ui, Vec2};
 const MIN_SCROLLED_HEIGHT: f32 = 400.0;
 const SPACING: Vec2 = Vec2::new(20.0, 8.0);
 const COLUMNS: usize = 7;
+const OVERLAY_COLUMNS: usize = 4;
+const RELOCATION_COLUMNS: usize = 4;
+const ADD_SPACE: f32 = 10.0;
 impl FileManager {
     pub(crate) fn section_header_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
         let section_num = self.get_section_num()?;
@@ -26,6 +30,9 @@ impl FileManager {
                 )
             })
             .collect();
+        let overlay = self.overlay()?;
+        let coff_sections = self.coff_sections()?;
+        let is_coff_object = self.get_file().is_coff_object;
         eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
             Self::show_main_title(ui, "Section Headers");
             eframe::egui::ScrollArea::vertical()
@@ -93,10 +100,40 @@ impl FileManager {
                                                 .replace("{}", characteristics).to_string();
                                         ui.output_mut(|o| o.copied_text = info);
                                     }
+                                    if coff_sections
+                                        .as_ref()
+                                        .and_then(|details| details.get(index))
+                                        .is_some_and(|detail| {
+                                            !detail.relocations.is_empty()
+                                                || detail.comdat.is_some()
+                                        })
+                                        && ui.button(i18n::SECTION_RELOCATIONS).clicked()
+                                    {
+                                        self.sub_window_manager
+                                            .section_message
+                                            .selected_relocation_index = Some(index);
+                                    }
                                 });
                                 ui.end_row();
                             }
                         });
+                    let selected_relocation_index = self
+                        .sub_window_manager
+                        .section_message
+                        .selected_relocation_index;
+                    if let Some(index) = selected_relocation_index
+                        && let Some(detail) = coff_sections
+                            .as_ref()
+                            .and_then(|details| details.get(index))
+                    {
+                        ui.add_space(ADD_SPACE);
+                        Self::show_coff_section_detail(ui, &section_items[index].0, detail);
+                    }
+                    // 目标文件中节之后是符号表，不显示附加数据
+                    if !is_coff_object {
+                        ui.add_space(ADD_SPACE);
+                        Self::show_overlay(ui, overlay.as_ref());
+                    }
                 });
         });
 
@@ -499,4 +536,94 @@ impl FileManager {
             .section_headers
             .get_section_characteristics(index)
     }
+
+    /// 获取附加数据，不存在时返回None
+    pub(crate) fn overlay(&mut self) -> anyhow::Result<Option<OverlayInfo>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.overlay.is_none() {
+            file.overlay = file.get_overlay()?;
+        }
+        Ok(file.overlay.clone())
+    }
+
+    /// 获取每个节的COFF重定位项与COMDAT信息
+    pub(crate) fn coff_sections(&mut self) -> anyhow::Result<Option<Vec<CoffSectionDetail>>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.coff_sections.is_none() {
+            file.coff_sections = file.get_coff_sections()?;
+        }
+        Ok(file.coff_sections.clone())
+    }
+
+    fn show_coff_section_detail(ui: &mut Ui, section_name: &str, detail: &CoffSectionDetail) {
+        Self::show_sub_title(
+            ui,
+            &format!("{}: {}", i18n::SECTION_RELOCATIONS, section_name),
+        );
+        if let Some(comdat) = &detail.comdat {
+            let mut comdat_info = format!(
+                "COMDAT: {} ({})    {}: {}",
+                comdat.get_selection_name(),
+                comdat.selection,
+                i18n::SYMBOL_NAME,
+                comdat.symbol_name
+            );
+            if let Some(associated_section) = comdat.associated_section {
+                comdat_info +=
+                    &format!("    {}: #{}", i18n::ASSOCIATED_SECTION, associated_section);
+            }
+            ui.label(comdat_info);
+        }
+        eframe::egui::Grid::new("coff_relocation_table")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(RELOCATION_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong(i18n::OFFSET);
+                ui.strong(i18n::RELOCATION_TYPE);
+                ui.strong(i18n::SYMBOL_INDEX);
+                ui.strong(i18n::SYMBOL_NAME);
+                ui.end_row();
+                for relocation in &detail.relocations {
+                    ui.label(format!("0x{:08X}", relocation.virtual_address));
+                    ui.label(&relocation.type_name);
+                    ui.label(format!("{}", relocation.symbol_table_index));
+                    ui.label(&relocation.symbol_name);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_overlay(ui: &mut Ui, overlay: Option<&OverlayInfo>) {
+        Self::show_sub_title(ui, i18n::OVERLAY);
+        let Some(overlay) = overlay else {
+            ui.label(i18n::NO_OVERLAY);
+            return;
+        };
+        ui.label(format!(
+            "{}: 0x{:X}    {}: 0x{:X}",
+            i18n::IMAGE_END,
+            overlay.image_end,
+            i18n::SIZE,
+            overlay.get_total_size()
+        ));
+        eframe::egui::Grid::new("overlay_table")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(OVERLAY_COLUMNS)
+            .show(ui, |ui| {
+                ui.strong(i18n::FILE_OFFSET);
+                ui.strong(i18n::SIZE);
+                ui.strong(i18n::ENTROPY);
+                ui.strong(i18n::FILE_TYPE);
+                ui.end_row();
+                for region in &overlay.regions {
+                    ui.label(format!("0x{:X}", region.offset));
+                    ui.label(format!("0x{:X}", region.size));
+                    ui.label(format!("{:.3}", region.entropy));
+                    ui.label(region.file_type.as_deref().unwrap_or("Unknown"));
+                    ui.end_row();
+                }
+            });
+    }
 }
@@ -0,0 +1,241 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::AuthenticodeHash;
+use crate::tools_api::read_file::{
+    AuthenticodeSignature, CertificateInfo, CertificateTable, SignerInfo, WinCertificate,
+};
+use eframe::egui::{Color32, Label, RichText, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const ENTRY_COLUMNS: usize = 5;
+const DETAIL_COLUMNS: usize = 2;
+const CERTIFICATE_COLUMNS: usize = 6;
+const ADD_SPACE: f32 = 10.0;
+const DIGEST_MATCH_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
+const DIGEST_MISMATCH_COLOR: Color32 = Color32::from_rgb(220, 80, 80);
+
+impl FileManager {
+    pub(crate) fn signature_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        // 先计算映像哈希，加载证书表时直接复用
+        let image_hash = self.authenticode_hash()?;
+        let certificate = self.certificate()?;
+        if certificate.0.borrow().is_empty() {
+            ui.add(Label::new(i18n::NO_SECURITY_DIRECTORY));
+            return Ok(());
+        }
+        let certificates = certificate.0.borrow();
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "Security Directory");
+            eframe::egui::ScrollArea::vertical()
+                .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                .show(ui, |ui| {
+                    let width = ui.available_width();
+                    Self::show_image_hash(ui, &image_hash, width);
+                    ui.add_space(ADD_SPACE);
+                    Self::show_win_certificate_table(ui, &certificates, width);
+                    for (index, entry) in certificates.iter().enumerate() {
+                        ui.add_space(ADD_SPACE);
+                        Self::show_sub_title(
+                            ui,
+                            &format!("{} {}", index + 1, entry.get_type_name()),
+                        );
+                        match &entry.signature {
+                            Some(signature) => Self::show_signature(
+                                ui,
+                                &format!("{}", index + 1),
+                                signature,
+                                width,
+                            ),
+                            None => {
+                                ui.label(i18n::SIGNATURE_DECODE_FAILED);
+                            }
+                        }
+                    }
+                });
+        });
+        Ok(())
+    }
+
+    /// 获取证书表的引用
+    pub(crate) fn certificate(&mut self) -> anyhow::Result<CertificateTable> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.certificate.0.borrow().is_empty() {
+            file.certificate = file.get_certificates()?;
+        }
+        Ok(file.certificate.fclone())
+    }
+
+    /// 获取Authenticode映像哈希
+    pub(crate) fn authenticode_hash(&mut self) -> anyhow::Result<AuthenticodeHash> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.authenticode_hash.is_none() {
+            file.authenticode_hash = Some(file.get_authenticode_hash()?);
+        }
+        Ok(file.authenticode_hash.clone().unwrap_or_default())
+    }
+
+    fn show_image_hash(ui: &mut Ui, image_hash: &AuthenticodeHash, width: f32) {
+        let col_width = width / DETAIL_COLUMNS as f32;
+        eframe::egui::Grid::new("image_hash_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.label(format!("{} SHA-1", i18n::IMAGE_HASH));
+                ui.label(&image_hash.sha1);
+                ui.end_row();
+                ui.label(format!("{} SHA-256", i18n::IMAGE_HASH));
+                ui.label(&image_hash.sha256);
+                ui.end_row();
+            });
+    }
+
+    fn show_win_certificate_table(ui: &mut Ui, certificates: &[WinCertificate], width: f32) {
+        let col_width = width / ENTRY_COLUMNS as f32;
+        eframe::egui::Grid::new("win_certificate_grid")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(ENTRY_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong(i18n::SEQUENCE_NUMBER);
+                ui.strong(i18n::FILE_OFFSET);
+                ui.strong(i18n::SIZE);
+                ui.strong(i18n::CERTIFICATE_REVISION);
+                ui.strong(i18n::CERTIFICATE_TYPE);
+                ui.end_row();
+
+                for (index, certificate) in certificates.iter().enumerate() {
+                    ui.label(format!("{}", index + 1));
+                    ui.label(format!("0x{:08X}", certificate.file_offset));
+                    ui.label(format!("0x{:X}", certificate.length));
+                    ui.label(format!(
+                        "{} (0x{:04X})",
+                        certificate.get_revision_name(),
+                        certificate.revision
+                    ));
+                    ui.label(format!(
+                        "{} (0x{:04X})",
+                        certificate.get_type_name(),
+                        certificate.certificate_type
+                    ));
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// 显示签名及其嵌套签名，id 用于区分各层的表格
+    fn show_signature(ui: &mut Ui, id: &str, signature: &AuthenticodeSignature, width: f32) {
+        let col_width = width / DETAIL_COLUMNS as f32;
+        eframe::egui::Grid::new(format!("signature_grid_{}", id))
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.label(i18n::DIGEST_ALGORITHM);
+                ui.label(&signature.digest_algorithm);
+                ui.end_row();
+                ui.label("ContentType");
+                ui.label(&signature.content_type);
+                ui.end_row();
+                ui.label(i18n::IMAGE_DIGEST);
+                ui.label(format!(
+                    "{} {}",
+                    signature.image_digest_algorithm, signature.image_digest
+                ));
+                ui.end_row();
+                ui.label(i18n::IMAGE_DIGEST_CHECK);
+                match signature.image_digest_matches {
+                    Some(true) => {
+                        ui.label(RichText::new(i18n::IMAGE_DIGEST_MATCH).color(DIGEST_MATCH_COLOR))
+                    }
+                    Some(false) => ui.label(
+                        RichText::new(i18n::IMAGE_DIGEST_MISMATCH).color(DIGEST_MISMATCH_COLOR),
+                    ),
+                    None => ui.label(i18n::IMAGE_DIGEST_UNSUPPORTED),
+                };
+                ui.end_row();
+            });
+        for (index, signer) in signature.signers.iter().enumerate() {
+            ui.add_space(ADD_SPACE);
+            ui.strong(format!("{} {}", i18n::SIGNER, index + 1));
+            Self::show_signer(ui, &format!("{}_{}", id, index), signer, width);
+        }
+        ui.add_space(ADD_SPACE);
+        ui.strong(i18n::CERTIFICATE_LIST);
+        Self::show_certificate_list(ui, id, &signature.certificates, width);
+        for (index, nested) in signature.nested_signatures.iter().enumerate() {
+            let nested_id = format!("{}.{}", id, index + 1);
+            ui.add_space(ADD_SPACE);
+            Self::show_sub_title(ui, &format!("{} {}", i18n::NESTED_SIGNATURE, nested_id));
+            Self::show_signature(ui, &nested_id, nested, width);
+        }
+    }
+
+    fn show_signer(ui: &mut Ui, id: &str, signer: &SignerInfo, width: f32) {
+        let col_width = width / DETAIL_COLUMNS as f32;
+        eframe::egui::Grid::new(format!("signer_grid_{}", id))
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(DETAIL_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                for (name, value) in [
+                    (i18n::CERTIFICATE_SUBJECT, &signer.subject),
+                    (i18n::CERTIFICATE_ISSUER, &signer.issuer),
+                    (i18n::SERIAL_NUMBER, &signer.serial_number),
+                    (i18n::DIGEST_ALGORITHM, &signer.digest_algorithm),
+                    (i18n::SIGNATURE_ALGORITHM, &signer.signature_algorithm),
+                ] {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+                for counter_signature in &signer.counter_signatures {
+                    ui.label(format!(
+                        "{} ({})",
+                        i18n::COUNTER_SIGNATURE,
+                        counter_signature.kind
+                    ));
+                    ui.label(format!(
+                        "{}\n{}\n{} {}",
+                        counter_signature.signing_time,
+                        counter_signature.subject,
+                        i18n::SERIAL_NUMBER,
+                        counter_signature.serial_number
+                    ));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_certificate_list(ui: &mut Ui, id: &str, certificates: &[CertificateInfo], width: f32) {
+        let col_width = width / (2 * CERTIFICATE_COLUMNS) as f32;
+        eframe::egui::Grid::new(format!("certificate_grid_{}", id))
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(CERTIFICATE_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.strong(i18n::CERTIFICATE_SUBJECT);
+                ui.strong(i18n::CERTIFICATE_ISSUER);
+                ui.strong(i18n::SERIAL_NUMBER);
+                ui.strong(i18n::VALID_FROM);
+                ui.strong(i18n::VALID_TO);
+                ui.strong(i18n::SIGNATURE_ALGORITHM);
+                ui.end_row();
+                for certificate in certificates {
+                    ui.label(&certificate.subject);
+                    ui.label(&certificate.issuer);
+                    ui.label(&certificate.serial_number);
+                    ui.label(&certificate.not_before);
+                    ui.label(&certificate.not_after);
+                    ui.label(&certificate.signature_algorithm);
+                    ui.end_row();
+                }
+            });
+    }
+}
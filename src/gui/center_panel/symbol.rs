@@ -0,0 +1,136 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::read_file::{CoffSymbol, CoffSymbolTable};
+use crate::tools_api::search;
+use eframe::egui::{Color32, Label, RichText, ScrollArea, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const HEADER_COLUMNS: usize = 2;
+const SYMBOL_COLUMNS: usize = 7;
+const ADD_SPACE: f32 = 10.0;
+const MAX_SYMBOL_NAME_LENGTH: usize = 60;
+const FUNCTION_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
+
+impl FileManager {
+    pub(crate) fn symbol_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        self.symbols()?;
+        let file = &self.files[self.current_index];
+        let Some(symbols) = &file.symbols else {
+            ui.add(Label::new(i18n::NO_SYMBOL_TABLE));
+            return Ok(());
+        };
+        let section_names = file.section_headers.get_section_names();
+        let search_string = &mut self.sub_window_manager.symbol_message.search_string;
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "COFF Symbol Table");
+            Self::show_symbol_table_header(ui, symbols);
+            ui.add_space(ADD_SPACE);
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.text_edit_singleline(search_string);
+            });
+            // 符号数量可能很多，只渲染可见的行
+            let filtered: Vec<&CoffSymbol> = symbols
+                .symbols
+                .iter()
+                .filter(|symbol| {
+                    search(&symbol.name, search_string)
+                        || search(&format!("0x{:08X}", symbol.value), search_string)
+                })
+                .collect();
+            let width = ui.available_width();
+            let col_width = width / (2 * SYMBOL_COLUMNS) as f32;
+            let row_height = ui.spacing().interact_size.y + SPACING.y;
+            ScrollArea::vertical()
+                .id_salt("symbol_table")
+                .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                .auto_shrink([false, false])
+                .show_rows(ui, row_height, filtered.len() + 1, |ui, range| {
+                    eframe::egui::Grid::new("symbol_table")
+                        .striped(true)
+                        .spacing(SPACING)
+                        .num_columns(SYMBOL_COLUMNS)
+                        .min_col_width(col_width)
+                        .show(ui, |ui| {
+                            ui.strong(i18n::SYMBOL_INDEX);
+                            ui.strong(i18n::SYMBOL_NAME);
+                            ui.strong(i18n::VALUE);
+                            ui.strong(i18n::SECTION_NAME);
+                            ui.strong(i18n::SYMBOL_TYPE);
+                            ui.strong(i18n::SYMBOL_STORAGE_CLASS);
+                            ui.strong(i18n::SYMBOL_AUX);
+                            ui.end_row();
+                            let start = range.start.saturating_sub(1);
+                            let end = range.end.saturating_sub(1).min(filtered.len());
+                            for symbol in &filtered[start..end.max(start)] {
+                                Self::show_symbol_row(ui, symbol, section_names);
+                            }
+                        });
+                });
+        });
+        Ok(())
+    }
+
+    fn show_symbol_table_header(ui: &mut Ui, symbols: &CoffSymbolTable) {
+        let col_width = ui.available_width() / HEADER_COLUMNS as f32;
+        eframe::egui::Grid::new("symbol_table_header")
+            .striped(true)
+            .spacing(SPACING)
+            .num_columns(HEADER_COLUMNS)
+            .min_col_width(col_width)
+            .show(ui, |ui| {
+                ui.label("PointerToSymbolTable");
+                ui.label(format!("0x{:08X}", symbols.pointer_to_symbol_table));
+                ui.end_row();
+                ui.label("NumberOfSymbols");
+                ui.label(format!("{}", symbols.number_of_symbols));
+                ui.end_row();
+                ui.label(i18n::STRING_TABLE_SIZE);
+                ui.label(format!("0x{:X}", symbols.string_table_size));
+                ui.end_row();
+            });
+    }
+
+    fn show_symbol_row(ui: &mut Ui, symbol: &CoffSymbol, section_names: &[String]) {
+        ui.label(format!("{}", symbol.index));
+        let display_name = if symbol.name.chars().count() > MAX_SYMBOL_NAME_LENGTH {
+            let name: String = symbol
+                .name
+                .chars()
+                .take(MAX_SYMBOL_NAME_LENGTH - 3)
+                .collect();
+            format!("{}...", name)
+        } else {
+            symbol.name.clone()
+        };
+        if symbol.is_function() {
+            ui.label(RichText::new(display_name).color(FUNCTION_COLOR))
+                .on_hover_text(&symbol.name);
+        } else {
+            ui.label(display_name).on_hover_text(&symbol.name);
+        }
+        ui.label(format!("0x{:08X}", symbol.value));
+        ui.label(symbol.get_section_name(section_names));
+        ui.label(format!("0x{:04X}", symbol.symbol_type));
+        ui.label(symbol.get_storage_class_name());
+        ui.label(
+            symbol
+                .aux_symbols
+                .iter()
+                .map(|aux| aux.get_summary())
+                .collect::<Vec<_>>()
+                .join("; "),
+        );
+        ui.end_row();
+    }
+
+    /// 按需加载COFF符号表
+    pub(crate) fn symbols(&mut self) -> anyhow::Result<()> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.symbols.is_none() {
+            file.symbols = file.get_symbols()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use crate::gui::FileManager;
+use crate::i18n;
+use crate::tools_api::read_file::TlsInfo;
+use eframe::egui::{Label, Ui, Vec2};
+
+const MIN_SCROLLED_HEIGHT: f32 = 400.0;
+const SPACING: Vec2 = Vec2::new(20.0, 8.0);
+const COLUMNS: usize = 3;
+const CALLBACK_COLUMNS: usize = 5;
+const ADD_SPACE: f32 = 10.0;
+
+impl FileManager {
+    pub(crate) fn tls_panel(&mut self, ui: &mut Ui) -> anyhow::Result<()> {
+        let tls = match self.tls()? {
+            Some(tls) => tls,
+            None => {
+                ui.add(Label::new(i18n::NO_TLS));
+                return Ok(());
+            }
+        };
+        eframe::egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            Self::show_main_title(ui, "TLS Directory");
+            eframe::egui::ScrollArea::vertical()
+                .min_scrolled_height(MIN_SCROLLED_HEIGHT)
+                .show(ui, |ui| {
+                    let width = ui.available_width();
+                    let col_width = width / COLUMNS as f32;
+                    eframe::egui::Grid::new("tls_directory_grid")
+                        .striped(true)
+                        .spacing(SPACING)
+                        .num_columns(COLUMNS)
+                        .min_col_width(col_width)
+                        .show(ui, |ui| {
+                            ui.strong(i18n::FIELD_NAME);
+                            ui.strong(i18n::VALUE);
+                            ui.strong(i18n::DESCRIPTION);
+                            ui.end_row();
+
+                            ui.label("StartAddressOfRawData");
+                            ui.label(format!("0x{:X}", tls.start_address_of_raw_data));
+                            ui.label(i18n::TLS_START_ADDRESS_OF_RAW_DATA);
+                            ui.end_row();
+
+                            ui.label("EndAddressOfRawData");
+                            ui.label(format!("0x{:X}", tls.end_address_of_raw_data));
+                            ui.label(i18n::TLS_END_ADDRESS_OF_RAW_DATA);
+                            ui.end_row();
+
+                            ui.label("AddressOfIndex");
+                            ui.label(format!("0x{:X}", tls.address_of_index));
+                            ui.label(i18n::TLS_ADDRESS_OF_INDEX);
+                            ui.end_row();
+
+                            ui.label("AddressOfCallBacks");
+                            ui.label(format!("0x{:X}", tls.address_of_call_backs));
+                            ui.label(i18n::TLS_ADDRESS_OF_CALL_BACKS);
+                            ui.end_row();
+
+                            ui.label("SizeOfZeroFill");
+                            ui.label(format!("{}", tls.size_of_zero_fill));
+                            ui.label(i18n::TLS_SIZE_OF_ZERO_FILL);
+                            ui.end_row();
+
+                            ui.label("Characteristics");
+                            ui.label(format!("0x{:08X}", tls.characteristics));
+                            ui.label(i18n::TLS_CHARACTERISTICS);
+                            ui.end_row();
+                        });
+
+                    ui.add_space(ADD_SPACE);
+
+                    Self::show_sub_title(ui, i18n::TLS_CALLBACKS);
+                    if tls.callbacks.is_empty() {
+                        ui.label(i18n::NO_TLS_CALLBACKS);
+                        return;
+                    }
+                    let col_width = width / CALLBACK_COLUMNS as f32;
+                    eframe::egui::Grid::new("tls_callback_grid")
+                        .striped(true)
+                        .spacing(SPACING)
+                        .num_columns(CALLBACK_COLUMNS)
+                        .min_col_width(col_width)
+                        .show(ui, |ui| {
+                            ui.strong(i18n::SEQUENCE_NUMBER);
+                            ui.strong(i18n::VIRTUAL_ADDRESS);
+                            ui.strong("RVA");
+                            ui.strong(i18n::FILE_OFFSET);
+                            ui.strong(i18n::SECTION_NAME);
+                            ui.end_row();
+
+                            for (index, callback) in tls.callbacks.iter().enumerate() {
+                                ui.label(format!("{}", index + 1));
+                                ui.label(format!("0x{:X}", callback.virtual_address));
+                                ui.label(format!("0x{:08X}", callback.rva));
+                                match callback.file_offset {
+                                    Some(fo) => ui.label(format!("0x{:08X}", fo)),
+                                    None => ui.label(i18n::NOT_FOUND),
+                                };
+                                ui.label(&callback.section_name);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+        Ok(())
+    }
+
+    /// 获取TLS信息
+    pub(crate) fn tls(&mut self) -> anyhow::Result<Option<TlsInfo>> {
+        let file = self.files.get_mut(self.current_index).unwrap();
+        if file.tls.is_none() {
+            file.tls = file.get_tls()?;
+        }
+        Ok(file.tls.clone())
+    }
+}
@@ -1,4 +1,6 @@
 use crate::gui::FileManager;
+use crate::tools_api::FileInfo;
+use crate::tools_api::read_file::ArchiveMemberKind;
 use eframe::egui::panel::Side;
 use eframe::egui::{Color32, Frame, Label, RichText, Sense, SidePanel, Ui};
 
@@ -7,6 +9,8 @@ const LEFT_PANEL_FILL_COLOR: Color32 = Color32::from_rgb(43, 45, 48);
 const LEFT_PANEL_TEXT_COLOR: Color32 = Color32::from_rgb(114, 151, 88);
 const LEFT_PANEL_BACKGROUND_HOVER_COLOR: Color32 = Color32::from_rgb(70, 70, 70);
 const LEFT_PANEL_TEXT_SELECTED_COLOR: Color32 = Color32::from_rgb(234, 198, 118);
+/// 静态库成员相对库文件的缩进
+const ARCHIVE_MEMBER_INDENT: f32 = 12.0;
 
 impl FileManager {
     pub(crate) fn left_label(&mut self, ctx: &eframe::egui::Context) {
@@ -20,9 +24,14 @@ impl FileManager {
     }
     fn scroll_area(&mut self, ui: &mut Ui) {
         let mut files_to_drop = Vec::new();
+        let mut member_to_open = None;
         eframe::egui::ScrollArea::vertical().show(ui, |ui| {
             // 循环输出文件名
             for (i, file) in self.files.iter().enumerate() {
+                // 静态库成员显示在所属的库下方
+                if file.archive_parent.is_some() {
+                    continue;
+                }
                 let text_context;
                 let mut color = LEFT_PANEL_FILL_COLOR;
                 let file_name = if file.file.is_some() {
@@ -68,8 +77,16 @@ impl FileManager {
                         files_to_drop.push(i);
                     }
                 });
+                if let Some(member_index) = self.archive_member_list(ui, file) {
+                    member_to_open = Some((i, member_index));
+                }
             }
         });
+        if let Some((archive_index, member_index)) = member_to_open
+            && let Err(e) = self.open_archive_member(archive_index, member_index)
+        {
+            self.sub_window_manager.show_error(&e.to_string());
+        }
 
         for &index in files_to_drop.iter().rev() {
             if index < self.files.len()
@@ -79,4 +96,34 @@ impl FileManager {
             }
         }
     }
+
+    /// 库文件或其成员被选中时列出可打开的COFF目标文件，返回被点击的成员索引
+    fn archive_member_list(&self, ui: &mut Ui, file: &FileInfo) -> Option<usize> {
+        let archive = file.archive.as_ref()?;
+        let current = self.get_file();
+        if **current != *file && current.archive_parent.as_ref() != Some(&file.file_path) {
+            return None;
+        }
+        let mut clicked = None;
+        for (index, member) in archive.members.iter().enumerate() {
+            if member.kind != ArchiveMemberKind::CoffObject {
+                continue;
+            }
+            let color = if current.file_path == member.get_extract_path(&file.file_path) {
+                LEFT_PANEL_TEXT_SELECTED_COLOR
+            } else {
+                LEFT_PANEL_TEXT_COLOR
+            };
+            ui.horizontal(|ui| {
+                ui.add_space(ARCHIVE_MEMBER_INDENT);
+                let label = Label::new(RichText::from(member.get_display_name()).color(color))
+                    .sense(Sense::click())
+                    .truncate();
+                if ui.add(label).on_hover_text(&member.name).clicked() {
+                    clicked = Some(index);
+                }
+            });
+        }
+        clicked
+    }
 }
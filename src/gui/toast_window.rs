@@ -5,7 +5,7 @@ use crate::{
     tools_api::{
         parse_address_string,
         read_file::{ImageSectionHeaders, nt_header::traits::NtHeaders, rva_2_fo},
-        serde_pe::DangerousFunction,
+        serde_pe::{BUILTIN_ORDINALS, DangerousFunction},
     },
 };
 use eframe::egui::{Color32, Context};
@@ -76,6 +76,27 @@ impl SubWindowManager {
                                 self.show_success(i18n::CREATE_DANGEROUS_FUNCTION_SUCCESS);
                             }
                         });
+                        ui.horizontal(|ui| {
+                            ui.label(i18n::CREATE_ORDINAL_DATABASE);
+                            if ui.button(i18n::CREATE).clicked() {
+                                // 序号数据库从配置目录加载，直接在配置目录中生成
+                                let mut path = crate::get_config_path();
+                                path.push("Ordinals.toml");
+                                let result =
+                                    fs::create_dir_all(crate::get_config_path()).and_then(|_| {
+                                        if fs::exists(&path)? {
+                                            return Ok(());
+                                        }
+                                        fs::write(&path, BUILTIN_ORDINALS)
+                                    });
+                                match result {
+                                    Ok(_) => {
+                                        self.show_success(i18n::CREATE_ORDINAL_DATABASE_SUCCESS)
+                                    }
+                                    Err(e) => self.show_error(&e.to_string()),
+                                }
+                            }
+                        });
                         if ui.button(i18n::CANCEL_BUTTON).clicked() {
                             self.window_message.show_settings_window = false;
                         }
@@ -11,6 +11,11 @@ use std::path::PathBuf;
 
 impl FileManager {
     pub(crate) fn top_label(&mut self, ctx: &eframe::egui::Context) {
+        // 目标文件与静态库没有资源目录
+        let is_pe_image = self
+            .files
+            .get(self.current_index)
+            .is_none_or(|file| file.is_pe_image());
         eframe::egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             eframe::egui::MenuBar::new().ui(ui, |ui| {
                 ui.menu_button(i18n::FILE_MENU, |ui| {
@@ -57,7 +62,7 @@ impl FileManager {
                             self.sub_window_manager.show_error(&e.to_string());
                         }
                     });
-                    if ui.button(i18n::EXTRACT_RESOURCE_MENU).clicked() {
+                    if is_pe_image && ui.button(i18n::EXTRACT_RESOURCE_MENU).clicked() {
                         let files = match self.files.get(self.current_index) {
                             Some(file) => file,
                             None => {
@@ -103,6 +108,11 @@ impl FileManager {
                                 vec![]
                             });
                     }
+                    if ui.button(i18n::EXTRACT_OVERLAY_MENU).clicked()
+                        && let Err(e) = self.extract_overlay()
+                    {
+                        self.sub_window_manager.show_error(&e.to_string());
+                    }
                 });
 
                 ui.menu_button(i18n::HELP_MENU, |ui| {
@@ -116,6 +126,22 @@ impl FileManager {
             });
         });
     }
+    /// 将附加数据提取到选择的目录中
+    fn extract_overlay(&mut self) -> anyhow::Result<()> {
+        let file_info = self
+            .files
+            .get(self.current_index)
+            .ok_or(anyhow::anyhow!(i18n::FILE_NOT_FOUND))?;
+        if file_info.get_overlay()?.is_none() {
+            return Err(anyhow::anyhow!(i18n::NO_OVERLAY));
+        }
+        if let Some(output_path) = FileDialog::new().pick_folder() {
+            file_info.extract_overlay(&output_path)?;
+            self.sub_window_manager
+                .show_success(i18n::EXTRACT_OVERLAY_SUCCESS);
+        }
+        Ok(())
+    }
     fn save_serde(&mut self, ui: &mut Ui, file_type: &str) -> anyhow::Result<()> {
         if ui
             .button(i18n::SAVE_AS_FORMAT.replace("{}", file_type).to_string())
@@ -189,9 +215,12 @@ impl FileManager {
                 {
                     if i != j {
                         let mut f = file_info.get_mut_file()?;
-                        i.write_func_name(&mut f, &j.name)?;
-                        self.sub_window_manager
-                            .show_success(i18n::EXPORT_TABLE_MODIFIED);
+                        // 只按序号导出的函数没有名称字符串
+                        if i.is_named() {
+                            i.write_func_name(&mut f, &j.name)?;
+                            self.sub_window_manager
+                                .show_success(i18n::EXPORT_TABLE_MODIFIED);
+                        }
                         i.write_func_address(&mut f, j.function)?;
                         self.sub_window_manager
                             .show_success(i18n::EXPORT_TABLE_MODIFIED);
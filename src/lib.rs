@@ -4,7 +4,7 @@ pub mod i18n;
 pub mod tools_api;
 use crate::tools_api::HashInfo;
 use crate::tools_api::calc::ThreadPool;
-use crate::tools_api::serde_pe::DangerousFunction;
+use crate::tools_api::serde_pe::{DangerousFunction, OrdinalDatabase};
 use std::path::PathBuf;
 use std::sync::{LazyLock, Mutex, RwLock};
 /// 获取linux系统特定的配置文件路径
@@ -53,6 +53,13 @@ pub static DANGEROUS_FUNCTION_TOML_PATH: LazyLock<DangerousFunction> = LazyLock:
     DangerousFunction::from_file_info(&path).unwrap_or_default()
 });
 
+/// 序号导入名称数据库，配置文件中的条目会覆盖内置条目
+pub static ORDINAL_DATABASE: LazyLock<OrdinalDatabase> = LazyLock::new(|| {
+    let mut path = get_config_path();
+    path.push("Ordinals.toml");
+    OrdinalDatabase::from_file_info(&path)
+});
+
 // Global thread pool
 pub static GLOBAL_THREAD_POOL: LazyLock<ThreadPool> = LazyLock::new(|| ThreadPool::new(3));
 
@@ -5,24 +5,56 @@ pub(crate) mod serde_pe;
 pub(crate) mod write_file;
 use crate::gui::SubWindowManager;
 use crate::i18n;
-use crate::tools_api::calc::start_calc_hash;
+use crate::tools_api::calc::{
+    calc_authenticode_hash, calc_exphash, calc_imphash, get_hash_info, start_calc_hash,
+};
+use crate::tools_api::read_file::anomaly::HeaderScanner;
 use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::nt_header::{
+    DIRECTORY_BASERELOC, DIRECTORY_BOUND_IMPORT, DIRECTORY_COM_DESCRIPTOR, DIRECTORY_DEBUG,
+    DIRECTORY_DELAY_IMPORT, DIRECTORY_EXCEPTION, DIRECTORY_EXPORT, DIRECTORY_IMPORT,
+    DIRECTORY_LOAD_CONFIG, DIRECTORY_RESOURCE, DIRECTORY_SECURITY, DIRECTORY_TLS, MACHINE_64,
+};
+use crate::tools_api::read_file::symbol::get_string_table_offset;
 use crate::tools_api::read_file::{
-    DataDirectory, ExportDir, ExportTable, ImageDosHeader, ImageDosStub, ImageFileHeader,
-    ImageNtHeaders, ImageNtHeaders64, ImageSectionHeaders, ImportDescriptor, ImportDll,
-    ImportTable, nt_header,
+    Anomaly, AnomalySeverity, ArchiveInfo, BoundImportInfo, CertificateTable, ChpeMetadata,
+    CoffSectionDetail, CoffSymbolTable, DataDirectory, DebugTable, DotNetInfo, ExceptionTable,
+    ExportDir, ExportTable, ImageCoffHeader, ImageDosHeader, ImageDosStub, ImageFileHeader,
+    ImageLoadConfigDirectory, ImageLoadConfigDirectory64, ImageNtHeaders, ImageNtHeaders64,
+    ImageSectionHeaders, ImportDescriptor, ImportDll, ImportTable, LoadConfigInfo, ManifestInfo,
+    OverlayInfo, RelocationTable, ResourceStrings, ResourceTree, RichHeader, TlsInfo, UiResources,
+    VersionInfo, nt_header,
 };
+use byteorder::{LittleEndian, ReadBytesExt};
 use log::debug;
 use serde_derive::{Deserialize, Serialize};
 use std::cell::{Ref, RefCell, RefMut};
 use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::mem::offset_of;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+/// 可选头 Magic，决定按32位还是64位布局解析
+const IMAGE_NT_OPTIONAL_HDR32_MAGIC: u16 = 0x10B;
+const IMAGE_NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20B;
+/// 可选头中 CheckSum 字段的偏移，32位与64位相同
+const CHECKSUM_OFFSET: u64 = 64;
+/// 数据目录中安全目录项的偏移
+const SECURITY_ENTRY_OFFSET: u64 = 4 * 8;
+/// 文件头中 PointerToSymbolTable 字段的偏移
+const POINTER_TO_SYMBOL_TABLE_OFFSET: u64 = 8;
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct HashInfo {
     pub md5: String,
     pub sha1: String,
+    /// 导入表哈希，只有PE映像存在导入时才有
+    #[serde(default)]
+    pub imphash: Option<String>,
+    /// 导出表哈希，只有PE映像存在导出时才有
+    #[serde(default)]
+    pub exphash: Option<String>,
     pub path: PathBuf,
 }
 impl HashInfo {
@@ -31,6 +63,13 @@ impl HashInfo {
     }
 }
 
+/// Authenticode映像哈希，不包含校验和、安全目录项与证书表
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Default)]
+pub struct AuthenticodeHash {
+    pub sha1: String,
+    pub sha256: String,
+}
+
 pub struct FileInfo {
     pub file: Option<RefCell<File>>,
     pub file_name: String,
@@ -38,13 +77,37 @@ pub struct FileInfo {
     pub file_hash: Option<HashInfo>,
     pub dos_head: Box<ImageDosHeader>,
     pub dos_stub: ImageDosStub,
+    pub rich_header: Option<RichHeader>,
+    pub version_info: Option<VersionInfo>,
     pub is_64_bit: bool,
+    /// 不含DOS头与可选头的COFF目标文件（.obj）
+    pub is_coff_object: bool,
+    /// 从静态库中打开的成员所属的库文件
+    pub archive_parent: Option<PathBuf>,
     pub file_size: u64,
     pub(crate) nt_head: Box<dyn NtHeaders>,
     pub(crate) data_directory: DataDirectory,
     pub(crate) section_headers: ImageSectionHeaders,
     pub(crate) import_dll: ImportTable,
     pub(crate) export: ExportTable,
+    pub(crate) relocation: RelocationTable,
+    pub(crate) tls: Option<TlsInfo>,
+    pub(crate) load_config: Option<LoadConfigInfo>,
+    pub(crate) debug: DebugTable,
+    pub(crate) exception: ExceptionTable,
+    pub(crate) certificate: CertificateTable,
+    pub(crate) authenticode_hash: Option<AuthenticodeHash>,
+    pub(crate) dotnet: Option<DotNetInfo>,
+    pub(crate) manifest: Option<ManifestInfo>,
+    pub(crate) resource_strings: Option<ResourceStrings>,
+    pub(crate) ui_resources: Option<UiResources>,
+    pub(crate) overlay: Option<OverlayInfo>,
+    pub(crate) symbols: Option<CoffSymbolTable>,
+    pub(crate) coff_sections: Option<Vec<CoffSectionDetail>>,
+    /// 静态库（.lib / .a）的成员与符号索引
+    pub(crate) archive: Option<ArchiveInfo>,
+    /// 打开文件及解析各目录时发现的畸形结构
+    pub(crate) anomalies: RefCell<Vec<Anomaly>>,
 }
 
 /// 窗口数组及其信息
@@ -57,6 +120,27 @@ pub enum Page {
     SectionHead,
     Import,
     Export,
+    Relocation,
+    Tls,
+    LoadConfig,
+    Debug,
+    Exception,
+    Signature,
+    DotNet,
+    Resource,
+    Symbol,
+    Archive,
+    Anomaly,
+}
+
+impl Page {
+    /// COFF目标文件只有文件头、节、符号表与畸形结构页面
+    pub(crate) fn is_coff_object_page(&self) -> bool {
+        matches!(
+            self,
+            Page::NtHead | Page::SectionHead | Page::Symbol | Page::Anomaly
+        )
+    }
 }
 
 #[derive(Default)]
@@ -126,19 +210,126 @@ impl FileInfo {
         let file_size = file.metadata()?.len();
         debug!("{} size: {}", file_name, file_size);
         let _is_little_endian = true; //todo 需要根据文件头判断
-        let dos_head = Box::new(ImageDosHeader::new(&mut file)?);
-        debug!("{:?}", dos_head);
-        let nt_addr = dos_head.get_nt_addr();
-        let is_64_bit = is_64(&mut file, &dos_head)?;
-        let (nt_head, data_directory) = Self::parse_nt_headers(&mut file, nt_addr, is_64_bit)?;
+        let archive = if ArchiveInfo::is_archive(&mut file)? {
+            Some(ArchiveInfo::new(&mut file)?)
+        } else {
+            None
+        };
+        // 不以MZ开头且文件头合法时按COFF目标文件处理
+        let coff_header = match archive {
+            Some(_) => None,
+            None => ImageCoffHeader::new(&mut file)?,
+        };
+        let is_coff_object = coff_header.is_some();
+        let is_pe_image = !is_coff_object && archive.is_none();
+        let mut nt_headers_valid = true;
+        let (dos_head, nt_addr, is_64_bit, nt_head, data_directory) = match coff_header {
+            // 静态库没有文件头，使用空的COFF头占位
+            None if archive.is_some() => (
+                Box::default(),
+                0,
+                false,
+                Box::new(ImageCoffHeader::default()) as Box<dyn NtHeaders>,
+                DataDirectory::new_empty(),
+            ),
+            Some(coff_header) => (
+                Box::default(),
+                0,
+                coff_header.is_64_bit(),
+                Box::new(coff_header) as Box<dyn NtHeaders>,
+                DataDirectory::new_empty(),
+            ),
+            None => {
+                file.seek(SeekFrom::Start(0))?;
+                let dos_head = Box::new(ImageDosHeader::new(&mut file)?);
+                debug!("{:?}", dos_head);
+                let nt_addr = dos_head.get_nt_addr();
+                let nt_headers = is_64(&mut file, &dos_head).and_then(|is_64_bit| {
+                    let (nt_head, data_directory) =
+                        Self::parse_nt_headers(&mut file, nt_addr, is_64_bit)?;
+                    Ok((is_64_bit, nt_head, data_directory))
+                });
+                // NT头超出文件末尾或被截断时按空的头部继续，由畸形结构检查报告
+                let (is_64_bit, nt_head, data_directory) = nt_headers.unwrap_or_else(|_| {
+                    nt_headers_valid = false;
+                    (
+                        false,
+                        Box::new(ImageNtHeaders::default()) as Box<dyn NtHeaders>,
+                        DataDirectory::new_empty(),
+                    )
+                });
+                (dos_head, nt_addr, is_64_bit, nt_head, data_directory)
+            }
+        };
         debug!("{}\n{:?}", nt_head, data_directory);
-        let section_headers = ImageSectionHeaders::new(
-            &mut file,
-            nt_head.section_start(nt_addr),
-            nt_head.section_number(),
-        )?;
+        let (pointer_to_symbol_table, number_of_symbols) = nt_head.symbol_table();
+        let section_headers = if archive.is_some() {
+            ImageSectionHeaders::default()
+        } else {
+            ImageSectionHeaders::new(
+                &mut file,
+                nt_head.section_start(nt_addr),
+                nt_head.section_number(),
+                get_string_table_offset(pointer_to_symbol_table, number_of_symbols),
+            )?
+        };
         debug!("{:?}", section_headers);
-        let dos_stub = ImageDosStub::new(&mut file, nt_addr)?;
+        let scanner = HeaderScanner {
+            file_size,
+            nt_addr,
+            is_64_bit,
+            nt_headers_valid,
+            nt_head: &*nt_head,
+            data_directory: &data_directory,
+            section_headers: &section_headers,
+        };
+        let mut anomalies = if is_pe_image {
+            scanner.scan_image(&mut file)?
+        } else if is_coff_object {
+            scanner.scan_object()
+        } else {
+            Vec::new()
+        };
+        let (dos_stub, rich_header, version_info) = if !is_pe_image {
+            (ImageDosStub { buffer: Vec::new() }, None, None)
+        } else {
+            let dos_stub = ImageDosStub::new(&mut file, nt_addr)?;
+            let rich_header = RichHeader::new(&mut file, nt_addr)?;
+            let resource_address = data_directory.get_resource_directory_address()?;
+            if resource_address != 0 {
+                match ResourceTree::get_resource_tree_with_anomalies(
+                    &mut file,
+                    resource_address,
+                    &*nt_head,
+                    &section_headers,
+                ) {
+                    Ok((_, resource_anomalies)) => anomalies.extend(resource_anomalies),
+                    Err(e) => anomalies.push(Anomaly::new_directory_failure(
+                        nt_addr,
+                        is_64_bit,
+                        DIRECTORY_RESOURCE,
+                        &e,
+                    )),
+                }
+            }
+            // 资源目录损坏时仍然允许打开文件
+            let version_info =
+                VersionInfo::new(&mut file, &*nt_head, &section_headers, &data_directory)
+                    .unwrap_or_else(|e| {
+                        anomalies.push(Anomaly::new(
+                            "VS_VERSIONINFO",
+                            Anomaly::get_directory_entry_offset(
+                                nt_addr,
+                                is_64_bit,
+                                DIRECTORY_RESOURCE,
+                            ),
+                            AnomalySeverity::Warning,
+                            i18n::ANOMALY_PARSE_FAILED.replace("{}", &e.to_string()),
+                        ));
+                        None
+                    });
+            (dos_stub, rich_header, version_info)
+        };
         let file = match File::options().read(true).write(true).open(&file_path) {
             Ok(file) => Some(RefCell::new(file)),
             Err(_e) => None,
@@ -151,13 +342,33 @@ impl FileInfo {
             file_hash: None,
             dos_head,
             dos_stub,
+            rich_header,
+            version_info,
             is_64_bit,
+            is_coff_object,
+            archive_parent: None,
             file_size,
             nt_head,
             data_directory,
             section_headers,
             import_dll: ImportTable::default(),
             export: ExportTable::default(),
+            relocation: RelocationTable::default(),
+            tls: None,
+            load_config: None,
+            debug: DebugTable::default(),
+            exception: ExceptionTable::default(),
+            certificate: CertificateTable::default(),
+            authenticode_hash: None,
+            dotnet: None,
+            manifest: None,
+            resource_strings: None,
+            ui_resources: None,
+            overlay: None,
+            symbols: None,
+            coff_sections: None,
+            archive,
+            anomalies: RefCell::new(anomalies),
         }))
     }
 
@@ -173,7 +384,7 @@ impl FileInfo {
     /// 解析NT头部信息
     fn parse_nt_headers(
         file: &mut File,
-        nt_addr: u16,
+        nt_addr: u32,
         is_64_bit: bool,
     ) -> anyhow::Result<(Box<dyn NtHeaders>, DataDirectory)> {
         if is_64_bit {
@@ -187,17 +398,90 @@ impl FileInfo {
 
     pub fn get_export(&self) -> anyhow::Result<ExportTable> {
         let mut f = self.get_mut_file()?;
-        if let Some(export_dir) = ExportDir::new(
+        let export = ExportDir::new(
             &mut f,
             &*self.nt_head,
             &self.section_headers,
             &self.data_directory,
-        )? {
-            let export_info =
-                ExportTable::new(&mut f, &*self.nt_head, &self.section_headers, &export_dir)?;
-            return Ok(export_info);
+        )
+        .and_then(|export_dir| match export_dir {
+            Some(export_dir) => ExportTable::new(
+                &mut f,
+                &*self.nt_head,
+                &self.section_headers,
+                &self.data_directory,
+                &export_dir,
+            ),
+            None => Ok(ExportTable::default()),
+        });
+        Ok(self.report_directory(DIRECTORY_EXPORT, export))
+    }
+
+    /// 记录一条畸形结构，同一问题只记录一次
+    pub(crate) fn add_anomaly(&self, anomaly: Anomaly) {
+        let mut anomalies = self.anomalies.borrow_mut();
+        if !anomalies.contains(&anomaly) {
+            anomalies.push(anomaly);
+        }
+    }
+
+    /// 目录解析失败时记录为畸形结构并返回空结果，使文件其余部分仍可查看
+    fn report_directory<R: Default>(&self, index: usize, result: anyhow::Result<R>) -> R {
+        result.unwrap_or_else(|e| {
+            self.add_anomaly(Anomaly::new_directory_failure(
+                self.dos_head.get_nt_addr(),
+                self.is_64_bit,
+                index,
+                &e,
+            ));
+            R::default()
+        })
+    }
+
+    /// 数据目录以外的结构解析失败时记录为畸形结构并返回空结果
+    fn report_failure<R: Default>(&self, field: &str, offset: u64, result: anyhow::Result<R>) -> R {
+        result.unwrap_or_else(|e| {
+            self.add_anomaly(Anomaly::new(
+                field,
+                offset,
+                AnomalySeverity::Error,
+                i18n::ANOMALY_PARSE_FAILED.replace("{}", &e.to_string()),
+            ));
+            R::default()
+        })
+    }
+
+    /// 取回后台计算的文件哈希，同时补充导入表哈希与导出表哈希
+    pub fn update_file_hash(&mut self) {
+        if self.file_hash.is_some() {
+            return;
+        }
+        let Some(mut file_hash) = get_hash_info(self.file_path.clone()) else {
+            return;
+        };
+        if self.is_pe_image() {
+            file_hash.imphash = self.get_imphash();
+            file_hash.exphash = self.get_exphash();
+        }
+        self.file_hash = Some(file_hash);
+    }
+
+    /// 计算imphash，导入表尚未解析时先解析，解析失败时返回None
+    pub fn get_imphash(&mut self) -> Option<String> {
+        let import_dll_geted = !self.import_dll.0.borrow().is_empty();
+        if !import_dll_geted {
+            self.import_dll = self.get_imports().ok()?;
+        }
+        calc_imphash(&self.import_dll.0.borrow())
+    }
+
+    /// 计算导出表哈希，导出表尚未解析时先解析，解析失败时返回None
+    pub fn get_exphash(&mut self) -> Option<String> {
+        let export_geted = !self.export.0.borrow().is_empty();
+        if !export_geted {
+            self.export = self.get_export().ok()?;
         }
-        Ok(ExportTable::default())
+        calc_exphash(&self.export.0.borrow())
     }
 
     /// 获取导入表
@@ -205,6 +489,7 @@ impl FileInfo {
         let f = &mut self.get_mut_file()?;
         let mut import_infos = Vec::new();
         let mut index = 0;
+        // 单个DLL解析失败时跳过该DLL，保留其余的导入
         loop {
             let import = ImportDescriptor::new(
                 f,
@@ -212,23 +497,307 @@ impl FileInfo {
                 &self.section_headers,
                 &self.data_directory,
                 index,
-            )?;
-            if let Some(import) = import {
-                let import_info = ImportDll::new(
-                    f,
-                    &self.dos_head,
-                    import,
-                    &*self.nt_head,
-                    &self.section_headers,
-                )?;
-                import_infos.push(import_info);
-            } else {
+            );
+            let Some(import) = self.report_directory(DIRECTORY_IMPORT, import) else {
                 break;
+            };
+            let import_info = ImportDll::new(
+                f,
+                &self.dos_head,
+                import,
+                &*self.nt_head,
+                &self.section_headers,
+            )
+            .map(Some);
+            if let Some(import_info) = self.report_directory(DIRECTORY_IMPORT, import_info) {
+                import_infos.push(import_info);
             }
             index += 1;
         }
+        let bound_imports = BoundImportInfo::new_list(
+            f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        let bound_imports = self.report_directory(DIRECTORY_BOUND_IMPORT, bound_imports);
+        for import_info in import_infos.iter_mut() {
+            import_info.bind(&bound_imports, &self.file_path);
+        }
+        // 延迟加载的DLL追加在普通导入之后
+        let delay_imports = ImportDll::new_delay_imports(
+            f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+            self.is_64_bit,
+        );
+        import_infos.extend(self.report_directory(DIRECTORY_DELAY_IMPORT, delay_imports));
         Ok(ImportTable(Rc::new(RefCell::new(import_infos))))
     }
+
+    /// 获取基址重定位表
+    pub fn get_relocations(&self) -> anyhow::Result<RelocationTable> {
+        let mut f = self.get_mut_file()?;
+        let relocation = RelocationTable::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_BASERELOC, relocation))
+    }
+
+    /// 获取TLS目录及回调函数
+    pub fn get_tls(&self) -> anyhow::Result<Option<TlsInfo>> {
+        let mut f = self.get_mut_file()?;
+        let tls = TlsInfo::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+            self.is_64_bit,
+        );
+        Ok(self.report_directory(DIRECTORY_TLS, tls))
+    }
+
+    /// 获取加载配置目录
+    pub fn get_load_config(&self) -> anyhow::Result<Option<LoadConfigInfo>> {
+        let mut f = self.get_mut_file()?;
+        let load_config = LoadConfigInfo::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+            self.is_64_bit,
+        );
+        let mut load_config = self.report_directory(DIRECTORY_LOAD_CONFIG, load_config);
+        // 混合映像元数据损坏时只丢弃元数据，保留加载配置目录本身
+        if let Some(load_config) = load_config.as_mut() {
+            let chpe_metadata = ChpeMetadata::new(
+                &mut f,
+                &*self.nt_head,
+                &self.section_headers,
+                load_config.chpe_metadata_pointer,
+            );
+            let pointer_offset = if self.is_64_bit {
+                offset_of!(ImageLoadConfigDirectory64, chpe_metadata_pointer)
+            } else {
+                offset_of!(ImageLoadConfigDirectory, chpe_metadata_pointer)
+            };
+            load_config.chpe_metadata = self.report_failure(
+                "CHPEMetadataPointer",
+                load_config.file_offset as u64 + pointer_offset as u64,
+                chpe_metadata,
+            );
+        }
+        Ok(load_config)
+    }
+
+    /// 获取调试目录
+    pub fn get_debug(&self) -> anyhow::Result<DebugTable> {
+        let mut f = self.get_mut_file()?;
+        let debug = DebugTable::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_DEBUG, debug))
+    }
+
+    /// 获取异常目录中的函数表
+    pub fn get_exception(&self) -> anyhow::Result<ExceptionTable> {
+        let mut f = self.get_mut_file()?;
+        let exception = ExceptionTable::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_EXCEPTION, exception))
+    }
+
+    /// 获取安全目录中的证书表，并用映像哈希校验其中的签名摘要
+    pub fn get_certificates(&self) -> anyhow::Result<CertificateTable> {
+        let certificate = {
+            let mut f = self.get_mut_file()?;
+            let certificate = CertificateTable::new(&mut f, &self.data_directory);
+            self.report_directory(DIRECTORY_SECURITY, certificate)
+        };
+        if certificate.is_signed() {
+            let image_hash = match &self.authenticode_hash {
+                Some(image_hash) => image_hash.clone(),
+                None => self.get_authenticode_hash()?,
+            };
+            certificate.verify_image_digest(&image_hash);
+        }
+        Ok(certificate)
+    }
+
+    /// 获取CLI头及.NET元数据，非托管文件返回None
+    pub fn get_dotnet(&self) -> anyhow::Result<Option<DotNetInfo>> {
+        let mut f = self.get_mut_file()?;
+        let dotnet = DotNetInfo::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_COM_DESCRIPTOR, dotnet))
+    }
+
+    /// 获取应用程序清单，不含 RT_MANIFEST 资源时返回None
+    pub fn get_manifest(&self) -> anyhow::Result<Option<ManifestInfo>> {
+        let mut f = self.get_mut_file()?;
+        let manifest = ManifestInfo::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_RESOURCE, manifest))
+    }
+
+    /// 获取字符串表与消息表，两者都不存在时返回None
+    pub fn get_resource_strings(&self) -> anyhow::Result<Option<ResourceStrings>> {
+        let mut f = self.get_mut_file()?;
+        let resource_strings = ResourceStrings::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_RESOURCE, resource_strings))
+    }
+
+    /// 获取对话框、菜单与快捷键表，都不存在时返回None
+    pub fn get_ui_resources(&self) -> anyhow::Result<Option<UiResources>> {
+        let mut f = self.get_mut_file()?;
+        let ui_resources = UiResources::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        );
+        Ok(self.report_directory(DIRECTORY_RESOURCE, ui_resources))
+    }
+
+    /// 获取映像结束位置之后的附加数据，不存在时返回None
+    pub fn get_overlay(&self) -> anyhow::Result<Option<OverlayInfo>> {
+        // 目标文件中节数据之后是符号表，不属于附加数据
+        if !self.is_pe_image() {
+            return Ok(None);
+        }
+        let mut f = self.get_mut_file()?;
+        OverlayInfo::new(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            &self.data_directory,
+        )
+    }
+
+    /// 是否为可执行映像，COFF目标文件与静态库没有PE数据目录
+    pub fn is_pe_image(&self) -> bool {
+        !self.is_coff_object && self.archive.is_none()
+    }
+
+    /// 将静态库成员解压到临时目录，已存在时直接返回路径
+    pub fn extract_archive_member(&self, index: usize) -> anyhow::Result<PathBuf> {
+        let member = self
+            .archive
+            .as_ref()
+            .and_then(|archive| archive.members.get(index))
+            .ok_or(anyhow::anyhow!(i18n::ARCHIVE_MEMBER_NOT_FOUND))?;
+        let path = member.get_extract_path(&self.file_path);
+        if !path.exists() {
+            let mut f = self.get_mut_file()?;
+            member.extract(&mut f, &path)?;
+        }
+        Ok(path)
+    }
+
+    /// 获取COFF符号表，不存在时返回None
+    pub fn get_symbols(&self) -> anyhow::Result<Option<CoffSymbolTable>> {
+        let mut f = self.get_mut_file()?;
+        let (pointer_to_symbol_table, number_of_symbols) = self.nt_head.symbol_table();
+        let symbols = CoffSymbolTable::new(&mut f, pointer_to_symbol_table, number_of_symbols);
+        Ok(self.report_failure(
+            "PointerToSymbolTable",
+            self.get_file_header_offset() + POINTER_TO_SYMBOL_TABLE_OFFSET,
+            symbols,
+        ))
+    }
+
+    /// 获取每个节的COFF重定位项与COMDAT信息，都不存在时返回None
+    pub fn get_coff_sections(&self) -> anyhow::Result<Option<Vec<CoffSectionDetail>>> {
+        let symbols = match &self.symbols {
+            Some(symbols) => Some(symbols.clone()),
+            None => self.get_symbols()?,
+        };
+        let mut f = self.get_mut_file()?;
+        let coff_sections = CoffSectionDetail::new_list(
+            &mut f,
+            &*self.nt_head,
+            &self.section_headers,
+            symbols.as_ref(),
+        );
+        let section_start = self.nt_head.section_start(self.dos_head.get_nt_addr());
+        Ok(self.report_failure("IMAGE_SECTION_HEADER", section_start as u64, coff_sections))
+    }
+
+    /// 文件头的偏移，目标文件没有DOS头与PE签名
+    fn get_file_header_offset(&self) -> u64 {
+        if self.is_pe_image() {
+            self.dos_head.get_nt_addr() as u64 + 4
+        } else {
+            0
+        }
+    }
+
+    /// 将附加数据逐段提取到目录中，返回写入的文件路径
+    pub fn extract_overlay(&self, output_dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let overlay = self
+            .get_overlay()?
+            .ok_or(anyhow::anyhow!(i18n::NO_OVERLAY))?;
+        let mut f = self.get_mut_file()?;
+        let mut paths = Vec::new();
+        for region in &overlay.regions {
+            let path = output_dir.join(format!(
+                "{}_overlay_0x{:X}{}",
+                self.file_name,
+                region.offset,
+                region.file_type.as_deref().unwrap_or(".bin")
+            ));
+            region.extract(&mut f, &path)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// 计算Authenticode映像哈希，跳过校验和字段、安全目录项和证书表
+    pub fn get_authenticode_hash(&self) -> anyhow::Result<AuthenticodeHash> {
+        let optional_header =
+            self.dos_head.get_nt_addr() as u64 + 4 + size_of::<ImageFileHeader>() as u64;
+        let data_directory = optional_header + if self.is_64_bit { 112 } else { 96 };
+        let mut skip_ranges = vec![(optional_header + CHECKSUM_OFFSET, 4)];
+        // NumberOfRvaAndSizes 不足时没有安全目录项，这部分字节照常参与哈希
+        if self.nt_head.num_of_rva() > DIRECTORY_SECURITY as u32 {
+            skip_ranges.push((data_directory + SECURITY_ENTRY_OFFSET, 8));
+        }
+        let table_offset = self
+            .data_directory
+            .get_data_directory_virtual_address(DIRECTORY_SECURITY);
+        let table_size = self
+            .data_directory
+            .get_data_directory_size(DIRECTORY_SECURITY);
+        if table_offset != 0 && table_size != 0 {
+            skip_ranges.push((table_offset as u64, table_size as u64));
+        }
+        let mut f = self.get_mut_file()?;
+        calc_authenticode_hash(&mut f, &skip_ranges)
+    }
 }
 
 pub(crate) fn load_file_info(path: PathBuf) -> anyhow::Result<Box<FileInfo>> {
@@ -257,14 +826,16 @@ pub fn parse_address_string(input: &str) -> Result<usize, String> {
     }
 }
 
+/// 按可选头 Magic 判断头部布局，与机器类型无关
 pub fn is_64(file: &mut File, image_dos_header: &ImageDosHeader) -> anyhow::Result<bool> {
-    let image_file_header = ImageFileHeader::new(file, image_dos_header)?;
-    if nt_header::MACHINE_32.contains(&image_file_header.machine) {
-        return Ok(false);
-    } else if nt_header::MACHINE_64.contains(&image_file_header.machine) {
-        return Ok(true);
+    // Magic 紧跟在文件头之后
+    let file_header = ImageFileHeader::new(file, image_dos_header)?;
+    match file.read_u16::<LittleEndian>()? {
+        IMAGE_NT_OPTIONAL_HDR32_MAGIC => Ok(false),
+        IMAGE_NT_OPTIONAL_HDR64_MAGIC => Ok(true),
+        // Magic 被篡改时只能按机器类型猜测，由畸形结构检查报告
+        _ => Ok(MACHINE_64.contains(&file_header.machine)),
     }
-    Err(anyhow::anyhow!(i18n::NOT_NORMAL_MACHINE_IMAGE))
 }
 
 // 搜索需要改进
@@ -1,8 +1,12 @@
-use crate::tools_api::HashInfo;
-use crate::{GLOBAL_HASH_INFO, GLOBAL_THREAD_POOL, i18n};
+use crate::tools_api::read_file::{ExportInfo, ImportDll};
+use crate::tools_api::{AuthenticodeHash, HashInfo};
+use crate::{GLOBAL_HASH_INFO, GLOBAL_THREAD_POOL, ORDINAL_DATABASE, i18n};
 use file_hashing::get_hash_file;
 use md5::{Digest, Md5};
 use sha1::Sha1;
+use sha2::Sha256;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
@@ -12,6 +16,11 @@ use std::thread::{self, JoinHandle};
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+/// imphash 中会被去掉的DLL扩展名
+const IMPHASH_DLL_EXTENSIONS: [&str; 3] = ["ocx", "sys", "dll"];
+/// pefile 只对这些DLL查序号表，且要求带扩展名的全名，其余的序号导入写为 ord{序号}
+const IMPHASH_ORDINAL_DLLS: [&str; 3] = ["ws2_32.dll", "wsock32.dll", "oleaut32.dll"];
+
 pub struct ThreadPool {
     _max_threads: usize,
     _current_threads: usize,
@@ -87,10 +96,76 @@ pub fn calc_sha1(file_path: &PathBuf) -> String {
         Err(_e) => i18n::CALC_SHA1_FAILED.to_string(),
     }
 }
+fn calc_md5_hex(data: &[u8]) -> String {
+    Md5::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+/// Rich头哈希，即解密后从 "DanS" 到各项结尾数据的MD5
+pub fn calc_rich_header_hash(clear_data: &[u8]) -> String {
+    calc_md5_hex(clear_data)
+}
+/// 与 pefile 相同的imphash：按导入顺序把小写的 dll.function 用逗号连接后取MD5，
+/// 不包含延迟加载导入，没有导入时返回None
+pub fn calc_imphash(dlls: &[ImportDll]) -> Option<String> {
+    let mut import_strings = Vec::new();
+    for dll in dlls.iter().filter(|dll| dll.delay_load.is_none()) {
+        let dll_name = dll.name.to_lowercase();
+        let library = match dll_name.rsplit_once('.') {
+            Some((stem, extension)) if IMPHASH_DLL_EXTENSIONS.contains(&extension) => stem,
+            _ => &dll_name,
+        };
+        for function in &dll.function_info {
+            let function_name = match function.ordinal {
+                // 只使用内置的序号表，用户配置不影响哈希
+                Some(ordinal) => IMPHASH_ORDINAL_DLLS
+                    .contains(&dll_name.as_str())
+                    .then(|| ORDINAL_DATABASE.get_builtin_name(&dll_name, ordinal))
+                    .flatten()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("ord{}", ordinal)),
+                None => function.name.clone(),
+            };
+            if !function_name.is_empty() {
+                import_strings.push(format!("{}.{}", library, function_name.to_lowercase()));
+            }
+        }
+    }
+    if import_strings.is_empty() {
+        return None;
+    }
+    Some(calc_md5_hex(import_strings.join(",").as_bytes()))
+}
+/// 导出表哈希：按序号顺序把小写的导出名用逗号连接后取MD5，
+/// 只按序号导出的函数写为 ord{序号}，跳过函数数组中的空位，没有导出时返回None
+pub fn calc_exphash(exports: &[ExportInfo]) -> Option<String> {
+    let mut exports: Vec<&ExportInfo> = exports
+        .iter()
+        .filter(|export| !export.is_empty_slot())
+        .collect();
+    if exports.is_empty() {
+        return None;
+    }
+    exports.sort_by_key(|export| export.ordinal);
+    let export_strings: Vec<String> = exports
+        .iter()
+        .map(|export| {
+            if export.is_named() {
+                export.name.to_lowercase()
+            } else {
+                format!("ord{}", export.ordinal)
+            }
+        })
+        .collect();
+    Some(calc_md5_hex(export_strings.join(",").as_bytes()))
+}
 fn calc_hash(file_path: &PathBuf) {
     let hash_info = HashInfo {
         md5: calc_md5(file_path),
         sha1: calc_sha1(file_path),
+        imphash: None,
+        exphash: None,
         path: file_path.clone(),
     };
     if let Ok(mut guard) = GLOBAL_HASH_INFO.lock() {
@@ -117,3 +192,171 @@ pub fn get_hash_info(path: PathBuf) -> Option<HashInfo> {
 
     Some(hash_info_vec.swap_remove(index))
 }
+
+/// 计算Authenticode映像哈希，skip_ranges 为不参与哈希的 (文件偏移, 长度)，
+/// 即校验和字段、安全目录项和证书表
+pub fn calc_authenticode_hash(
+    file: &mut File,
+    skip_ranges: &[(u64, u64)],
+) -> anyhow::Result<AuthenticodeHash> {
+    let file_size = file.metadata()?.len();
+    let mut skip_ranges = skip_ranges.to_vec();
+    skip_ranges.sort();
+    let mut sha1 = Sha1::new();
+    let mut sha256 = Sha256::new();
+    let mut buffer = vec![0u8; 0x10000];
+    let mut position = 0u64;
+    for (start, length) in skip_ranges
+        .into_iter()
+        .chain(std::iter::once((file_size, 0)))
+    {
+        let start = start.min(file_size);
+        if start > position {
+            file.seek(SeekFrom::Start(position))?;
+            let mut remaining = start - position;
+            while remaining > 0 {
+                let size = remaining.min(buffer.len() as u64) as usize;
+                file.read_exact(&mut buffer[..size])?;
+                sha1.update(&buffer[..size]);
+                sha256.update(&buffer[..size]);
+                remaining -= size as u64;
+            }
+        }
+        position = position.max(start.saturating_add(length));
+    }
+    let to_hex =
+        |digest: &[u8]| -> String { digest.iter().map(|b| format!("{:02x}", b)).collect() };
+    Ok(AuthenticodeHash {
+        sha1: to_hex(&sha1.finalize()),
+        sha256: to_hex(&sha256.finalize()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::ImportFunction;
+
+    fn to_hex(digest: &[u8]) -> String {
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_authenticode_hash_skip_ranges() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(0x30000).collect();
+        let path = std::env::temp_dir().join("penguin_authenticode_skip_ranges.bin");
+        std::fs::write(&path, &data).unwrap();
+        // 乱序、重叠以及超出文件末尾的范围
+        let skip_ranges = [(0x2FFF0, 0x100), (0x40, 4), (0x100, 0x20), (0x110, 0x20)];
+        let hash = calc_authenticode_hash(&mut File::open(&path).unwrap(), &skip_ranges).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut expected = Sha256::new();
+        expected.update(&data[..0x40]);
+        expected.update(&data[0x44..0x100]);
+        expected.update(&data[0x130..0x2FFF0]);
+        assert_eq!(hash.sha256, to_hex(&expected.finalize()));
+    }
+
+    #[test]
+    fn test_authenticode_hash_matches_signature() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        let hash = file_info.get_authenticode_hash().unwrap();
+        assert_eq!(hash.sha1, "94f0a2a267deb73c7bb558ce972a513693e988f7");
+        assert_eq!(
+            hash.sha256,
+            "7c795ebe43a8a13dc1ff5e33f85ffad798f155c3d3f8f3294c266ba3be67dcd7"
+        );
+        let certificates = file_info.get_certificates().unwrap();
+        let certificates = certificates.0.borrow();
+        let signature = certificates[0].signature.as_ref().unwrap();
+        assert_eq!(signature.image_digest_matches, Some(true));
+
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api64.dll")).unwrap();
+        let hash = file_info.get_authenticode_hash().unwrap();
+        assert_eq!(
+            hash.sha256,
+            "ca944cb12a6080003af2dcc3d2a19eda95e03505b9afab113c3d7e0e86c7fcac"
+        );
+    }
+
+    #[test]
+    fn test_authenticode_hash_without_security_entry() {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        let optional_header =
+            u32::from_le_bytes(data[0x3C..0x40].try_into().unwrap()) as usize + 4 + 20;
+        // NumberOfRvaAndSizes 改为4，数据目录中不再有安全目录项
+        data[optional_header + 92..optional_header + 96].copy_from_slice(&4u32.to_le_bytes());
+        let path = std::env::temp_dir().join("penguin_authenticode_no_security.dll");
+        std::fs::write(&path, &data).unwrap();
+        let hash = FileInfo::new(path.clone())
+            .unwrap()
+            .get_authenticode_hash()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let checksum = optional_header + 64;
+        let mut expected = Sha256::new();
+        expected.update(&data[..checksum]);
+        expected.update(&data[checksum + 4..]);
+        assert_eq!(hash.sha256, to_hex(&expected.finalize()));
+    }
+
+    fn import_dll(name: &str, functions: &[(&str, Option<u16>)]) -> ImportDll {
+        ImportDll {
+            name: name.to_string(),
+            function_info: functions
+                .iter()
+                .map(|(name, ordinal)| ImportFunction {
+                    name: name.to_string(),
+                    ordinal: *ordinal,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_imphash_ordinal_lookup() {
+        // 序号导入的名称来自用户数据库时不应影响imphash
+        let dlls = [
+            import_dll(
+                "OLEAUT32.dll",
+                &[("", Some(6)), ("UserName", Some(200)), ("", Some(500))],
+            ),
+            import_dll("ws2_32.dll", &[("", Some(115))]),
+            import_dll("WSOCK32.DLL", &[("", Some(116))]),
+            // 不带扩展名时 pefile 不会查序号表
+            import_dll("WS2_32", &[("WSAStartup", Some(115))]),
+            import_dll("mfc42.dll", &[("??1CString@@QAE@XZ", Some(800))]),
+            import_dll("KERNEL32.dll", &[("GetProcAddress", None)]),
+        ];
+        assert_eq!(
+            calc_imphash(&dlls).as_deref(),
+            Some("fb0d82ad15b28ef703d1e56ce2e2da88")
+        );
+        assert_eq!(
+            calc_imphash(&[import_dll("oleaut32.dll", &[("", Some(411))])]),
+            calc_imphash(&[import_dll(
+                "oleaut32.dll",
+                &[("SafeArrayCreateVector", None)]
+            )])
+        );
+        assert_eq!(calc_imphash(&[]), None);
+    }
+
+    #[test]
+    fn test_imphash_exphash_steam_api() {
+        let mut file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        assert_eq!(
+            file_info.get_imphash().as_deref(),
+            Some("96452bf7bf319c4122ec00a5fbb0a955")
+        );
+        assert_eq!(
+            file_info.get_exphash().as_deref(),
+            Some("5d18091c65344bd236d1c2b4a07f95ce")
+        );
+    }
+}
@@ -2,13 +2,34 @@ use crate::tools_api::read_file::nt_header::traits::NtHeaders;
 use serde_derive::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::rc::Rc;
+pub(crate) mod anomaly;
+mod archive;
+mod bound_import;
+mod certificate;
+mod chpe;
+mod coff_object;
+mod debug;
+mod delay_import;
+mod der;
 mod dos_header;
 mod dos_stub;
+mod dotnet;
+mod exception;
 mod export;
 mod import;
+mod load_config;
+mod manifest;
 pub mod nt_header;
+mod overlay;
+mod relocation;
 mod resource_header;
+mod resource_strings;
+mod rich_header;
 pub mod section_headers;
+pub(crate) mod symbol;
+mod tls;
+mod ui_resources;
+mod version_info;
 
 #[repr(C)]
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
@@ -31,7 +52,7 @@ pub struct ImageDosHeader {
     pub(crate) e_oemid: u16,      // OEM标识符(相对m_oeminfo)
     pub(crate) e_oeminfo: u16,    // OEM信息
     pub(crate) e_res2: [u16; 10], // 保留字
-    pub(crate) e_lfanew: u16,     // NT头相对于文件的偏移地址
+    pub(crate) e_lfanew: u32,     // NT头相对于文件的偏移地址
 }
 
 /// 存根内容
@@ -40,6 +61,31 @@ pub struct ImageDosStub {
     pub buffer: Vec<u8>,
 }
 
+/// Rich头，位于DOS存根与NT头之间，记录链接时用到的各个编译工具
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct RichHeader {
+    /// "DanS" 标记的文件偏移
+    pub file_offset: u32,
+    /// 从 "DanS" 到 "Rich" 结尾的大小
+    pub size: u32,
+    pub xor_key: u32,
+    /// 按DOS头、DOS存根与各项重新计算的校验和，未被篡改时等于异或密钥
+    pub checksum: u32,
+    pub checksum_valid: bool,
+    pub entries: Vec<RichEntry>,
+    /// 解密后数据的MD5，用于样本聚类
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct RichEntry {
+    pub product_id: u16,
+    pub build: u16,
+    pub count: u32,
+    pub product_name: String,
+    pub vs_version: String,
+}
+
 /// image_file_header 位于nt头中
 #[repr(C)]
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
@@ -182,6 +228,11 @@ pub struct ImageNtHeaders64 {
     pub(crate) file_header: ImageFileHeader,
     pub(crate) optional_header: ImageOptionalHeader64,
 }
+/// 不含DOS头与可选头的COFF目标文件头（.obj）
+#[derive(Serialize, Deserialize, Default, Clone, Debug, Eq, PartialEq)]
+pub struct ImageCoffHeader {
+    pub(crate) file_header: ImageFileHeader,
+}
 #[repr(C)]
 #[derive(Serialize, Deserialize, Default, Clone, Debug, Eq, PartialEq)]
 pub struct ImageNtHeaders {
@@ -192,7 +243,12 @@ pub struct ImageNtHeaders {
 
 type SectionCharAddr = u64;
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
-pub struct ImageSectionHeaders(pub(crate) Vec<ImageSectionHeader>, Vec<SectionCharAddr>);
+/// 第三项为解析后的节名称，`/123` 形式的长名称已从字符串表中读出
+pub struct ImageSectionHeaders(
+    pub(crate) Vec<ImageSectionHeader>,
+    Vec<SectionCharAddr>,
+    Vec<String>,
+);
 
 // 为序列化创建包装结构体
 #[derive(Serialize, Deserialize)]
@@ -253,11 +309,15 @@ pub fn rva_2_fo<T>(nt_head: &T, section_heads: &ImageSectionHeaders, rva: u32) -
 where
     T: NtHeaders + ?Sized,
 {
-    for i in 0..nt_head.section_number() {
-        let start = section_heads.get_section_virtual_address(i as usize);
-        let end = section_heads.get_virtual_rva_end(i as usize);
+    // 节表被截断时实际读取到的节可能少于 NumberOfSections
+    let section_number = section_heads.0.len().min(nt_head.section_number() as usize);
+    for i in 0..section_number {
+        let start = section_heads.get_section_virtual_address(i);
+        let end = section_heads.get_virtual_rva_end(i);
         if rva >= start && rva < end {
-            return Some(section_heads.get_section_pointer_to_raw_data(i as usize) + (rva - start));
+            return section_heads
+                .get_section_pointer_to_raw_data(i)
+                .checked_add(rva - start);
         }
     }
     None
@@ -281,7 +341,7 @@ pub struct ExportDir {
     pub(crate) address_of_name_ordinals: u32,
 }
 /// ExportInfo 用于转递给egui
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
 pub struct ExportInfo {
     pub name_rva: u32,
     pub name_string_fo: u32,
@@ -290,7 +350,14 @@ pub struct ExportInfo {
     pub function_address: u32,
     pub function: u32,
     pub ordinals_address: u32,
+    /// 函数在 AddressOfFunctions 中的下标
     pub ordinals: u16,
+    /// 导出序号，即 Base + 下标
+    pub ordinal: u32,
+    /// 函数RVA所在的节
+    pub section: String,
+    /// 函数RVA位于导出目录内时为转发字符串，例如 NTDLL.RtlAllocateHeap
+    pub forwarder: Option<String>,
 }
 /// ExportInfos 用于传递egui
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
@@ -345,6 +412,365 @@ pub struct ResourceTree {
     pub(crate) data_address: u32,
     pub(crate) size: u32,
 }
+
+/// VS_FIXEDFILEINFO
+#[repr(C)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct VsFixedFileInfo {
+    pub signature: u32,
+    pub struc_version: u32,
+    pub file_version_ms: u32,
+    pub file_version_ls: u32,
+    pub product_version_ms: u32,
+    pub product_version_ls: u32,
+    pub file_flags_mask: u32,
+    pub file_flags: u32,
+    pub file_os: u32,
+    pub file_type: u32,
+    pub file_subtype: u32,
+    pub file_date_ms: u32,
+    pub file_date_ls: u32,
+}
+
+/// RT_VERSION 资源 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct VersionInfo {
+    pub fixed_file_info: Option<VsFixedFileInfo>,
+    pub string_tables: Vec<VersionStringTable>,
+    /// VarFileInfo 中的 Translation
+    pub translations: Vec<VersionTranslation>,
+}
+
+/// StringFileInfo 下的一个 StringTable，按语言与代码页区分
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct VersionStringTable {
+    pub language: u16,
+    pub code_page: u16,
+    pub strings: Vec<VersionString>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct VersionString {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct VersionTranslation {
+    pub language: u16,
+    pub code_page: u16,
+}
+
+/// RT_MANIFEST 资源 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ManifestInfo {
+    /// 资源名称，ID_1 用于进程，ID_2 用于DLL隔离
+    pub resource_name: String,
+    pub requested_execution_level: String,
+    pub ui_access: String,
+    pub dependencies: Vec<ManifestDependency>,
+    pub dpi_aware: String,
+    pub dpi_awareness: String,
+    pub supported_os: Vec<SupportedOs>,
+    pub xml: String,
+}
+
+/// dependentAssembly 中的 assemblyIdentity
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ManifestDependency {
+    pub name: String,
+    pub version: String,
+    pub assembly_type: String,
+    pub processor_architecture: String,
+    pub public_key_token: String,
+    pub language: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct SupportedOs {
+    pub id: String,
+    pub name: String,
+}
+
+/// RT_STRING 与 RT_MESSAGETABLE 资源 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ResourceStrings {
+    pub string_tables: Vec<ResourceStringTable>,
+    pub message_tables: Vec<ResourceStringTable>,
+}
+
+/// 同一语言下按ID排序的字符串
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ResourceStringTable {
+    pub language: u16,
+    pub strings: Vec<ResourceString>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ResourceString {
+    pub id: u32,
+    pub text: String,
+}
+
+/// RT_DIALOG、RT_MENU 与 RT_ACCELERATOR 资源 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct UiResources {
+    pub dialogs: Vec<DialogResource>,
+    pub menus: Vec<MenuResource>,
+    pub accelerators: Vec<AcceleratorTable>,
+}
+
+/// DLGTEMPLATE 或 DLGTEMPLATEEX
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct DialogResource {
+    pub resource_name: String,
+    pub language: u16,
+    pub extended: bool,
+    pub style: u32,
+    pub ex_style: u32,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+    pub menu: String,
+    pub class: String,
+    pub title: String,
+    pub font: String,
+    pub controls: Vec<DialogControl>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct DialogControl {
+    pub id: u32,
+    pub class: String,
+    pub text: String,
+    pub style: u32,
+    pub ex_style: u32,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+}
+
+/// MENU 或 MENUEX
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MenuResource {
+    pub resource_name: String,
+    pub language: u16,
+    pub extended: bool,
+    pub items: Vec<MenuItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MenuItem {
+    pub id: u32,
+    pub text: String,
+    /// MENU 中为 MF_* 标志，MENUEX 中为 MFT_* 类型
+    pub flags: u32,
+    /// MENUEX 中的 MFS_* 状态
+    pub state: u32,
+    pub children: Vec<MenuItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct AcceleratorTable {
+    pub resource_name: String,
+    pub language: u16,
+    pub entries: Vec<Accelerator>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct Accelerator {
+    pub flags: u16,
+    pub key: u16,
+    pub id: u16,
+    /// 形如 Ctrl+Shift+O 的按键组合
+    pub key_name: String,
+}
+
+/// COFF符号表与其后的字符串表
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CoffSymbolTable {
+    pub pointer_to_symbol_table: u32,
+    pub number_of_symbols: u32,
+    pub string_table_size: u32,
+    pub symbols: Vec<CoffSymbol>,
+}
+
+/// IMAGE_SYMBOL，index 为符号在表中的下标（辅助记录也占用下标）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CoffSymbol {
+    pub index: u32,
+    pub name: String,
+    pub value: u32,
+    /// 大于0为节序号（从1开始），0为未定义，-1为绝对符号，-2为调试符号
+    pub section_number: i16,
+    pub symbol_type: u16,
+    pub storage_class: u8,
+    pub aux_symbols: Vec<CoffAuxSymbol>,
+}
+
+/// 跟随在符号之后的辅助记录
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum CoffAuxSymbol {
+    FunctionDefinition {
+        tag_index: u32,
+        total_size: u32,
+        pointer_to_line_number: u32,
+        pointer_to_next_function: u32,
+    },
+    /// .bf 与 .ef 符号
+    FunctionLine {
+        line_number: u16,
+        pointer_to_next_function: u32,
+    },
+    WeakExternal {
+        tag_index: u32,
+        characteristics: u32,
+    },
+    File {
+        file_name: String,
+    },
+    SectionDefinition {
+        length: u32,
+        number_of_relocations: u16,
+        number_of_line_numbers: u16,
+        check_sum: u32,
+        number: u16,
+        selection: u8,
+    },
+    Unknown {
+        data: Vec<u8>,
+    },
+}
+
+/// 节的COFF重定位项与COMDAT信息，主要存在于目标文件中
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CoffSectionDetail {
+    pub relocations: Vec<CoffRelocation>,
+    pub comdat: Option<ComdatInfo>,
+}
+
+/// IMAGE_RELOCATION，virtual_address 为相对节开头的偏移
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CoffRelocation {
+    pub virtual_address: u32,
+    pub symbol_table_index: u32,
+    pub relocation_type: u16,
+    /// 重定位类型名称，取决于机器类型
+    pub type_name: String,
+    pub symbol_name: String,
+}
+
+/// 节定义辅助记录中的COMDAT信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ComdatInfo {
+    pub selection: u8,
+    /// 节符号之后第一个属于该节的符号
+    pub symbol_name: String,
+    /// 选择方式为 ASSOCIATIVE 时关联的节序号（从1开始）
+    pub associated_section: Option<u16>,
+}
+
+/// ar格式的静态库或导入库（.lib/.a）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ArchiveInfo {
+    pub members: Vec<ArchiveMember>,
+    /// 链接器成员中的符号索引
+    pub symbols: Vec<ArchiveSymbol>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ArchiveMember {
+    /// 已从长名称成员中解析出的名称
+    pub name: String,
+    pub header_offset: u64,
+    pub data_offset: u64,
+    pub size: u64,
+    pub date: u64,
+    pub mode: String,
+    pub kind: ArchiveMemberKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub enum ArchiveMemberKind {
+    FirstLinkerMember,
+    SecondLinkerMember,
+    LongNames,
+    CoffObject,
+    /// 导入库中的短格式导入项（IMPORT_OBJECT_HEADER）
+    ImportObject(ImportObjectInfo),
+    #[default]
+    Unknown,
+}
+
+/// IMPORT_OBJECT_HEADER 及其后的符号名与DLL名
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ImportObjectInfo {
+    pub machine: u16,
+    pub time_date_stamp: u32,
+    pub size_of_data: u32,
+    pub ordinal_or_hint: u16,
+    /// 0为CODE，1为DATA，2为CONST
+    pub import_type: u8,
+    /// 决定实际导入名称的方式
+    pub name_type: u8,
+    pub symbol_name: String,
+    pub dll_name: String,
+    /// 仅 NAME_EXPORTAS 类型存在
+    pub export_name: Option<String>,
+}
+
+/// 链接器成员中的一个符号及其所在成员的下标
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ArchiveSymbol {
+    pub name: String,
+    pub member_index: Option<usize>,
+}
+
+/// 映像结束位置之后的附加数据（overlay），不包含证书表
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OverlayInfo {
+    /// 所有节 pointer_to_raw_data + size_of_raw_data 的最大值
+    pub image_end: u64,
+    pub certificate_offset: u64,
+    pub certificate_size: u64,
+    /// 证书表前后的数据分别作为一段
+    pub regions: Vec<OverlayRegion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OverlayRegion {
+    pub offset: u64,
+    pub size: u64,
+    /// 香农熵，范围为0到8
+    pub entropy: f64,
+    /// 根据文件头识别的扩展名，例如 .zip
+    pub file_type: Option<String>,
+}
+
+/// 畸形结构的严重程度
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum AnomalySeverity {
+    /// 不影响加载，但正常编译器不会生成
+    #[default]
+    Info,
+    /// 加载器会容忍或修正，常见于加壳与恶意文件
+    Warning,
+    /// 结构损坏，相关内容无法完整解析
+    Error,
+}
+
+/// 解析过程中发现的畸形结构
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct Anomaly {
+    /// 出现问题的字段或结构名称
+    pub field: String,
+    /// 字段所在的文件偏移
+    pub offset: u64,
+    pub severity: AnomalySeverity,
+    pub description: String,
+}
 #[repr(C)]
 #[derive(Default, Debug)]
 pub struct ImportDescriptor {
@@ -365,6 +791,73 @@ pub struct ImportDll {
     pub(crate) first_thunk: u32,
     pub(crate) function_info: Vec<ImportFunction>,
     pub(crate) function_size: u32,
+    /// 延迟加载的DLL才有该信息
+    pub(crate) delay_load: Option<DelayImportInfo>,
+    /// 绑定导入目录中对应的项
+    pub(crate) bound_import: Option<BoundImportInfo>,
+    /// 绑定的时间戳与样本同目录下的DLL不一致，加载器会重新解析导入
+    pub(crate) stale_binding: bool,
+}
+
+/// 绑定导入描述符，名称偏移相对于绑定导入目录起始
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageBoundImportDescriptor {
+    pub(crate) time_date_stamp: u32,
+    pub(crate) offset_module_name: u16,
+    pub(crate) number_of_module_forwarder_refs: u16,
+}
+
+/// 绑定导入的转发引用，紧跟在所属描述符之后
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageBoundForwarderRef {
+    pub(crate) time_date_stamp: u32,
+    pub(crate) offset_module_name: u16,
+    pub(crate) reserved: u16,
+}
+
+/// 绑定导入信息 用于传递egui
+#[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq, Clone)]
+pub struct BoundImportInfo {
+    pub time_date_stamp: u32,
+    pub module_name: String,
+    pub forwarder_refs: Vec<BoundForwarderRef>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq, Clone)]
+pub struct BoundForwarderRef {
+    pub time_date_stamp: u32,
+    pub module_name: String,
+}
+
+/// 延迟加载描述符 ImgDelayDescr
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageDelayLoadDescriptor {
+    pub(crate) attributes: u32,
+    pub(crate) dll_name_rva: u32,
+    pub(crate) module_handle_rva: u32,
+    pub(crate) import_address_table_rva: u32,
+    pub(crate) import_name_table_rva: u32,
+    pub(crate) bound_import_address_table_rva: u32,
+    pub(crate) unload_information_table_rva: u32,
+    pub(crate) time_date_stamp: u32,
+}
+
+/// 延迟加载信息，旧格式中的VA已统一转换为RVA
+#[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq, Clone)]
+pub struct DelayImportInfo {
+    pub attributes: u32,
+    /// attributes 未设置 dlattrRva 时为旧的VA格式
+    pub is_rva_based: bool,
+    pub dll_name_rva: u32,
+    pub module_handle_rva: u32,
+    pub import_address_table_rva: u32,
+    pub import_name_table_rva: u32,
+    pub bound_import_address_table_rva: u32,
+    pub unload_information_table_rva: u32,
+    pub time_date_stamp: u32,
 }
 
 #[derive(Default, Eq, PartialEq)]
@@ -390,7 +883,711 @@ pub struct ImportFunction {
     pub(crate) name_address: u32,
     pub(crate) name_length: u32,
     pub(crate) name_max_length: u32,
+    /// 按名称导入时为函数名，按序号导入时为从序号数据库查到的名称，查不到时为空
     pub(crate) name: String,
+    /// 按序号导入时的序号
+    pub(crate) ordinal: Option<u16>,
+}
+
+/// 基址重定位块头
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageBaseRelocation {
+    pub(crate) virtual_address: u32, // 当前块对应页面的RVA
+    pub(crate) size_of_block: u32,   // 块大小，包含块头及其后的所有重定位项
+}
+
+/// 重定位块 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct RelocationBlock {
+    pub page_rva: u32,
+    pub size_of_block: u32,
+    /// 块头所在文件偏移
+    pub file_offset: u32,
+    /// 页面所在节的名称
+    pub section_name: String,
+    pub entries: Vec<RelocationEntry>,
+}
+
+/// 重定位项 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct RelocationEntry {
+    pub page_rva: u32,
+    /// 高4位，IMAGE_REL_BASED_*
+    pub reloc_type: u8,
+    /// 低12位，页内偏移
+    pub offset: u16,
+    pub target_rva: u32,
+    /// 目标RVA不在任何节中时为None
+    pub file_offset: Option<u32>,
+    /// HIGHADJ 的参数，即调整值的低16位，占用紧随其后的槽
+    pub parameter: Option<u16>,
+}
+
+/// RelocationTable 用于传递egui
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RelocationTable(pub(crate) Rc<RefCell<Vec<RelocationBlock>>>);
+
+// 为序列化创建包装结构体
+#[derive(Serialize, Deserialize)]
+pub struct SerializableRelocationTable {
+    pub blocks: Vec<RelocationBlock>,
+}
+
+impl RelocationTable {
+    pub fn to_serializable(&self) -> SerializableRelocationTable {
+        SerializableRelocationTable {
+            blocks: self.0.borrow().clone(),
+        }
+    }
+}
+
+/// 32位TLS目录，地址字段均为VA
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageTlsDirectory {
+    pub(crate) start_address_of_raw_data: u32,
+    pub(crate) end_address_of_raw_data: u32,
+    pub(crate) address_of_index: u32,
+    pub(crate) address_of_call_backs: u32,
+    pub(crate) size_of_zero_fill: u32,
+    pub(crate) characteristics: u32,
+}
+
+/// 64位TLS目录，地址字段均为VA
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageTlsDirectory64 {
+    pub(crate) start_address_of_raw_data: u64,
+    pub(crate) end_address_of_raw_data: u64,
+    pub(crate) address_of_index: u64,
+    pub(crate) address_of_call_backs: u64,
+    pub(crate) size_of_zero_fill: u32,
+    pub(crate) characteristics: u32,
+}
+
+/// TLS信息 用于传递egui，32位与64位统一为u64
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct TlsInfo {
+    /// TLS目录所在文件偏移
+    pub file_offset: u32,
+    pub start_address_of_raw_data: u64,
+    pub end_address_of_raw_data: u64,
+    pub address_of_index: u64,
+    pub address_of_call_backs: u64,
+    pub size_of_zero_fill: u32,
+    pub characteristics: u32,
+    pub callbacks: Vec<TlsCallback>,
+}
+
+/// TLS回调函数
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct TlsCallback {
+    pub virtual_address: u64,
+    pub rva: u32,
+    /// 回调不在任何节中时为None
+    pub file_offset: Option<u32>,
+    /// 回调所在节的名称
+    pub section_name: String,
+}
+
+/// 加载配置中的代码完整性信息
+#[repr(C)]
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ImageLoadConfigCodeIntegrity {
+    pub(crate) flags: u16,
+    pub(crate) catalog: u16,
+    pub(crate) catalog_offset: u32,
+    pub(crate) reserved: u32,
+}
+
+/// 32位加载配置目录，实际长度由首字段size决定，旧链接器生成的结构只包含前面一部分字段
+#[repr(C)]
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ImageLoadConfigDirectory {
+    pub(crate) size: u32,
+    pub(crate) time_date_stamp: u32,
+    pub(crate) major_version: u16,
+    pub(crate) minor_version: u16,
+    pub(crate) global_flags_clear: u32,
+    pub(crate) global_flags_set: u32,
+    pub(crate) critical_section_default_timeout: u32,
+    pub(crate) de_commit_free_block_threshold: u32,
+    pub(crate) de_commit_total_free_threshold: u32,
+    pub(crate) lock_prefix_table: u32,
+    pub(crate) maximum_allocation_size: u32,
+    pub(crate) virtual_memory_threshold: u32,
+    pub(crate) process_heap_flags: u32,
+    pub(crate) process_affinity_mask: u32,
+    pub(crate) csd_version: u16,
+    pub(crate) dependent_load_flags: u16,
+    pub(crate) edit_list: u32,
+    pub(crate) security_cookie: u32,
+    pub(crate) se_handler_table: u32,
+    pub(crate) se_handler_count: u32,
+    pub(crate) guard_cf_check_function_pointer: u32,
+    pub(crate) guard_cf_dispatch_function_pointer: u32,
+    pub(crate) guard_cf_function_table: u32,
+    pub(crate) guard_cf_function_count: u32,
+    pub(crate) guard_flags: u32,
+    pub(crate) code_integrity: ImageLoadConfigCodeIntegrity,
+    pub(crate) guard_address_taken_iat_entry_table: u32,
+    pub(crate) guard_address_taken_iat_entry_count: u32,
+    pub(crate) guard_long_jump_target_table: u32,
+    pub(crate) guard_long_jump_target_count: u32,
+    pub(crate) dynamic_value_reloc_table: u32,
+    pub(crate) chpe_metadata_pointer: u32,
+    pub(crate) guard_rf_failure_routine: u32,
+    pub(crate) guard_rf_failure_routine_function_pointer: u32,
+    pub(crate) dynamic_value_reloc_table_offset: u32,
+    pub(crate) dynamic_value_reloc_table_section: u16,
+    pub(crate) reserved2: u16,
+    pub(crate) guard_rf_verify_stack_pointer_function_pointer: u32,
+    pub(crate) hot_patch_table_offset: u32,
+    pub(crate) reserved3: u32,
+    pub(crate) enclave_configuration_pointer: u32,
+    pub(crate) volatile_metadata_pointer: u32,
+    pub(crate) guard_eh_continuation_table: u32,
+    pub(crate) guard_eh_continuation_count: u32,
+    pub(crate) guard_xfg_check_function_pointer: u32,
+    pub(crate) guard_xfg_dispatch_function_pointer: u32,
+    pub(crate) guard_xfg_table_dispatch_function_pointer: u32,
+    pub(crate) cast_guard_os_determined_failure_mode: u32,
+    pub(crate) guard_memcpy_function_pointer: u32,
+}
+
+/// 64位加载配置目录，注意 process_affinity_mask 与 process_heap_flags 的顺序与32位相反
+#[repr(C)]
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ImageLoadConfigDirectory64 {
+    pub(crate) size: u32,
+    pub(crate) time_date_stamp: u32,
+    pub(crate) major_version: u16,
+    pub(crate) minor_version: u16,
+    pub(crate) global_flags_clear: u32,
+    pub(crate) global_flags_set: u32,
+    pub(crate) critical_section_default_timeout: u32,
+    pub(crate) de_commit_free_block_threshold: u64,
+    pub(crate) de_commit_total_free_threshold: u64,
+    pub(crate) lock_prefix_table: u64,
+    pub(crate) maximum_allocation_size: u64,
+    pub(crate) virtual_memory_threshold: u64,
+    pub(crate) process_affinity_mask: u64,
+    pub(crate) process_heap_flags: u32,
+    pub(crate) csd_version: u16,
+    pub(crate) dependent_load_flags: u16,
+    pub(crate) edit_list: u64,
+    pub(crate) security_cookie: u64,
+    pub(crate) se_handler_table: u64,
+    pub(crate) se_handler_count: u64,
+    pub(crate) guard_cf_check_function_pointer: u64,
+    pub(crate) guard_cf_dispatch_function_pointer: u64,
+    pub(crate) guard_cf_function_table: u64,
+    pub(crate) guard_cf_function_count: u64,
+    pub(crate) guard_flags: u32,
+    pub(crate) code_integrity: ImageLoadConfigCodeIntegrity,
+    pub(crate) guard_address_taken_iat_entry_table: u64,
+    pub(crate) guard_address_taken_iat_entry_count: u64,
+    pub(crate) guard_long_jump_target_table: u64,
+    pub(crate) guard_long_jump_target_count: u64,
+    pub(crate) dynamic_value_reloc_table: u64,
+    pub(crate) chpe_metadata_pointer: u64,
+    pub(crate) guard_rf_failure_routine: u64,
+    pub(crate) guard_rf_failure_routine_function_pointer: u64,
+    pub(crate) dynamic_value_reloc_table_offset: u32,
+    pub(crate) dynamic_value_reloc_table_section: u16,
+    pub(crate) reserved2: u16,
+    pub(crate) guard_rf_verify_stack_pointer_function_pointer: u64,
+    pub(crate) hot_patch_table_offset: u32,
+    pub(crate) reserved3: u32,
+    pub(crate) enclave_configuration_pointer: u64,
+    pub(crate) volatile_metadata_pointer: u64,
+    pub(crate) guard_eh_continuation_table: u64,
+    pub(crate) guard_eh_continuation_count: u64,
+    pub(crate) guard_xfg_check_function_pointer: u64,
+    pub(crate) guard_xfg_dispatch_function_pointer: u64,
+    pub(crate) guard_xfg_table_dispatch_function_pointer: u64,
+    pub(crate) cast_guard_os_determined_failure_mode: u64,
+    pub(crate) guard_memcpy_function_pointer: u64,
+}
+
+/// 加载配置信息 用于传递egui，32位与64位统一为u64，超出size的字段为0
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct LoadConfigInfo {
+    /// 加载配置目录所在文件偏移
+    pub file_offset: u32,
+    pub size: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub global_flags_clear: u32,
+    pub global_flags_set: u32,
+    pub critical_section_default_timeout: u32,
+    pub process_heap_flags: u32,
+    pub csd_version: u16,
+    pub dependent_load_flags: u16,
+    pub lock_prefix_table: u64,
+    pub edit_list: u64,
+    pub security_cookie: u64,
+    pub se_handler_table: u64,
+    pub se_handler_count: u64,
+    pub guard_cf_check_function_pointer: u64,
+    pub guard_cf_dispatch_function_pointer: u64,
+    pub guard_cf_function_table: u64,
+    pub guard_cf_function_count: u64,
+    pub guard_flags: u32,
+    pub guard_address_taken_iat_entry_table: u64,
+    pub guard_address_taken_iat_entry_count: u64,
+    pub guard_long_jump_target_table: u64,
+    pub guard_long_jump_target_count: u64,
+    pub dynamic_value_reloc_table: u64,
+    pub dynamic_value_reloc_table_offset: u32,
+    pub dynamic_value_reloc_table_section: u16,
+    pub chpe_metadata_pointer: u64,
+    pub volatile_metadata_pointer: u64,
+    pub guard_eh_continuation_table: u64,
+    pub guard_eh_continuation_count: u64,
+    /// SafeSEH 处理函数RVA列表，仅32位有效
+    pub se_handlers: Vec<u32>,
+    pub guard_cf_functions: Vec<GuardTableEntry>,
+    pub guard_address_taken_iat_entries: Vec<GuardTableEntry>,
+    pub guard_long_jump_targets: Vec<GuardTableEntry>,
+    pub guard_eh_continuations: Vec<GuardTableEntry>,
+    /// CHPEMetadataPointer 指向的混合映像元数据
+    pub chpe_metadata: Option<ChpeMetadata>,
+}
+
+/// CFG相关表中的一项，RVA后跟随 GuardFlags 高4位指定字节数的附加信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct GuardTableEntry {
+    pub rva: u32,
+    pub flags: u8,
+}
+
+/// 混合映像元数据，x86映像为 IMAGE_CHPE_METADATA_X86，其余为 IMAGE_ARM64EC_METADATA
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChpeMetadata {
+    pub file_offset: u32,
+    pub is_x86: bool,
+    /// 按结构体顺序排列的字段值，第一个为 Version
+    pub fields: Vec<u32>,
+    pub code_ranges: Vec<ChpeCodeRange>,
+    /// ARM64EC 代码范围到入口点的映射
+    pub entry_points: Vec<ChpeEntryPoint>,
+    /// ARM64EC 重定向的函数
+    pub redirections: Vec<ChpeRedirection>,
+}
+
+/// 代码映射中的一段连续代码
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChpeCodeRange {
+    pub start_rva: u32,
+    pub length: u32,
+    pub code_type: ChpeCodeType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ChpeCodeType {
+    #[default]
+    Arm64,
+    Arm64Ec,
+    Amd64,
+    X86,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChpeEntryPoint {
+    pub start_rva: u32,
+    pub end_rva: u32,
+    pub entry_point: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChpeRedirection {
+    pub source: u32,
+    pub destination: u32,
+}
+
+/// 调试目录项
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct ImageDebugDirectory {
+    pub(crate) characteristics: u32,
+    pub(crate) time_date_stamp: u32,
+    pub(crate) major_version: u16,
+    pub(crate) minor_version: u16,
+    pub(crate) debug_type: u32,
+    pub(crate) size_of_data: u32,
+    pub(crate) address_of_raw_data: u32,
+    pub(crate) pointer_to_raw_data: u32,
+}
+
+/// 调试目录项及其解码后的数据
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct DebugEntry {
+    /// 目录项所在文件偏移
+    pub file_offset: u32,
+    pub characteristics: u32,
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub debug_type: u32,
+    pub size_of_data: u32,
+    pub address_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    pub data: DebugData,
+}
+
+/// 按调试类型解码后的数据
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub enum DebugData {
+    CodeView(CodeViewInfo),
+    Pogo(PogoInfo),
+    VcFeature(VcFeatureInfo),
+    Repro(ReproInfo),
+    ExDllCharacteristics(u32),
+    /// 未解码或数据无法读取
+    #[default]
+    Unknown,
+}
+
+/// CodeView 调试信息，RSDS 使用GUID，NB10 使用时间戳作为签名
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CodeViewInfo {
+    pub signature: String,
+    /// RSDS 的 GUID，格式为 XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX
+    pub guid: String,
+    /// NB10 的时间戳签名
+    pub nb10_signature: u32,
+    pub age: u32,
+    pub pdb_path: String,
+}
+
+/// POGO(PGO/LTCG) 记录的各子节信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct PogoInfo {
+    pub signature: String,
+    pub entries: Vec<PogoEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct PogoEntry {
+    pub rva: u32,
+    pub size: u32,
+    pub name: String,
+}
+
+/// VC_FEATURE 记录的编译选项统计
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct VcFeatureInfo {
+    pub pre_vc11: u32,
+    pub c_cpp: u32,
+    pub gs: u32,
+    pub sdl: u32,
+    pub guard_n: u32,
+}
+
+/// REPRO 确定性构建哈希，旧版链接器不带哈希数据
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ReproInfo {
+    pub hash: String,
+}
+
+/// DebugTable 用于传递egui
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct DebugTable(pub(crate) Rc<RefCell<Vec<DebugEntry>>>);
+
+// 为序列化创建包装结构体
+#[derive(Serialize, Deserialize)]
+pub struct SerializableDebugTable {
+    pub entries: Vec<DebugEntry>,
+}
+
+impl DebugTable {
+    pub fn to_serializable(&self) -> SerializableDebugTable {
+        SerializableDebugTable {
+            entries: self.0.borrow().clone(),
+        }
+    }
+}
+
+/// x64 RUNTIME_FUNCTION，地址均为RVA
+#[repr(C)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+pub struct ImageRuntimeFunction {
+    pub begin_address: u32,
+    pub end_address: u32,
+    pub unwind_info_address: u32,
+}
+
+/// 函数边界及其展开信息 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct RuntimeFunction {
+    pub begin_address: u32,
+    pub end_address: u32,
+    pub unwind_info_address: u32,
+    pub section_name: String,
+    /// 展开信息无法读取时为None
+    pub unwind_info: Option<UnwindInfo>,
+}
+
+/// UNWIND_INFO
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnwindInfo {
+    pub version: u8,
+    pub flags: u8,
+    pub size_of_prolog: u8,
+    pub count_of_codes: u8,
+    pub frame_register: u8,
+    pub frame_offset: u8,
+    pub unwind_codes: Vec<UnwindCode>,
+    /// UNW_FLAG_EHANDLER 或 UNW_FLAG_UHANDLER 时的异常处理函数RVA
+    pub handler_rva: Option<u32>,
+    /// UNW_FLAG_CHAININFO 时链接到的上一个函数项
+    pub chained_function: Option<ImageRuntimeFunction>,
+}
+
+/// 展开代码，operand 为按操作码解码后的栈大小或偏移
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnwindCode {
+    pub code_offset: u8,
+    pub unwind_op: u8,
+    pub op_info: u8,
+    pub operand: u32,
+}
+
+/// ExceptionTable 用于传递egui
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ExceptionTable(pub(crate) Rc<RefCell<Vec<RuntimeFunction>>>);
+
+// 为序列化创建包装结构体
+#[derive(Serialize, Deserialize)]
+pub struct SerializableExceptionTable {
+    pub functions: Vec<RuntimeFunction>,
+}
+
+impl ExceptionTable {
+    pub fn to_serializable(&self) -> SerializableExceptionTable {
+        SerializableExceptionTable {
+            functions: self.0.borrow().clone(),
+        }
+    }
+}
+
+/// WIN_CERTIFICATE 头部，后面紧跟证书数据
+#[repr(C)]
+#[derive(Default, Debug, Eq, PartialEq)]
+pub(crate) struct WinCertificateHeader {
+    pub(crate) length: u32,
+    pub(crate) revision: u16,
+    pub(crate) certificate_type: u16,
+}
+
+/// 证书表中的一项
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct WinCertificate {
+    pub file_offset: u32,
+    pub length: u32,
+    pub revision: u16,
+    pub certificate_type: u16,
+    /// PKCS#7 SignedData 解码失败或不是 PKCS_SIGNED_DATA 时为None
+    pub signature: Option<AuthenticodeSignature>,
+}
+
+/// Authenticode 签名，即 PKCS#7 SignedData
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct AuthenticodeSignature {
+    pub digest_algorithm: String,
+    pub content_type: String,
+    /// SpcIndirectDataContent 中对PE映像计算的摘要
+    pub image_digest_algorithm: String,
+    pub image_digest: String,
+    /// 与重新计算的映像哈希比较的结果，摘要算法不是SHA-1或SHA-256时为None
+    pub image_digest_matches: Option<bool>,
+    pub signers: Vec<SignerInfo>,
+    pub certificates: Vec<CertificateInfo>,
+    /// 双签名时附加在未认证属性中的签名
+    pub nested_signatures: Vec<AuthenticodeSignature>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct SignerInfo {
+    /// 在证书列表中按颁发者和序列号找到的签名者
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub digest_algorithm: String,
+    pub signature_algorithm: String,
+    pub counter_signatures: Vec<CounterSignature>,
+}
+
+/// 时间戳副署，PKCS#9 countersignature 或 RFC3161 时间戳
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CounterSignature {
+    pub kind: String,
+    pub signing_time: String,
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub digest_algorithm: String,
+}
+
+/// X.509 证书
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub signature_algorithm: String,
+}
+
+/// CertificateTable 用于传递egui
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CertificateTable(pub(crate) Rc<RefCell<Vec<WinCertificate>>>);
+
+// 为序列化创建包装结构体
+#[derive(Serialize, Deserialize)]
+pub struct SerializableCertificateTable {
+    pub certificates: Vec<WinCertificate>,
+}
+
+impl CertificateTable {
+    pub fn to_serializable(&self) -> SerializableCertificateTable {
+        SerializableCertificateTable {
+            certificates: self.0.borrow().clone(),
+        }
+    }
+}
+
+/// IMAGE_COR20_HEADER，即CLI头
+#[repr(C)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ImageCor20Header {
+    pub cb: u32,
+    pub major_runtime_version: u16,
+    pub minor_runtime_version: u16,
+    pub metadata_rva: u32,
+    pub metadata_size: u32,
+    pub flags: u32,
+    /// 托管入口点的元数据令牌，设置 NATIVE_ENTRYPOINT 时为本机入口点RVA
+    pub entry_point_token: u32,
+    pub resources_rva: u32,
+    pub resources_size: u32,
+    pub strong_name_signature_rva: u32,
+    pub strong_name_signature_size: u32,
+    pub code_manager_table_rva: u32,
+    pub code_manager_table_size: u32,
+    pub vtable_fixups_rva: u32,
+    pub vtable_fixups_size: u32,
+    pub export_address_table_jumps_rva: u32,
+    pub export_address_table_jumps_size: u32,
+    pub managed_native_header_rva: u32,
+    pub managed_native_header_size: u32,
+}
+
+/// .NET 元数据 用于传递egui
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct DotNetInfo {
+    pub cli_header: ImageCor20Header,
+    pub metadata_file_offset: u32,
+    pub metadata_major_version: u16,
+    pub metadata_minor_version: u16,
+    pub metadata_version: String,
+    pub streams: Vec<MetadataStream>,
+    pub tables_major_version: u8,
+    pub tables_minor_version: u8,
+    pub heap_sizes: u8,
+    pub valid_tables: u64,
+    pub sorted_tables: u64,
+    pub table_rows: Vec<MetadataTableRows>,
+    pub modules: Vec<ModuleRow>,
+    pub type_refs: Vec<TypeRefRow>,
+    pub type_defs: Vec<TypeDefRow>,
+    pub method_defs: Vec<MethodDefRow>,
+    pub member_refs: Vec<MemberRefRow>,
+    pub assembly_refs: Vec<AssemblyRefRow>,
+    pub manifest_resources: Vec<ManifestResourceRow>,
+    /// #US 堆中的字符串
+    pub user_strings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MetadataStream {
+    pub name: String,
+    /// 相对于元数据根的偏移
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MetadataTableRows {
+    pub table: u8,
+    pub name: String,
+    pub rows: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ModuleRow {
+    pub generation: u16,
+    pub name: String,
+    pub mvid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct TypeRefRow {
+    pub resolution_scope: String,
+    pub name: String,
+    pub namespace: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct TypeDefRow {
+    pub flags: u32,
+    pub name: String,
+    pub namespace: String,
+    pub extends: String,
+    pub field_list: u32,
+    pub method_list: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MethodDefRow {
+    pub rva: u32,
+    pub impl_flags: u16,
+    pub flags: u16,
+    pub name: String,
+    /// 所属类型，由 TypeDef 的 MethodList 范围得到
+    pub type_name: String,
+    /// 签名blob的十六进制
+    pub signature: String,
+    pub param_list: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MemberRefRow {
+    pub class: String,
+    pub name: String,
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct AssemblyRefRow {
+    pub version: String,
+    pub flags: u32,
+    pub public_key_or_token: String,
+    pub name: String,
+    pub culture: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ManifestResourceRow {
+    pub offset: u32,
+    pub flags: u32,
+    pub name: String,
+    /// 为空时资源位于本文件的CLI资源中
+    pub implementation: String,
 }
 
 // 为NtHeaders trait对象创建可序列化的包装
@@ -398,4 +1595,5 @@ pub struct ImportFunction {
 pub enum SerializableNtHeaders {
     ImageNtHeaders32(ImageNtHeaders),
     ImageNtHeaders64(ImageNtHeaders64),
+    CoffObject(ImageCoffHeader),
 }
@@ -0,0 +1,597 @@
+use crate::i18n;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::nt_header::{DIRECTORY_SECURITY, NUMBER_OF_DIRECTORY_ENTRIES};
+use crate::tools_api::read_file::{
+    Anomaly, AnomalySeverity, DataDirectory, ImageFileHeader, ImageSectionHeaders,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::mem::size_of;
+
+const DOS_HEADER_SIZE: u64 = 64;
+const E_LFANEW_OFFSET: u64 = 0x3C;
+/// Windows 加载器拒绝 e_lfanew 不小于256MB的映像
+const MAX_E_LFANEW: u32 = 0x1000_0000;
+const PE_SIGNATURE: u32 = 0x0000_4550;
+const IMAGE_NT_OPTIONAL_HDR32_MAGIC: u16 = 0x10B;
+const IMAGE_NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20B;
+/// 可选头中数据目录之前部分的大小
+const OPTIONAL_HEADER32_SIZE: u64 = 96;
+const OPTIONAL_HEADER64_SIZE: u64 = 112;
+/// 可选头各字段相对可选头起始的偏移
+const ENTRY_POINT_OFFSET: u64 = 16;
+const SECTION_ALIGNMENT_OFFSET: u64 = 32;
+const FILE_ALIGNMENT_OFFSET: u64 = 36;
+const SIZE_OF_IMAGE_OFFSET: u64 = 56;
+const SIZE_OF_HEADERS_OFFSET: u64 = 60;
+/// 文件头各字段相对文件头起始的偏移
+const NUMBER_OF_SECTIONS_OFFSET: u64 = 2;
+const SIZE_OF_OPTIONAL_HEADER_OFFSET: u64 = 16;
+/// 节表项中各字段的偏移
+const SECTION_VIRTUAL_ADDRESS_OFFSET: u64 = 12;
+const SECTION_POINTER_TO_RAW_DATA_OFFSET: u64 = 20;
+const SECTION_CHARACTERISTICS_OFFSET: u64 = 36;
+const IMAGE_SIZEOF_SECTION_HEADER: u64 = 40;
+/// Windows XP 之前的加载器最多接受96个节
+const MAX_SECTIONS: u16 = 96;
+const MIN_FILE_ALIGNMENT: u32 = 0x200;
+const MAX_FILE_ALIGNMENT: u32 = 0x1_0000;
+const IMAGE_SCN_CNT_CODE: u32 = 0x0000_0020;
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+const IMAGE_SCN_MEM_WRITE: u32 = 0x8000_0000;
+const DIRECTORY_NAMES: [&str; NUMBER_OF_DIRECTORY_ENTRIES] = [
+    "IMAGE_DIRECTORY_ENTRY_EXPORT",
+    "IMAGE_DIRECTORY_ENTRY_IMPORT",
+    "IMAGE_DIRECTORY_ENTRY_RESOURCE",
+    "IMAGE_DIRECTORY_ENTRY_EXCEPTION",
+    "IMAGE_DIRECTORY_ENTRY_SECURITY",
+    "IMAGE_DIRECTORY_ENTRY_BASERELOC",
+    "IMAGE_DIRECTORY_ENTRY_DEBUG",
+    "IMAGE_DIRECTORY_ENTRY_ARCHITECTURE",
+    "IMAGE_DIRECTORY_ENTRY_GLOBALPTR",
+    "IMAGE_DIRECTORY_ENTRY_TLS",
+    "IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG",
+    "IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT",
+    "IMAGE_DIRECTORY_ENTRY_IAT",
+    "IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT",
+    "IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR",
+    "IMAGE_DIRECTORY_ENTRY_RESERVED",
+];
+
+impl AnomalySeverity {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            AnomalySeverity::Info => i18n::ANOMALY_INFO,
+            AnomalySeverity::Warning => i18n::ANOMALY_WARNING,
+            AnomalySeverity::Error => i18n::ANOMALY_ERROR,
+        }
+    }
+}
+
+impl Anomaly {
+    pub(crate) fn new(
+        field: impl Into<String>,
+        offset: u64,
+        severity: AnomalySeverity,
+        description: impl Into<String>,
+    ) -> Self {
+        Anomaly {
+            field: field.into(),
+            offset,
+            severity,
+            description: description.into(),
+        }
+    }
+
+    /// 数据目录指向的结构无法解析
+    pub(crate) fn new_directory_failure(
+        nt_addr: u32,
+        is_64_bit: bool,
+        index: usize,
+        error: &anyhow::Error,
+    ) -> Self {
+        Anomaly::new(
+            Anomaly::get_directory_name(index),
+            Anomaly::get_directory_entry_offset(nt_addr, is_64_bit, index),
+            AnomalySeverity::Error,
+            i18n::ANOMALY_PARSE_FAILED.replace("{}", &error.to_string()),
+        )
+    }
+
+    /// 数据目录项的名称，例如 IMAGE_DIRECTORY_ENTRY_IMPORT
+    pub(crate) fn get_directory_name(index: usize) -> &'static str {
+        DIRECTORY_NAMES
+            .get(index)
+            .copied()
+            .unwrap_or("IMAGE_DATA_DIRECTORY")
+    }
+
+    /// 数据目录项在文件中的偏移
+    pub(crate) fn get_directory_entry_offset(nt_addr: u32, is_64_bit: bool, index: usize) -> u64 {
+        optional_header_offset(nt_addr)
+            + if is_64_bit {
+                OPTIONAL_HEADER64_SIZE
+            } else {
+                OPTIONAL_HEADER32_SIZE
+            }
+            + index as u64 * 8
+    }
+}
+
+fn optional_header_offset(nt_addr: u32) -> u64 {
+    nt_addr as u64 + 4 + size_of::<ImageFileHeader>() as u64
+}
+
+/// 打开文件时检查DOS头、NT头、数据目录与节表中的畸形结构
+pub(crate) struct HeaderScanner<'a, T: NtHeaders + ?Sized> {
+    pub(crate) file_size: u64,
+    pub(crate) nt_addr: u32,
+    pub(crate) is_64_bit: bool,
+    /// NT头无法完整读取时其余的头部都是占位的空值，只检查DOS头
+    pub(crate) nt_headers_valid: bool,
+    pub(crate) nt_head: &'a T,
+    pub(crate) data_directory: &'a DataDirectory,
+    pub(crate) section_headers: &'a ImageSectionHeaders,
+}
+
+impl<T: NtHeaders + ?Sized> HeaderScanner<'_, T> {
+    /// 检查PE映像的全部头部
+    pub(crate) fn scan_image(&self, file: &mut File) -> anyhow::Result<Vec<Anomaly>> {
+        let mut anomalies = Vec::new();
+        self.scan_dos_header(file, &mut anomalies)?;
+        if !self.nt_headers_valid {
+            anomalies.push(Anomaly::new(
+                "IMAGE_NT_HEADERS",
+                self.nt_addr as u64,
+                AnomalySeverity::Error,
+                i18n::ANOMALY_NT_HEADER_TRUNCATED.replace("{}", &format!("0x{:08X}", self.nt_addr)),
+            ));
+            return Ok(anomalies);
+        }
+        self.scan_optional_header(&mut anomalies);
+        self.scan_data_directory(&mut anomalies);
+        self.scan_section_table(&mut anomalies, true);
+        self.scan_layout(&mut anomalies);
+        Ok(anomalies)
+    }
+
+    /// COFF目标文件只有文件头与节表
+    pub(crate) fn scan_object(&self) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        self.scan_section_table(&mut anomalies, false);
+        anomalies
+    }
+
+    fn scan_dos_header(&self, file: &mut File, anomalies: &mut Vec<Anomaly>) -> anyhow::Result<()> {
+        let e_lfanew = self.nt_addr;
+        if e_lfanew >= MAX_E_LFANEW {
+            anomalies.push(Anomaly::new(
+                "e_lfanew",
+                E_LFANEW_OFFSET,
+                AnomalySeverity::Error,
+                i18n::ANOMALY_E_LFANEW_TOO_LARGE.replace("{}", &format!("0x{:08X}", e_lfanew)),
+            ));
+        } else if (e_lfanew as u64) < DOS_HEADER_SIZE {
+            anomalies.push(Anomaly::new(
+                "e_lfanew",
+                E_LFANEW_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_NT_HEADER_OVERLAP.replace("{}", &format!("0x{:08X}", e_lfanew)),
+            ));
+        }
+        file.seek(SeekFrom::Start(self.nt_addr as u64))?;
+        // 签名超出文件末尾时由 scan_image 报告NT头被截断
+        let Ok(signature) = file.read_u32::<LittleEndian>() else {
+            return Ok(());
+        };
+        if signature != PE_SIGNATURE {
+            anomalies.push(Anomaly::new(
+                "Signature",
+                self.nt_addr as u64,
+                AnomalySeverity::Error,
+                i18n::ANOMALY_INVALID_PE_SIGNATURE.replace("{}", &format!("0x{:08X}", signature)),
+            ));
+        }
+        Ok(())
+    }
+
+    fn scan_optional_header(&self, anomalies: &mut Vec<Anomaly>) {
+        let file_header = self.nt_addr as u64 + 4;
+        let optional_header = optional_header_offset(self.nt_addr);
+        let magic = self.nt_head.get_magic();
+        if magic != IMAGE_NT_OPTIONAL_HDR32_MAGIC && magic != IMAGE_NT_OPTIONAL_HDR64_MAGIC {
+            anomalies.push(Anomaly::new(
+                "Magic",
+                optional_header,
+                AnomalySeverity::Error,
+                i18n::UNKNOWN_OPTIONAL_HEADER_MAGIC.replace("{}", &format!("0x{:04X}", magic)),
+            ));
+        }
+        let number_of_rva_and_sizes = self.nt_head.num_of_rva();
+        if number_of_rva_and_sizes != NUMBER_OF_DIRECTORY_ENTRIES as u32 {
+            anomalies.push(Anomaly::new(
+                "NumberOfRvaAndSizes",
+                self.get_directory_offset(0) - 4,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_NUMBER_OF_RVA_AND_SIZES
+                    .replace("{}", &number_of_rva_and_sizes.to_string()),
+            ));
+        }
+        let expected_size = self.get_directory_offset(0) - optional_header
+            + 8 * (number_of_rva_and_sizes as u64).min(NUMBER_OF_DIRECTORY_ENTRIES as u64);
+        if self.nt_head.size_of_optional_header() as u64 != expected_size {
+            anomalies.push(Anomaly::new(
+                "SizeOfOptionalHeader",
+                file_header + SIZE_OF_OPTIONAL_HEADER_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_SIZE_OF_OPTIONAL_HEADER
+                    .replace("{}", &format!("0x{:X}", expected_size)),
+            ));
+        }
+        let file_alignment = self.nt_head.get_file_alignment();
+        if !file_alignment.is_power_of_two()
+            || !(MIN_FILE_ALIGNMENT..=MAX_FILE_ALIGNMENT).contains(&file_alignment)
+        {
+            anomalies.push(Anomaly::new(
+                "FileAlignment",
+                optional_header + FILE_ALIGNMENT_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_FILE_ALIGNMENT.replace("{}", &format!("0x{:X}", file_alignment)),
+            ));
+        }
+        let section_alignment = self.nt_head.get_section_alignment();
+        if !section_alignment.is_power_of_two() || section_alignment < file_alignment {
+            anomalies.push(Anomaly::new(
+                "SectionAlignment",
+                optional_header + SECTION_ALIGNMENT_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_SECTION_ALIGNMENT
+                    .replace("{}", &format!("0x{:X}", section_alignment)),
+            ));
+        }
+        let size_of_headers = self.nt_head.size_of_headers();
+        if size_of_headers as u64 > self.file_size {
+            anomalies.push(Anomaly::new(
+                "SizeOfHeaders",
+                optional_header + SIZE_OF_HEADERS_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_SIZE_OF_HEADERS.replace("{}", &format!("0x{:X}", size_of_headers)),
+            ));
+        }
+    }
+
+    fn scan_data_directory(&self, anomalies: &mut Vec<Anomaly>) {
+        let expected = (self.nt_head.num_of_rva() as usize).min(NUMBER_OF_DIRECTORY_ENTRIES);
+        if self.data_directory.0.len() < expected {
+            anomalies.push(Anomaly::new(
+                "DataDirectory",
+                self.get_directory_offset(self.data_directory.0.len()),
+                AnomalySeverity::Error,
+                i18n::ANOMALY_DATA_DIRECTORY_TRUNCATED
+                    .replace("{}", &self.data_directory.0.len().to_string()),
+            ));
+        }
+        let size_of_headers = self.nt_head.size_of_headers();
+        for (index, directory) in self.data_directory.0.iter().enumerate() {
+            if directory.virtual_address == 0 {
+                continue;
+            }
+            // 安全目录中保存的是文件偏移
+            if index == DIRECTORY_SECURITY {
+                if directory.virtual_address as u64 + directory.size as u64 > self.file_size {
+                    anomalies.push(Anomaly::new(
+                        Anomaly::get_directory_name(index),
+                        self.get_directory_offset(index),
+                        AnomalySeverity::Warning,
+                        i18n::ANOMALY_CERTIFICATE_OUTSIDE
+                            .replace("{}", &format!("0x{:08X}", directory.virtual_address)),
+                    ));
+                }
+                continue;
+            }
+            // 绑定导入等目录可以位于头部中
+            if directory.virtual_address >= size_of_headers
+                && self.find_section(directory.virtual_address).is_none()
+            {
+                anomalies.push(Anomaly::new(
+                    Anomaly::get_directory_name(index),
+                    self.get_directory_offset(index),
+                    AnomalySeverity::Warning,
+                    i18n::ANOMALY_DIRECTORY_OUTSIDE
+                        .replace("{}", &format!("0x{:08X}", directory.virtual_address)),
+                ));
+            }
+        }
+    }
+
+    fn scan_section_table(&self, anomalies: &mut Vec<Anomaly>, is_image: bool) {
+        // 目标文件没有PE签名，文件头从文件开头开始
+        let file_header = self.nt_addr as u64 + if is_image { 4 } else { 0 };
+        let number_of_sections = self.nt_head.section_number();
+        if number_of_sections == 0 {
+            anomalies.push(Anomaly::new(
+                "NumberOfSections",
+                file_header + NUMBER_OF_SECTIONS_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_NO_SECTIONS,
+            ));
+        } else if number_of_sections > MAX_SECTIONS {
+            anomalies.push(Anomaly::new(
+                "NumberOfSections",
+                file_header + NUMBER_OF_SECTIONS_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_TOO_MANY_SECTIONS.replace("{}", &number_of_sections.to_string()),
+            ));
+        }
+        let sections = &self.section_headers.0;
+        if sections.len() < number_of_sections as usize {
+            anomalies.push(Anomaly::new(
+                "NumberOfSections",
+                file_header + NUMBER_OF_SECTIONS_OFFSET,
+                AnomalySeverity::Error,
+                i18n::ANOMALY_SECTION_TABLE_TRUNCATED.replace("{}", &sections.len().to_string()),
+            ));
+        }
+        for (index, section) in sections.iter().enumerate() {
+            let header_offset = self.get_section_header_offset(index);
+            if section.size_of_raw_data != 0
+                && section.pointer_to_raw_data as u64 + section.size_of_raw_data as u64
+                    > self.file_size
+            {
+                anomalies.push(Anomaly::new(
+                    self.get_section_field(index, "PointerToRawData"),
+                    header_offset + SECTION_POINTER_TO_RAW_DATA_OFFSET,
+                    AnomalySeverity::Error,
+                    i18n::ANOMALY_SECTION_DATA_TRUNCATED
+                        .replace("{}", &self.get_section_name(index)),
+                ));
+            }
+            if is_image
+                && section.characteristics & IMAGE_SCN_MEM_WRITE != 0
+                && section.characteristics & IMAGE_SCN_MEM_EXECUTE != 0
+            {
+                anomalies.push(Anomaly::new(
+                    self.get_section_field(index, "Characteristics"),
+                    header_offset + SECTION_CHARACTERISTICS_OFFSET,
+                    AnomalySeverity::Info,
+                    i18n::ANOMALY_SECTION_WRITABLE_EXECUTABLE
+                        .replace("{}", &self.get_section_name(index)),
+                ));
+            }
+        }
+    }
+
+    /// 节在内存中的布局、映像大小与入口点
+    fn scan_layout(&self, anomalies: &mut Vec<Anomaly>) {
+        let optional_header = optional_header_offset(self.nt_addr);
+        let sections = &self.section_headers.0;
+        let mut order: Vec<usize> = (0..sections.len()).collect();
+        order.sort_by_key(|&index| sections[index].virtual_address);
+        for pair in order.windows(2) {
+            let (previous, current) = (pair[0], pair[1]);
+            if self.get_virtual_end(previous) > sections[current].virtual_address as u64 {
+                anomalies.push(Anomaly::new(
+                    self.get_section_field(current, "VirtualAddress"),
+                    self.get_section_header_offset(current) + SECTION_VIRTUAL_ADDRESS_OFFSET,
+                    AnomalySeverity::Warning,
+                    i18n::ANOMALY_SECTION_OVERLAP.replace("{}", &self.get_section_name(previous)),
+                ));
+            }
+        }
+        let image_end = (0..sections.len())
+            .map(|index| self.get_virtual_end(index))
+            .max()
+            .unwrap_or(0);
+        if image_end > self.nt_head.size_of_image() as u64 {
+            anomalies.push(Anomaly::new(
+                "SizeOfImage",
+                optional_header + SIZE_OF_IMAGE_OFFSET,
+                AnomalySeverity::Error,
+                i18n::ANOMALY_SIZE_OF_IMAGE.replace("{}", &format!("0x{:X}", image_end)),
+            ));
+        }
+        let entry_point = self.nt_head.get_address_of_entry_point();
+        if entry_point == 0 || entry_point < self.nt_head.size_of_headers() {
+            return;
+        }
+        match self.find_section(entry_point) {
+            None => anomalies.push(Anomaly::new(
+                "AddressOfEntryPoint",
+                optional_header + ENTRY_POINT_OFFSET,
+                AnomalySeverity::Warning,
+                i18n::ANOMALY_ENTRY_POINT_OUTSIDE.replace("{}", &format!("0x{:08X}", entry_point)),
+            )),
+            Some(index)
+                if sections[index].characteristics
+                    & (IMAGE_SCN_MEM_EXECUTE | IMAGE_SCN_CNT_CODE)
+                    == 0 =>
+            {
+                anomalies.push(Anomaly::new(
+                    "AddressOfEntryPoint",
+                    optional_header + ENTRY_POINT_OFFSET,
+                    AnomalySeverity::Warning,
+                    i18n::ANOMALY_ENTRY_POINT_NOT_EXECUTABLE
+                        .replace("{}", &self.get_section_name(index)),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn get_directory_offset(&self, index: usize) -> u64 {
+        Anomaly::get_directory_entry_offset(self.nt_addr, self.is_64_bit, index)
+    }
+
+    fn get_section_header_offset(&self, index: usize) -> u64 {
+        self.nt_head.section_start(self.nt_addr) as u64 + index as u64 * IMAGE_SIZEOF_SECTION_HEADER
+    }
+
+    fn get_section_name(&self, index: usize) -> String {
+        self.section_headers
+            .get_section_names()
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| format!("#{}", index))
+    }
+
+    fn get_section_field(&self, index: usize, field: &str) -> String {
+        format!("{}.{}", self.get_section_name(index), field)
+    }
+
+    /// 节在内存中的结束位置，VirtualSize 为0时加载器使用 SizeOfRawData
+    fn get_virtual_end(&self, index: usize) -> u64 {
+        let section = &self.section_headers.0[index];
+        let size = match section.misc.virtual_size {
+            0 => section.size_of_raw_data,
+            virtual_size => virtual_size,
+        };
+        section.virtual_address as u64 + size as u64
+    }
+
+    fn find_section(&self, rva: u32) -> Option<usize> {
+        (0..self.section_headers.0.len()).find(|&index| {
+            let start = self.section_headers.0[index].virtual_address;
+            rva >= start && (rva as u64) < self.get_virtual_end(index)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::AnomalySeverity;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_e_lfanew_beyond_64kb() {
+        let original = std::fs::read("test_pe/steam_api.dll").unwrap();
+        let nt_addr = u32::from_le_bytes(original[0x3C..0x40].try_into().unwrap()) as usize;
+        let file_header = nt_addr + 4;
+        let read_u16 = |offset: usize| u16::from_le_bytes([original[offset], original[offset + 1]]);
+        let number_of_sections = read_u16(file_header + 2);
+        let size_of_optional_header = read_u16(file_header + 16);
+        let headers_end =
+            file_header + 20 + size_of_optional_header as usize + number_of_sections as usize * 40;
+
+        // 把NT头与节表搬到文件末尾之后的0x41000处，节数据的位置不变
+        let new_nt_addr = 0x41000u32;
+        let mut data = original.clone();
+        data.resize(new_nt_addr as usize, 0);
+        data.extend_from_slice(&original[nt_addr..headers_end]);
+        data[0x3C..0x40].copy_from_slice(&new_nt_addr.to_le_bytes());
+        let path = std::env::temp_dir().join("penguin_e_lfanew_beyond_64kb.dll");
+        std::fs::write(&path, &data).unwrap();
+        let mut moved = FileInfo::new(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut expected = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+
+        assert_eq!(moved.dos_head.get_nt_addr(), new_nt_addr);
+        assert_eq!(moved.nt_head.machine(), expected.nt_head.machine());
+        // 节表项本身的文件偏移随NT头移动，内容不变
+        assert_eq!(moved.section_headers.0, expected.section_headers.0);
+        assert_eq!(moved.dos_stub.buffer.len(), new_nt_addr as usize - 64);
+        assert_eq!(moved.rich_header, expected.rich_header);
+        assert_eq!(moved.get_imphash(), expected.get_imphash());
+        assert!(
+            !moved
+                .anomalies
+                .borrow()
+                .iter()
+                .any(|anomaly| anomaly.field == "e_lfanew")
+        );
+    }
+
+    fn open_truncated(data: &[u8], name: &str) -> Box<FileInfo> {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, data).unwrap();
+        let file_info = FileInfo::new(path.clone());
+        std::fs::remove_file(&path).unwrap();
+        file_info.unwrap()
+    }
+
+    fn assert_nt_header_truncated(file_info: &FileInfo, nt_addr: u64) {
+        let anomalies = file_info.anomalies.borrow();
+        let anomaly = anomalies
+            .iter()
+            .find(|anomaly| anomaly.field == "IMAGE_NT_HEADERS")
+            .unwrap();
+        assert_eq!(anomaly.offset, nt_addr);
+        assert_eq!(anomaly.severity, AnomalySeverity::Error);
+        assert!(file_info.section_headers.0.is_empty());
+    }
+
+    #[test]
+    fn test_truncated_nt_header() {
+        let original = std::fs::read("test_pe/steam_api.dll").unwrap();
+        let nt_addr = u32::from_le_bytes(original[0x3C..0x40].try_into().unwrap()) as usize;
+        // 文件头完整，可选头只剩前10个字节
+        let file_info = open_truncated(&original[..nt_addr + 24 + 10], "penguin_nt_truncated.dll");
+        assert_nt_header_truncated(&file_info, nt_addr as u64);
+        assert_eq!(file_info.dos_stub.buffer, original[0x40..nt_addr]);
+    }
+
+    #[test]
+    fn test_e_lfanew_past_end_of_file() {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        data.truncate(0x400);
+        data[0x3C..0x40].copy_from_slice(&0x1000u32.to_le_bytes());
+        let file_info = open_truncated(&data, "penguin_e_lfanew_past_eof.dll");
+        assert_nt_header_truncated(&file_info, 0x1000);
+        // 签名无法读取时不再另外报告签名无效
+        assert!(
+            !file_info
+                .anomalies
+                .borrow()
+                .iter()
+                .any(|anomaly| anomaly.field == "Signature")
+        );
+    }
+
+    /// 把机器类型改为 `machine` 后打开
+    fn open_with_machine(path: &str, name: &str, machine: u16) -> Box<FileInfo> {
+        let mut data = std::fs::read(path).unwrap();
+        let nt_addr = u32::from_le_bytes(data[0x3C..0x40].try_into().unwrap()) as usize;
+        data[nt_addr + 4..nt_addr + 6].copy_from_slice(&machine.to_le_bytes());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        file_info
+    }
+
+    #[test]
+    fn test_unknown_machine_uses_magic() {
+        // 未知机器类型不影响按 Magic 0x20B 选择64位布局
+        let file_info = open_with_machine(
+            "test_pe/steam_api64.dll",
+            "penguin_unknown_machine_64.dll",
+            0x1234,
+        );
+        let expected = FileInfo::new(PathBuf::from("test_pe/steam_api64.dll")).unwrap();
+        assert!(file_info.is_64_bit);
+        assert_eq!(file_info.nt_head.machine(), 0x1234);
+        assert_eq!(
+            file_info.nt_head.get_image_base(),
+            expected.nt_head.get_image_base()
+        );
+        assert_eq!(file_info.section_headers.0, expected.section_headers.0);
+    }
+
+    #[test]
+    fn test_magic_overrides_64_bit_machine() {
+        // Magic 为 0x10B 时即使机器类型为 AMD64 也按32位布局解析
+        let mut file_info = open_with_machine(
+            "test_pe/steam_api.dll",
+            "penguin_amd64_machine_32.dll",
+            0x8664,
+        );
+        let mut expected = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        assert!(!file_info.is_64_bit);
+        assert_eq!(
+            file_info.nt_head.get_image_base(),
+            expected.nt_head.get_image_base()
+        );
+        assert_eq!(file_info.get_imphash(), expected.get_imphash());
+    }
+}
@@ -0,0 +1,589 @@
+use crate::tools_api::read_file::nt_header::{MACHINE_32, MACHINE_64, get_machine_descriptions};
+use crate::tools_api::read_file::{
+    ArchiveInfo, ArchiveMember, ArchiveMemberKind, ArchiveSymbol, ImportObjectInfo,
+};
+use std::fs::{self, File};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const ARCHIVE_MAGIC: &[u8; 8] = b"!<arch>\n";
+const MEMBER_HEADER_SIZE: u64 = 60;
+const MEMBER_HEADER_END: &[u8; 2] = b"`\n";
+const LINKER_MEMBER_NAME: &str = "/";
+const LONG_NAMES_MEMBER_NAME: &str = "//";
+const IMPORT_OBJECT_HEADER_SIZE: usize = 20;
+const IMPORT_OBJECT_HDR_SIG2: u16 = 0xFFFF;
+/// 导入项名称数据的最大长度，超出的部分被截断
+const MAX_IMPORT_DATA_SIZE: u64 = 0x10000;
+/// 解压成员时使用的临时目录名
+const EXTRACT_DIR_NAME: &str = "penguin";
+
+const IMPORT_NAME_TYPE_ORDINAL: u8 = 0;
+const IMPORT_NAME_TYPE_NAME: u8 = 1;
+const IMPORT_NAME_TYPE_NAME_NOPREFIX: u8 = 2;
+const IMPORT_NAME_TYPE_NAME_UNDECORATE: u8 = 3;
+const IMPORT_NAME_TYPE_NAME_EXPORTAS: u8 = 4;
+const IMPORT_TYPE_NAMES: [&str; 3] = ["CODE", "DATA", "CONST"];
+const IMPORT_NAME_TYPE_NAMES: [&str; 5] = [
+    "ORDINAL",
+    "NAME",
+    "NAME_NOPREFIX",
+    "NAME_UNDECORATE",
+    "NAME_EXPORTAS",
+];
+/// NAME_NOPREFIX 与 NAME_UNDECORATE 需要去掉的前缀字符
+const IMPORT_NAME_PREFIXES: [char; 3] = ['?', '@', '_'];
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn parse_decimal(field: &[u8]) -> u64 {
+    String::from_utf8_lossy(field).trim().parse().unwrap_or(0)
+}
+
+/// 读取以0结尾的连续字符串
+fn split_strings(data: &[u8], count: usize) -> Vec<String> {
+    data.split(|&b| b == 0)
+        .take(count)
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect()
+}
+
+fn read_member_data(file: &mut File, offset: u64, size: u64) -> anyhow::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    file.seek(SeekFrom::Start(offset))?;
+    file.take(size).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// 长名称成员中的名称以 `/\n`（GNU）或 `\0`（MSVC）结尾
+fn get_long_name(long_names: &[u8], offset: usize) -> Option<String> {
+    let data = long_names.get(offset..)?;
+    let end = data
+        .iter()
+        .position(|&b| b == 0 || b == b'\n')
+        .unwrap_or(data.len());
+    Some(
+        String::from_utf8_lossy(&data[..end])
+            .trim_end_matches('/')
+            .to_string(),
+    )
+}
+
+impl ImportObjectInfo {
+    fn new(data: &[u8]) -> Option<ImportObjectInfo> {
+        let type_info = read_u16(data, 18)?;
+        let name_type = ((type_info >> 2) & 0x7) as u8;
+        let mut names = split_strings(data.get(IMPORT_OBJECT_HEADER_SIZE..)?, 3).into_iter();
+        Some(ImportObjectInfo {
+            machine: read_u16(data, 6)?,
+            time_date_stamp: read_u32(data, 8)?,
+            size_of_data: read_u32(data, 12)?,
+            ordinal_or_hint: read_u16(data, 16)?,
+            import_type: (type_info & 0x3) as u8,
+            name_type,
+            symbol_name: names.next().unwrap_or_default(),
+            dll_name: names.next().unwrap_or_default(),
+            export_name: names
+                .next()
+                .filter(|_| name_type == IMPORT_NAME_TYPE_NAME_EXPORTAS),
+        })
+    }
+
+    pub fn get_machine_name(&self) -> &'static str {
+        get_machine_descriptions(self.machine)
+    }
+
+    pub fn get_import_type_name(&self) -> &'static str {
+        IMPORT_TYPE_NAMES
+            .get(self.import_type as usize)
+            .copied()
+            .unwrap_or("UNKNOWN")
+    }
+
+    pub fn get_name_type_name(&self) -> &'static str {
+        IMPORT_NAME_TYPE_NAMES
+            .get(self.name_type as usize)
+            .copied()
+            .unwrap_or("UNKNOWN")
+    }
+
+    /// 加载器实际按此名称（或序号）从DLL中导入
+    pub fn get_import_name(&self) -> String {
+        let without_prefix = || {
+            self.symbol_name
+                .strip_prefix(IMPORT_NAME_PREFIXES)
+                .unwrap_or(&self.symbol_name)
+        };
+        match self.name_type {
+            IMPORT_NAME_TYPE_ORDINAL => format!("#{}", self.ordinal_or_hint),
+            IMPORT_NAME_TYPE_NAME => self.symbol_name.clone(),
+            IMPORT_NAME_TYPE_NAME_NOPREFIX => without_prefix().to_string(),
+            IMPORT_NAME_TYPE_NAME_UNDECORATE => {
+                without_prefix().split('@').next().unwrap_or("").to_string()
+            }
+            _ => self
+                .export_name
+                .clone()
+                .unwrap_or_else(|| self.symbol_name.clone()),
+        }
+    }
+}
+
+impl ArchiveMember {
+    /// 根据成员开头的内容判断类型
+    fn detect_kind(data: &[u8]) -> ArchiveMemberKind {
+        if read_u16(data, 0) == Some(0) && read_u16(data, 2) == Some(IMPORT_OBJECT_HDR_SIG2) {
+            // Version 不为0时为 /GL 或 bigobj 生成的匿名对象
+            return match read_u16(data, 4) {
+                Some(0) => ImportObjectInfo::new(data)
+                    .map(ArchiveMemberKind::ImportObject)
+                    .unwrap_or_default(),
+                _ => ArchiveMemberKind::Unknown,
+            };
+        }
+        match read_u16(data, 0) {
+            Some(machine) if MACHINE_32.contains(&machine) || MACHINE_64.contains(&machine) => {
+                ArchiveMemberKind::CoffObject
+            }
+            _ => ArchiveMemberKind::Unknown,
+        }
+    }
+
+    /// MSVC 库中的成员名通常是完整路径，只显示文件名部分
+    pub fn get_display_name(&self) -> &str {
+        self.name.rsplit(['/', '\\']).next().unwrap_or(&self.name)
+    }
+
+    pub fn get_kind_name(&self) -> &'static str {
+        match self.kind {
+            ArchiveMemberKind::FirstLinkerMember => "First linker member",
+            ArchiveMemberKind::SecondLinkerMember => "Second linker member",
+            ArchiveMemberKind::LongNames => "Long names",
+            ArchiveMemberKind::CoffObject => "COFF object",
+            ArchiveMemberKind::ImportObject(_) => "Import object",
+            ArchiveMemberKind::Unknown => "Unknown",
+        }
+    }
+
+    /// 成员解压到临时目录中的路径，不同的库与成员互不冲突
+    pub(crate) fn get_extract_path(&self, archive_path: &Path) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        archive_path.hash(&mut hasher);
+        std::env::temp_dir()
+            .join(EXTRACT_DIR_NAME)
+            .join(format!("{:016X}_{:X}", hasher.finish(), self.header_offset))
+            .join(self.get_display_name())
+    }
+
+    /// 将成员写入文件
+    pub fn extract(&self, file: &mut File, output_path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        file.seek(SeekFrom::Start(self.data_offset))?;
+        let mut output = File::create(output_path)?;
+        io::copy(&mut file.take(self.size), &mut output)?;
+        Ok(())
+    }
+}
+
+impl ArchiveInfo {
+    /// 文件是否以 `!<arch>\n` 开头
+    pub fn is_archive(file: &mut File) -> anyhow::Result<bool> {
+        let mut magic = [0u8; ARCHIVE_MAGIC.len()];
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file.read_exact(&mut magic).is_ok() && &magic == ARCHIVE_MAGIC)
+    }
+
+    pub fn new(file: &mut File) -> anyhow::Result<ArchiveInfo> {
+        let file_size = file.metadata()?.len();
+        let mut offset = ARCHIVE_MAGIC.len() as u64;
+        let mut members = Vec::new();
+        let mut raw_names = Vec::new();
+        while offset + MEMBER_HEADER_SIZE <= file_size {
+            let mut header = [0u8; MEMBER_HEADER_SIZE as usize];
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut header)?;
+            // 成员头损坏时停止解析，保留之前的成员
+            if &header[58..60] != MEMBER_HEADER_END {
+                break;
+            }
+            let data_offset = offset + MEMBER_HEADER_SIZE;
+            let size = parse_decimal(&header[48..58]).min(file_size - data_offset);
+            raw_names.push(
+                String::from_utf8_lossy(&header[..16])
+                    .trim_end()
+                    .to_string(),
+            );
+            members.push(ArchiveMember {
+                name: String::new(),
+                header_offset: offset,
+                data_offset,
+                size,
+                date: parse_decimal(&header[16..28]),
+                mode: String::from_utf8_lossy(&header[40..48]).trim().to_string(),
+                kind: ArchiveMemberKind::Unknown,
+            });
+            // 成员数据按2字节对齐
+            offset = data_offset + size + (size & 1);
+        }
+
+        let long_names = match raw_names
+            .iter()
+            .position(|name| name == LONG_NAMES_MEMBER_NAME)
+        {
+            Some(index) => read_member_data(file, members[index].data_offset, members[index].size)?,
+            None => Vec::new(),
+        };
+        let mut linker_members = 0;
+        for (member, raw_name) in members.iter_mut().zip(raw_names) {
+            match raw_name.as_str() {
+                LINKER_MEMBER_NAME => {
+                    member.kind = if linker_members == 0 {
+                        ArchiveMemberKind::FirstLinkerMember
+                    } else {
+                        ArchiveMemberKind::SecondLinkerMember
+                    };
+                    linker_members += 1;
+                    member.name = raw_name;
+                }
+                LONG_NAMES_MEMBER_NAME => {
+                    member.kind = ArchiveMemberKind::LongNames;
+                    member.name = raw_name;
+                }
+                _ => {
+                    member.name = raw_name
+                        .strip_prefix('/')
+                        .and_then(|offset| offset.parse().ok())
+                        .and_then(|offset| get_long_name(&long_names, offset))
+                        .unwrap_or_else(|| raw_name.trim_end_matches('/').to_string());
+                    let preview_size = member.size.min(MAX_IMPORT_DATA_SIZE);
+                    let data = read_member_data(file, member.data_offset, preview_size)?;
+                    member.kind = ArchiveMember::detect_kind(&data);
+                }
+            }
+        }
+        let symbols = Self::read_symbols(file, &members)?;
+        Ok(ArchiveInfo { members, symbols })
+    }
+
+    /// 优先使用MSVC的第二链接器成员，只有第一链接器成员时按大端格式读取
+    fn read_symbols(
+        file: &mut File,
+        members: &[ArchiveMember],
+    ) -> anyhow::Result<Vec<ArchiveSymbol>> {
+        let member_index_of = |header_offset: u32| {
+            members
+                .iter()
+                .position(|member| member.header_offset == header_offset as u64)
+        };
+        if let Some(member) = members
+            .iter()
+            .find(|member| member.kind == ArchiveMemberKind::SecondLinkerMember)
+        {
+            let data = read_member_data(file, member.data_offset, member.size)?;
+            let number_of_members = read_u32(&data, 0).unwrap_or(0) as usize;
+            let offsets_end = 4 + number_of_members.min(data.len() / 4) * 4;
+            let number_of_symbols = read_u32(&data, offsets_end).unwrap_or(0) as usize;
+            let indices_start = offsets_end + 4;
+            let number_of_symbols =
+                number_of_symbols.min(data.len().saturating_sub(indices_start) / 2);
+            let strings_start = indices_start + number_of_symbols * 2;
+            let names = split_strings(data.get(strings_start..).unwrap_or(&[]), number_of_symbols);
+            return Ok(names
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    // 索引从1开始
+                    let member_index = read_u16(&data, indices_start + index * 2)
+                        .and_then(|index| (index as usize).checked_sub(1))
+                        .filter(|&index| index < number_of_members)
+                        .and_then(|index| read_u32(&data, 4 + index * 4))
+                        .and_then(member_index_of);
+                    ArchiveSymbol { name, member_index }
+                })
+                .collect());
+        }
+        if let Some(member) = members
+            .iter()
+            .find(|member| member.kind == ArchiveMemberKind::FirstLinkerMember)
+        {
+            let data = read_member_data(file, member.data_offset, member.size)?;
+            let number_of_symbols =
+                (read_u32_be(&data, 0).unwrap_or(0) as usize).min(data.len().saturating_sub(4) / 4);
+            let strings_start = 4 + number_of_symbols * 4;
+            let names = split_strings(data.get(strings_start..).unwrap_or(&[]), number_of_symbols);
+            return Ok(names
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| ArchiveSymbol {
+                    name,
+                    member_index: read_u32_be(&data, 4 + index * 4).and_then(member_index_of),
+                })
+                .collect());
+        }
+        Ok(Vec::new())
+    }
+
+    /// 导入库中的短格式导入项
+    pub fn get_import_objects(&self) -> impl Iterator<Item = &ImportObjectInfo> {
+        self.members.iter().filter_map(|member| match &member.kind {
+            ArchiveMemberKind::ImportObject(import) => Some(import),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n;
+    use crate::tools_api::FileInfo;
+
+    const LONG_NAMES: &[u8] = b"very_long_member_name.obj\0gnu_style_long_name.obj/\n";
+    /// 长名称成员中第二个名称的偏移
+    const GNU_LONG_NAME_OFFSET: usize = 26;
+
+    /// 成员头与数据，奇数长度的数据以 `\n` 补齐
+    fn member(name: &str, data: &[u8]) -> Vec<u8> {
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            1700000000,
+            "",
+            "",
+            644,
+            data.len()
+        );
+        let mut member = header.into_bytes();
+        member.extend(data);
+        if data.len() % 2 == 1 {
+            member.push(b'\n');
+        }
+        member
+    }
+
+    fn member_size(data_size: usize) -> u32 {
+        (MEMBER_HEADER_SIZE as usize + data_size + data_size % 2) as u32
+    }
+
+    fn import_object() -> Vec<u8> {
+        let mut data = vec![0u8; IMPORT_OBJECT_HEADER_SIZE];
+        data[2..4].copy_from_slice(&IMPORT_OBJECT_HDR_SIG2.to_le_bytes());
+        data[6..8].copy_from_slice(&0x8664u16.to_le_bytes());
+        data[8..12].copy_from_slice(&0x619D3C80u32.to_le_bytes());
+        data[12..16].copy_from_slice(&19u32.to_le_bytes());
+        data[16..18].copy_from_slice(&7u16.to_le_bytes());
+        data[18..20]
+            .copy_from_slice(&((IMPORT_NAME_TYPE_NAME_UNDECORATE as u16) << 2).to_le_bytes());
+        data.extend(b"_Foo@4\0penguin.dll\0");
+        data
+    }
+
+    /// 两个链接器成员、长名称成员、导入项与目标文件成员组成的导入库
+    fn archive(second_linker_member: bool) -> Vec<u8> {
+        let object = {
+            let mut object = vec![0u8; 21];
+            object[..2].copy_from_slice(&0x8664u16.to_le_bytes());
+            object
+        };
+        let import = import_object();
+        let first_linker_size = 4 + 2 * 4 + 8;
+        let second_linker_size = 4 + 2 * 4 + 4 + 2 * 2 + 8;
+        let mut import_offset = ARCHIVE_MAGIC.len() as u32 + member_size(first_linker_size);
+        if second_linker_member {
+            import_offset += member_size(second_linker_size);
+        }
+        import_offset += member_size(LONG_NAMES.len());
+        let object_offset = import_offset + member_size(import.len());
+
+        // 第一链接器成员为大端格式，符号按成员顺序排列
+        let mut first_linker = 2u32.to_be_bytes().to_vec();
+        first_linker.extend(object_offset.to_be_bytes());
+        first_linker.extend(import_offset.to_be_bytes());
+        first_linker.extend(b"bar\0Foo\0");
+        assert_eq!(first_linker.len(), first_linker_size);
+        // 第二链接器成员为小端格式，符号按名称排序并通过从1开始的索引引用成员
+        let mut second_linker = 2u32.to_le_bytes().to_vec();
+        second_linker.extend(import_offset.to_le_bytes());
+        second_linker.extend(object_offset.to_le_bytes());
+        second_linker.extend(2u32.to_le_bytes());
+        second_linker.extend(1u16.to_le_bytes());
+        second_linker.extend(2u16.to_le_bytes());
+        second_linker.extend(b"Foo\0bar\0");
+        assert_eq!(second_linker.len(), second_linker_size);
+
+        let mut data = ARCHIVE_MAGIC.to_vec();
+        data.extend(member(LINKER_MEMBER_NAME, &first_linker));
+        if second_linker_member {
+            data.extend(member(LINKER_MEMBER_NAME, &second_linker));
+        }
+        data.extend(member(LONG_NAMES_MEMBER_NAME, LONG_NAMES));
+        assert_eq!(data.len(), import_offset as usize);
+        data.extend(member("/0", &import));
+        assert_eq!(data.len(), object_offset as usize);
+        data.extend(member(&format!("/{}", GNU_LONG_NAME_OFFSET), &object));
+        data.extend(member("short.obj/", b"data"));
+        data
+    }
+
+    fn read_archive(name: &str, data: &[u8]) -> ArchiveInfo {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, data).unwrap();
+        let mut file = File::open(&path).unwrap();
+        assert!(ArchiveInfo::is_archive(&mut file).unwrap());
+        let archive = ArchiveInfo::new(&mut file).unwrap();
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+        archive
+    }
+
+    #[test]
+    fn test_archive_members() {
+        let archive = read_archive("penguin_archive.lib", &archive(true));
+        let members: Vec<_> = archive
+            .members
+            .iter()
+            .map(|member| (member.name.as_str(), member.get_kind_name()))
+            .collect();
+        assert_eq!(
+            members,
+            [
+                ("/", "First linker member"),
+                ("/", "Second linker member"),
+                ("//", "Long names"),
+                ("very_long_member_name.obj", "Import object"),
+                ("gnu_style_long_name.obj", "COFF object"),
+                ("short.obj", "Unknown"),
+            ]
+        );
+        let object = &archive.members[4];
+        assert_eq!(object.size, 21);
+        assert_eq!(object.date, 1700000000);
+        assert_eq!(object.mode, "644");
+        assert_eq!(
+            object.data_offset,
+            object.header_offset + MEMBER_HEADER_SIZE
+        );
+        // 奇数长度的成员之后有1字节填充
+        assert_eq!(
+            archive.members[5].header_offset,
+            object.data_offset + object.size + 1
+        );
+    }
+
+    #[test]
+    fn test_second_linker_member_symbols() {
+        let archive = read_archive("penguin_archive_second.lib", &archive(true));
+        assert_eq!(
+            archive.symbols,
+            [
+                ArchiveSymbol {
+                    name: "Foo".to_string(),
+                    member_index: Some(3),
+                },
+                ArchiveSymbol {
+                    name: "bar".to_string(),
+                    member_index: Some(4),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_linker_member_symbols() {
+        let archive = read_archive("penguin_archive_first.lib", &archive(false));
+        assert_eq!(
+            archive.members[0].kind,
+            ArchiveMemberKind::FirstLinkerMember
+        );
+        assert_eq!(
+            archive.symbols,
+            [
+                ArchiveSymbol {
+                    name: "bar".to_string(),
+                    member_index: Some(3),
+                },
+                ArchiveSymbol {
+                    name: "Foo".to_string(),
+                    member_index: Some(2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_object() {
+        let archive = read_archive("penguin_archive_import.lib", &archive(true));
+        let imports: Vec<_> = archive.get_import_objects().collect();
+        assert_eq!(imports.len(), 1);
+        let import = imports[0];
+        assert_eq!(import.symbol_name, "_Foo@4");
+        assert_eq!(import.dll_name, "penguin.dll");
+        assert_eq!(import.export_name, None);
+        assert_eq!(import.ordinal_or_hint, 7);
+        assert_eq!(import.get_import_type_name(), "CODE");
+        assert_eq!(import.get_name_type_name(), "NAME_UNDECORATE");
+        assert_eq!(import.get_import_name(), "Foo");
+        assert_eq!(import.get_machine_name(), i18n::MACHINE_X64_64);
+    }
+
+    #[test]
+    fn test_long_name_resolution() {
+        assert_eq!(
+            get_long_name(LONG_NAMES, 0).as_deref(),
+            Some("very_long_member_name.obj")
+        );
+        assert_eq!(
+            get_long_name(LONG_NAMES, GNU_LONG_NAME_OFFSET).as_deref(),
+            Some("gnu_style_long_name.obj")
+        );
+        assert_eq!(get_long_name(LONG_NAMES, LONG_NAMES.len() + 1), None);
+        // 偏移超出长名称成员时保留原始名称
+        let mut data = archive(true);
+        data.extend(member("/999", b"xx"));
+        let archive = read_archive("penguin_archive_long_name.lib", &data);
+        assert_eq!(archive.members.last().unwrap().name, "/999");
+    }
+
+    #[test]
+    fn test_corrupt_member_header() {
+        let mut data = archive(true);
+        let corrupt_offset = data.len();
+        data.extend(member("broken.obj/", b"xx"));
+        data.extend(member("after.obj/", b"xx"));
+        data[corrupt_offset + 58..corrupt_offset + 60].copy_from_slice(b"  ");
+        let archive = read_archive("penguin_archive_corrupt.lib", &data);
+        // 损坏的成员头及其之后的成员都被忽略
+        assert_eq!(archive.members.len(), 6);
+        assert_eq!(archive.members.last().unwrap().name, "short.obj");
+    }
+
+    #[test]
+    fn test_archive_file_info() {
+        let path = std::env::temp_dir().join("penguin_archive_file_info.lib");
+        std::fs::write(&path, archive(true)).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!file_info.is_pe_image());
+        assert!(!file_info.is_coff_object);
+        assert_eq!(file_info.archive.as_ref().unwrap().members.len(), 6);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_BOUND_IMPORT;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    BoundForwarderRef, BoundImportInfo, DataDirectory, ImageBoundForwarderRef,
+    ImageBoundImportDescriptor, ImageDosHeader, ImageFileHeader, ImageSectionHeaders, ImportDll,
+    rva_2_fo,
+};
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+use std::path::Path;
+
+/// 导入描述符的时间戳为-1时表示新式绑定，真实时间戳保存在绑定导入目录中
+const BOUND_NEW_STYLE: u32 = 0xFFFF_FFFF;
+/// 描述符以全0项结尾，防止畸形文件导致读取过多
+const MAX_BOUND_IMPORT_DESCRIPTORS: u32 = 0x1000;
+
+impl ImageBoundImportDescriptor {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut descriptor = MaybeUninit::<ImageBoundImportDescriptor>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                descriptor.as_mut_ptr() as *mut u8,
+                size_of::<ImageBoundImportDescriptor>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(descriptor.assume_init())
+        }
+    }
+}
+
+impl ImageBoundForwarderRef {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut forwarder_ref = MaybeUninit::<ImageBoundForwarderRef>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                forwarder_ref.as_mut_ptr() as *mut u8,
+                size_of::<ImageBoundForwarderRef>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(forwarder_ref.assume_init())
+        }
+    }
+}
+
+impl BoundImportInfo {
+    /// 读取 IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT 下的所有描述符及其转发引用
+    pub fn new_list<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Vec<BoundImportInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut bound_imports = Vec::new();
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_BOUND_IMPORT);
+        let directory_size = data_dir.get_data_directory_size(DIRECTORY_BOUND_IMPORT);
+        if directory_rva == 0 || directory_size == 0 {
+            return Ok(bound_imports);
+        }
+        // 绑定导入目录通常位于PE头之后、第一个节之前，此时RVA即文件偏移
+        let first_section_rva = (0..section_headers.0.len())
+            .map(|i| section_headers.get_section_virtual_address(i))
+            .min()
+            .unwrap_or(u32::MAX);
+        let directory_fo = if directory_rva < first_section_rva {
+            directory_rva
+        } else {
+            match rva_2_fo(nt_head, section_headers, directory_rva) {
+                Some(fo) => fo,
+                None => return Ok(bound_imports),
+            }
+        };
+        let entry_size = size_of::<ImageBoundImportDescriptor>() as u32;
+        let mut entry_fo = directory_fo;
+        for _ in 0..MAX_BOUND_IMPORT_DESCRIPTORS {
+            let descriptor = ImageBoundImportDescriptor::new(file, entry_fo)?;
+            if descriptor.time_date_stamp == 0 && descriptor.offset_module_name == 0 {
+                break;
+            }
+            entry_fo = entry_fo.saturating_add(entry_size);
+            let mut forwarder_refs = Vec::new();
+            for _ in 0..descriptor.number_of_module_forwarder_refs {
+                let forwarder_ref = ImageBoundForwarderRef::new(file, entry_fo)?;
+                entry_fo = entry_fo.saturating_add(entry_size);
+                forwarder_refs.push(BoundForwarderRef {
+                    time_date_stamp: forwarder_ref.time_date_stamp,
+                    module_name: read_module_name(
+                        file,
+                        directory_fo.saturating_add(forwarder_ref.offset_module_name as u32),
+                    )?,
+                });
+            }
+            bound_imports.push(BoundImportInfo {
+                time_date_stamp: descriptor.time_date_stamp,
+                module_name: read_module_name(
+                    file,
+                    directory_fo.saturating_add(descriptor.offset_module_name as u32),
+                )?,
+                forwarder_refs,
+            });
+        }
+        Ok(bound_imports)
+    }
+}
+
+/// 名称偏移相对于绑定导入目录起始
+fn read_module_name(file: &mut File, address: u32) -> anyhow::Result<String> {
+    let mut name = [0u8; 256];
+    file.seek(SeekFrom::Start(address as u64))?;
+    let length = file.read(&mut name)?;
+    let end = name[..length]
+        .iter()
+        .position(|&x| x == 0)
+        .unwrap_or(length);
+    Ok(String::from_utf8_lossy(&name[..end]).to_string())
+}
+
+/// 只在样本所在目录中查找DLL并读取其文件头时间戳，找不到时返回None
+/// 分析机上的系统DLL与样本运行环境无关，不参与比较
+fn get_dll_time_date_stamp(exe_path: &Path, dll_name: &str) -> Option<u32> {
+    let dll_path = exe_path.parent()?.join(dll_name);
+    let mut file = File::open(dll_path).ok()?;
+    let dos_header = ImageDosHeader::new(&mut file).ok()?;
+    let file_header = ImageFileHeader::new(&mut file, &dos_header).ok()?;
+    Some(file_header.time_date_stamp)
+}
+
+impl ImportDll {
+    /// 关联绑定导入目录中的项，并检查绑定是否已经过期
+    pub(crate) fn bind(&mut self, bound_imports: &[BoundImportInfo], exe_path: &Path) {
+        if self.time_date_stamp == 0 {
+            return;
+        }
+        self.bound_import = bound_imports
+            .iter()
+            .find(|bound| bound.module_name.eq_ignore_ascii_case(&self.name))
+            .cloned();
+        let bound_time_date_stamp = match (&self.bound_import, self.time_date_stamp) {
+            (Some(bound), BOUND_NEW_STYLE) => bound.time_date_stamp,
+            // 新式绑定却没有对应的绑定导入项，IAT中的地址无从校验
+            (None, BOUND_NEW_STYLE) => {
+                self.stale_binding = true;
+                return;
+            }
+            // 旧式绑定直接把时间戳写在导入描述符中
+            (_, time_date_stamp) => time_date_stamp,
+        };
+        if let Some(bound) = &self.bound_import
+            && bound.time_date_stamp != bound_time_date_stamp
+        {
+            self.stale_binding = true;
+            return;
+        }
+        let mut targets = vec![(self.name.as_str(), bound_time_date_stamp)];
+        if let Some(bound) = &self.bound_import {
+            targets.extend(
+                bound
+                    .forwarder_refs
+                    .iter()
+                    .map(|forwarder| (forwarder.module_name.as_str(), forwarder.time_date_stamp)),
+            );
+        }
+        self.stale_binding = targets.iter().any(|(name, time_date_stamp)| {
+            get_dll_time_date_stamp(exe_path, name).is_some_and(|actual| actual != *time_date_stamp)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+    use std::path::PathBuf;
+
+    /// steam_api.dll 的文件头时间戳
+    const STEAM_API_TIME_DATE_STAMP: u32 = 0x619D_3C80;
+    /// 借用 .reloc 节存放构造的绑定导入目录
+    const DIRECTORY_RVA: u32 = 0x40000;
+    const DIRECTORY_FO: usize = 0x3C600;
+    /// 绑定导入数据目录项及 KERNEL32.dll 导入描述符时间戳的文件偏移
+    const BOUND_IMPORT_ENTRY_FO: usize = 0x1A8 + (11 - 5) * 8;
+    const KERNEL32_TIME_DATE_STAMP_FO: usize = 0x3A70C + 4;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// 为 KERNEL32.dll 构造带一个转发引用的新式绑定，样本写入独立的临时目录
+    fn bound_sample(dir_name: &str, time_date_stamp: u32) -> PathBuf {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        data[DIRECTORY_FO..DIRECTORY_FO + 0x40].fill(0);
+        put(
+            &mut data,
+            BOUND_IMPORT_ENTRY_FO,
+            &DIRECTORY_RVA.to_le_bytes(),
+        );
+        put(&mut data, BOUND_IMPORT_ENTRY_FO + 4, &0x40u32.to_le_bytes());
+        put(
+            &mut data,
+            KERNEL32_TIME_DATE_STAMP_FO,
+            &BOUND_NEW_STYLE.to_le_bytes(),
+        );
+        put(&mut data, DIRECTORY_FO, &time_date_stamp.to_le_bytes());
+        put(&mut data, DIRECTORY_FO + 4, &0x18u16.to_le_bytes());
+        put(&mut data, DIRECTORY_FO + 6, &1u16.to_le_bytes());
+        put(&mut data, DIRECTORY_FO + 8, &0x2222_2222u32.to_le_bytes());
+        put(&mut data, DIRECTORY_FO + 12, &0x25u16.to_le_bytes());
+        put(&mut data, DIRECTORY_FO + 0x18, b"KERNEL32.dll\0NTDLL.DLL\0");
+
+        let dir = std::env::temp_dir().join(dir_name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sample.dll");
+        std::fs::write(&path, &data).unwrap();
+        path
+    }
+
+    fn kernel32_of(path: &Path) -> ImportDll {
+        let file_info = FileInfo::new(path.to_path_buf()).unwrap();
+        let imports = file_info.get_imports().unwrap();
+        let imports = imports.0.borrow();
+        assert!(
+            imports
+                .iter()
+                .filter(|dll| dll.name != "KERNEL32.dll")
+                .all(|dll| dll.bound_import.is_none() && !dll.stale_binding)
+        );
+        imports
+            .iter()
+            .find(|dll| dll.name == "KERNEL32.dll")
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bound_import_directory() {
+        let path = bound_sample("penguin_bound_import", STEAM_API_TIME_DATE_STAMP);
+        // 样本目录中没有 KERNEL32.dll，不会去比较分析机上的同名DLL
+        let kernel32 = kernel32_of(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        let bound = kernel32.bound_import.unwrap();
+        assert_eq!(bound.time_date_stamp, STEAM_API_TIME_DATE_STAMP);
+        assert_eq!(bound.module_name, "KERNEL32.dll");
+        assert_eq!(
+            bound.forwarder_refs,
+            [BoundForwarderRef {
+                time_date_stamp: 0x2222_2222,
+                module_name: "NTDLL.DLL".to_string(),
+            }]
+        );
+        assert!(!kernel32.stale_binding);
+    }
+
+    #[test]
+    fn test_bound_import_compares_sample_directory() {
+        for (dir_name, time_date_stamp, stale) in [
+            (
+                "penguin_bound_import_fresh",
+                STEAM_API_TIME_DATE_STAMP,
+                false,
+            ),
+            ("penguin_bound_import_stale", 0x1234_5678, true),
+        ] {
+            let path = bound_sample(dir_name, time_date_stamp);
+            // 用 steam_api.dll 冒充样本旁边的 KERNEL32.dll
+            std::fs::copy("test_pe/steam_api.dll", path.with_file_name("KERNEL32.dll")).unwrap();
+            let kernel32 = kernel32_of(&path);
+            std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+            assert_eq!(kernel32.stale_binding, stale);
+        }
+    }
+
+    #[test]
+    fn test_bind_old_style() {
+        let mut dll = ImportDll {
+            name: "steam_api.dll".to_string(),
+            time_date_stamp: 1,
+            ..Default::default()
+        };
+        // 样本目录中存在该DLL时才比较时间戳
+        dll.bind(&[], Path::new("test_pe/sample.exe"));
+        assert!(dll.stale_binding);
+        dll.time_date_stamp = STEAM_API_TIME_DATE_STAMP;
+        dll.bind(&[], Path::new("test_pe/sample.exe"));
+        assert!(!dll.stale_binding);
+        dll.time_date_stamp = 1;
+        dll.bind(&[], &std::env::temp_dir().join("sample.exe"));
+        assert!(!dll.stale_binding);
+    }
+
+    #[test]
+    fn test_bind_new_style_without_descriptor() {
+        let mut dll = ImportDll {
+            name: "KERNEL32.dll".to_string(),
+            time_date_stamp: BOUND_NEW_STYLE,
+            ..Default::default()
+        };
+        dll.bind(&[], Path::new("test_pe/sample.exe"));
+        assert!(dll.bound_import.is_none());
+        assert!(dll.stale_binding);
+    }
+}
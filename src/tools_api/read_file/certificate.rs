@@ -0,0 +1,451 @@
+use crate::tools_api::AuthenticodeHash;
+use crate::tools_api::read_file::der::{
+    DerReader, DerValue, TAG_CONTEXT_0, TAG_CONTEXT_1, TAG_GENERALIZED_TIME, TAG_INTEGER,
+    TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE, TAG_SET, TAG_UTC_TIME, get_oid_name,
+};
+use crate::tools_api::read_file::nt_header::DIRECTORY_SECURITY;
+use crate::tools_api::read_file::{
+    AuthenticodeSignature, CertificateInfo, CertificateTable, CounterSignature, DataDirectory,
+    SignerInfo, WinCertificate, WinCertificateHeader,
+};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+use std::rc::Rc;
+
+const WIN_CERT_TYPE_X509: u16 = 0x0001;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+const WIN_CERT_TYPE_RESERVED_1: u16 = 0x0003;
+const WIN_CERT_TYPE_TS_STACK_SIGNED: u16 = 0x0004;
+/// 证书表大小来自文件本身，防止畸形文件导致读取过多
+const MAX_CERTIFICATE_TABLE_SIZE: u32 = 0x100_0000;
+/// 嵌套签名本身还可以再嵌套，限制深度防止构造的数据耗尽栈空间
+const MAX_NESTED_SIGNATURE_DEPTH: usize = 8;
+
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const OID_COUNTER_SIGNATURE: &str = "1.2.840.113549.1.9.6";
+const OID_NESTED_SIGNATURE: &str = "1.3.6.1.4.1.311.2.4.1";
+const OID_RFC3161_TIMESTAMP: &str = "1.3.6.1.4.1.311.3.3.1";
+
+impl WinCertificateHeader {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut header = MaybeUninit::<WinCertificateHeader>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                header.as_mut_ptr() as *mut u8,
+                size_of::<WinCertificateHeader>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(header.assume_init())
+        }
+    }
+}
+
+impl WinCertificate {
+    /// 证书类型名称
+    pub fn get_type_name(&self) -> &'static str {
+        match self.certificate_type {
+            WIN_CERT_TYPE_X509 => "X509",
+            WIN_CERT_TYPE_PKCS_SIGNED_DATA => "PKCS_SIGNED_DATA",
+            WIN_CERT_TYPE_RESERVED_1 => "RESERVED_1",
+            WIN_CERT_TYPE_TS_STACK_SIGNED => "TS_STACK_SIGNED",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// 版本名称
+    pub fn get_revision_name(&self) -> &'static str {
+        match self.revision {
+            0x0100 => "REVISION_1_0",
+            0x0200 => "REVISION_2_0",
+            _ => "UNKNOWN",
+        }
+    }
+}
+
+/// 解码后的 SignedData，签名者信息留到调用者按需解析
+struct SignedData<'a> {
+    digest_algorithm: String,
+    content_type: String,
+    content: Option<DerValue<'a>>,
+    certificates: Vec<CertificateInfo>,
+    signer_infos: Vec<DerValue<'a>>,
+}
+
+impl<'a> SignedData<'a> {
+    /// ContentInfo { contentType, [0] EXPLICIT SignedData }
+    fn parse(content_info: DerValue<'a>) -> Option<Self> {
+        let mut fields = content_info.reader();
+        if fields.read_tag(TAG_OID)?.to_oid() != OID_SIGNED_DATA {
+            return None;
+        }
+        let signed_data = fields
+            .read_tag(TAG_CONTEXT_0)?
+            .reader()
+            .read_tag(TAG_SEQUENCE)?;
+        let mut fields = signed_data.reader();
+        fields.read_tag(TAG_INTEGER)?;
+        let digest_algorithm = fields
+            .read_tag(TAG_SET)?
+            .reader()
+            .read_tag(TAG_SEQUENCE)
+            .map(|algorithm| algorithm.to_algorithm())
+            .unwrap_or_default();
+        let mut encapsulated = fields.read_tag(TAG_SEQUENCE)?.reader();
+        let content_type = encapsulated.read_tag(TAG_OID)?.to_oid();
+        let content = encapsulated
+            .read_optional(TAG_CONTEXT_0)
+            .and_then(|content| content.reader().read());
+        let certificates = match fields.read_optional(TAG_CONTEXT_0) {
+            Some(certificates) => {
+                let mut reader = certificates.reader();
+                std::iter::from_fn(|| reader.read())
+                    .filter(|certificate| certificate.tag == TAG_SEQUENCE)
+                    .filter_map(CertificateInfo::parse)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        fields.read_optional(TAG_CONTEXT_1);
+        let mut reader = fields.read_tag(TAG_SET)?.reader();
+        let signer_infos = std::iter::from_fn(|| reader.read_tag(TAG_SEQUENCE)).collect();
+        Some(SignedData {
+            digest_algorithm,
+            content_type,
+            content,
+            certificates,
+            signer_infos,
+        })
+    }
+}
+
+impl CertificateInfo {
+    /// Certificate { tbsCertificate, signatureAlgorithm, signature }
+    fn parse(certificate: DerValue) -> Option<Self> {
+        let mut fields = certificate.reader();
+        let tbs_certificate = fields.read_tag(TAG_SEQUENCE)?;
+        let signature_algorithm = fields.read_tag(TAG_SEQUENCE)?.to_algorithm();
+        let mut fields = tbs_certificate.reader();
+        fields.read_optional(TAG_CONTEXT_0);
+        let serial_number = fields.read_tag(TAG_INTEGER)?.to_serial_number();
+        fields.read_tag(TAG_SEQUENCE)?;
+        let issuer = fields.read_tag(TAG_SEQUENCE)?.to_name();
+        let mut validity = fields.read_tag(TAG_SEQUENCE)?.reader();
+        let not_before = validity.read()?.to_time();
+        let not_after = validity.read()?.to_time();
+        let subject = fields.read_tag(TAG_SEQUENCE)?.to_name();
+        Some(CertificateInfo {
+            subject,
+            issuer,
+            serial_number,
+            not_before,
+            not_after,
+            signature_algorithm,
+        })
+    }
+}
+
+/// 解码后的 SignerInfo 公共部分
+struct ParsedSigner<'a> {
+    issuer: String,
+    serial_number: String,
+    digest_algorithm: String,
+    signature_algorithm: String,
+    signing_time: String,
+    unauthenticated_attributes: Option<DerValue<'a>>,
+}
+
+impl<'a> ParsedSigner<'a> {
+    fn parse(signer_info: DerValue<'a>) -> Option<Self> {
+        let mut fields = signer_info.reader();
+        fields.read_tag(TAG_INTEGER)?;
+        // v3 的签名者用 [0] subjectKeyIdentifier 标识，此时没有颁发者和序列号
+        let identifier = fields.read()?;
+        let (issuer, serial_number) = if identifier.tag == TAG_SEQUENCE {
+            let mut identifier = identifier.reader();
+            let issuer = identifier.read_tag(TAG_SEQUENCE)?.to_name();
+            let serial_number = identifier.read_tag(TAG_INTEGER)?.to_serial_number();
+            (issuer, serial_number)
+        } else {
+            (String::new(), identifier.to_hex())
+        };
+        let digest_algorithm = fields.read_tag(TAG_SEQUENCE)?.to_algorithm();
+        let signing_time = fields
+            .read_optional(TAG_CONTEXT_0)
+            .and_then(|attributes| find_attribute(attributes, OID_SIGNING_TIME))
+            .and_then(|mut values| values.read())
+            .map(|time| time.to_time())
+            .unwrap_or_default();
+        let signature_algorithm = fields.read_tag(TAG_SEQUENCE)?.to_algorithm();
+        fields.read_tag(TAG_OCTET_STRING)?;
+        let unauthenticated_attributes = fields.read_optional(TAG_CONTEXT_1);
+        Some(ParsedSigner {
+            issuer,
+            serial_number,
+            digest_algorithm,
+            signature_algorithm,
+            signing_time,
+            unauthenticated_attributes,
+        })
+    }
+
+    /// 在证书列表中按颁发者和序列号查找签名者的主题
+    fn find_subject(&self, certificates: &[CertificateInfo]) -> String {
+        certificates
+            .iter()
+            .find(|certificate| {
+                certificate.issuer == self.issuer && certificate.serial_number == self.serial_number
+            })
+            .map(|certificate| certificate.subject.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// 在属性集合中查找指定类型，返回其值集合
+fn find_attribute<'a>(attributes: DerValue<'a>, oid: &str) -> Option<DerReader<'a>> {
+    let mut reader = attributes.reader();
+    std::iter::from_fn(|| reader.read_tag(TAG_SEQUENCE)).find_map(|attribute| {
+        let mut fields = attribute.reader();
+        if fields.read_tag(TAG_OID)?.to_oid() != oid {
+            return None;
+        }
+        Some(fields.read_tag(TAG_SET)?.reader())
+    })
+}
+
+impl CounterSignature {
+    /// PKCS#9 countersignature，值为一个 SignerInfo，证书位于外层签名中
+    fn parse_pkcs9(signer_info: DerValue, certificates: &[CertificateInfo]) -> Option<Self> {
+        let signer = ParsedSigner::parse(signer_info)?;
+        Some(CounterSignature {
+            kind: "PKCS#9".to_string(),
+            signing_time: signer.signing_time.clone(),
+            subject: signer.find_subject(certificates),
+            issuer: signer.issuer,
+            serial_number: signer.serial_number,
+            digest_algorithm: signer.digest_algorithm,
+        })
+    }
+
+    /// RFC3161 时间戳，值为一个完整的 SignedData，签名时间取自 TSTInfo.genTime
+    fn parse_rfc3161(content_info: DerValue) -> Option<Self> {
+        let signed_data = SignedData::parse(content_info)?;
+        let signer = ParsedSigner::parse(*signed_data.signer_infos.first()?)?;
+        let signing_time = signed_data
+            .content
+            .filter(|content| content.tag == TAG_OCTET_STRING)
+            .and_then(|content| content.reader().read_tag(TAG_SEQUENCE))
+            .and_then(|tst_info| {
+                let mut reader = tst_info.reader();
+                std::iter::from_fn(|| reader.read())
+                    .find(|field| field.tag == TAG_GENERALIZED_TIME || field.tag == TAG_UTC_TIME)
+            })
+            .map(|time| time.to_time())
+            .unwrap_or(signer.signing_time.clone());
+        Some(CounterSignature {
+            kind: "RFC3161".to_string(),
+            signing_time,
+            subject: signer.find_subject(&signed_data.certificates),
+            issuer: signer.issuer,
+            serial_number: signer.serial_number,
+            digest_algorithm: signer.digest_algorithm,
+        })
+    }
+}
+
+impl AuthenticodeSignature {
+    /// 解码 PKCS#7 SignedData，格式不符时返回None
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        Self::from_content_info(DerReader::new(data).read_tag(TAG_SEQUENCE)?, 0)
+    }
+
+    fn from_content_info(content_info: DerValue, depth: usize) -> Option<Self> {
+        let signed_data = SignedData::parse(content_info)?;
+        let mut signature = AuthenticodeSignature {
+            digest_algorithm: signed_data.digest_algorithm,
+            content_type: get_oid_name(&signed_data.content_type),
+            certificates: signed_data.certificates,
+            ..Default::default()
+        };
+        // SpcIndirectDataContent { data, messageDigest DigestInfo }
+        if let Some(digest_info) = signed_data
+            .content
+            .filter(|content| content.tag == TAG_SEQUENCE)
+            .and_then(|content| {
+                let mut fields = content.reader();
+                fields.read_tag(TAG_SEQUENCE)?;
+                fields.read_tag(TAG_SEQUENCE)
+            })
+        {
+            let mut fields = digest_info.reader();
+            if let Some(algorithm) = fields.read_tag(TAG_SEQUENCE) {
+                signature.image_digest_algorithm = algorithm.to_algorithm();
+            }
+            if let Some(digest) = fields.read_tag(TAG_OCTET_STRING) {
+                signature.image_digest = digest.to_hex().to_lowercase();
+            }
+        }
+        for signer_info in signed_data.signer_infos {
+            let Some(signer) = ParsedSigner::parse(signer_info) else {
+                continue;
+            };
+            let mut counter_signatures = Vec::new();
+            if let Some(attributes) = signer.unauthenticated_attributes {
+                let mut reader = attributes.reader();
+                while let Some(attribute) = reader.read_tag(TAG_SEQUENCE) {
+                    let mut fields = attribute.reader();
+                    let (Some(oid), Some(values)) =
+                        (fields.read_tag(TAG_OID), fields.read_tag(TAG_SET))
+                    else {
+                        continue;
+                    };
+                    let mut values = values.reader();
+                    while let Some(value) = values.read() {
+                        match oid.to_oid().as_str() {
+                            OID_COUNTER_SIGNATURE => counter_signatures.extend(
+                                CounterSignature::parse_pkcs9(value, &signature.certificates),
+                            ),
+                            OID_RFC3161_TIMESTAMP => {
+                                counter_signatures.extend(CounterSignature::parse_rfc3161(value))
+                            }
+                            OID_NESTED_SIGNATURE if depth < MAX_NESTED_SIGNATURE_DEPTH => signature
+                                .nested_signatures
+                                .extend(Self::from_content_info(value, depth + 1)),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            signature.signers.push(SignerInfo {
+                subject: signer.find_subject(&signature.certificates),
+                issuer: signer.issuer,
+                serial_number: signer.serial_number,
+                digest_algorithm: signer.digest_algorithm,
+                signature_algorithm: signer.signature_algorithm,
+                counter_signatures,
+            });
+        }
+        Some(signature)
+    }
+}
+
+impl AuthenticodeSignature {
+    /// 用重新计算的映像哈希校验签名中的摘要，嵌套签名一并校验
+    fn verify_image_digest(&mut self, image_hash: &AuthenticodeHash) {
+        let computed = match self.image_digest_algorithm.as_str() {
+            "sha1" => Some(&image_hash.sha1),
+            "sha256" => Some(&image_hash.sha256),
+            _ => None,
+        };
+        self.image_digest_matches =
+            computed.map(|computed| computed.eq_ignore_ascii_case(&self.image_digest));
+        for nested in self.nested_signatures.iter_mut() {
+            nested.verify_image_digest(image_hash);
+        }
+    }
+}
+
+impl CertificateTable {
+    /// 读取 IMAGE_DIRECTORY_ENTRY_SECURITY 下的所有 WIN_CERTIFICATE，目录地址为文件偏移
+    pub fn new(file: &mut File, data_dir: &DataDirectory) -> anyhow::Result<CertificateTable> {
+        let mut certificates = Vec::new();
+        let table_offset = data_dir.get_data_directory_virtual_address(DIRECTORY_SECURITY);
+        let table_size = data_dir.get_data_directory_size(DIRECTORY_SECURITY);
+        let file_size = file.metadata()?.len();
+        if table_offset == 0
+            || table_size == 0
+            || table_size > MAX_CERTIFICATE_TABLE_SIZE
+            || table_offset as u64 + table_size as u64 > file_size
+        {
+            return Ok(CertificateTable::default());
+        }
+        let header_size = size_of::<WinCertificateHeader>() as u32;
+        let mut offset = 0u32;
+        while table_size - offset >= header_size {
+            let Some(file_offset) = table_offset.checked_add(offset) else {
+                break;
+            };
+            let header = WinCertificateHeader::new(file, file_offset)?;
+            if header.length < header_size || header.length > table_size - offset {
+                break;
+            }
+            let mut data = vec![0u8; (header.length - header_size) as usize];
+            file.read_exact(&mut data)?;
+            let signature = if header.certificate_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+                AuthenticodeSignature::parse(&data)
+            } else {
+                None
+            };
+            certificates.push(WinCertificate {
+                file_offset,
+                length: header.length,
+                revision: header.revision,
+                certificate_type: header.certificate_type,
+                signature,
+            });
+            // 每一项按8字节对齐
+            offset = offset
+                .saturating_add(header.length.saturating_add(7) & !7)
+                .min(table_size);
+        }
+        Ok(CertificateTable(Rc::new(RefCell::new(certificates))))
+    }
+
+    /// 是否包含可以校验的 PKCS#7 签名
+    pub fn is_signed(&self) -> bool {
+        self.0
+            .borrow()
+            .iter()
+            .any(|certificate| certificate.signature.is_some())
+    }
+
+    pub(crate) fn verify_image_digest(&self, image_hash: &AuthenticodeHash) {
+        for certificate in self.0.borrow_mut().iter_mut() {
+            if let Some(signature) = certificate.signature.as_mut() {
+                signature.verify_image_digest(image_hash);
+            }
+        }
+    }
+
+    pub fn fclone(&self) -> Self {
+        CertificateTable(Rc::clone(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_signed_data() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        let certificates = file_info.get_certificates().unwrap();
+        let certificates = certificates.0.borrow();
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates[0].file_offset, 0x3E000);
+        let signature = certificates[0].signature.as_ref().unwrap();
+        assert_eq!(signature.content_type, "SPC_INDIRECT_DATA");
+        assert_eq!(signature.image_digest_algorithm, "sha256");
+        assert_eq!(
+            signature.image_digest,
+            "7c795ebe43a8a13dc1ff5e33f85ffad798f155c3d3f8f3294c266ba3be67dcd7"
+        );
+        assert_eq!(signature.certificates.len(), 5);
+        assert!(signature.nested_signatures.is_empty());
+
+        let signer = &signature.signers[0];
+        assert_eq!(
+            signer.subject,
+            "C=US, ST=Washington, L=Bellevue, O=Valve Corp., CN=Valve Corp."
+        );
+        assert_eq!(signer.serial_number, "0689B3BCEB4409890A32D71976B132A4");
+        let counter_signature = &signer.counter_signatures[0];
+        assert_eq!(counter_signature.kind, "PKCS#9");
+        assert_eq!(counter_signature.signing_time, "2021-11-23 19:09:57 UTC");
+    }
+}
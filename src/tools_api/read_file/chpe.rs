@@ -0,0 +1,231 @@
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    ChpeCodeRange, ChpeCodeType, ChpeEntryPoint, ChpeMetadata, ChpeRedirection,
+    ImageSectionHeaders, rva_2_fo,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+
+/// 表项数量来自文件本身，防止畸形文件导致读取过多
+const MAX_TABLE_ENTRIES: u32 = 0x10_0000;
+const IMAGE_FILE_MACHINE_I386: u16 = 0x014C;
+
+const X86_FIELD_NAMES: [&str; 12] = [
+    "Version",
+    "CHPECodeAddressRangeOffset",
+    "CHPECodeAddressRangeCount",
+    "WowA64ExceptionHandlerFunctionPointer",
+    "WowA64DispatchCallFunctionPointer",
+    "WowA64DispatchIndirectCallFunctionPointer",
+    "WowA64DispatchIndirectCallCfgFunctionPointer",
+    "WowA64DispatchRetFunctionPointer",
+    "WowA64DispatchRetLeafFunctionPointer",
+    "WowA64DispatchJumpFunctionPointer",
+    "CompilerIATPointer",
+    "WowA64RdtscFunctionPointer",
+];
+const ARM64EC_FIELD_NAMES: [&str; 23] = [
+    "Version",
+    "CodeMap",
+    "CodeMapCount",
+    "CodeRangesToEntryPoints",
+    "RedirectionMetadata",
+    "__os_arm64x_dispatch_call_no_redirect",
+    "__os_arm64x_dispatch_ret",
+    "__os_arm64x_check_call",
+    "__os_arm64x_check_icall",
+    "__os_arm64x_check_icall_cfg",
+    "AlternateEntryPoint",
+    "AuxiliaryIAT",
+    "CodeRangesToEntryPointsCount",
+    "RedirectionMetadataCount",
+    "GetX64InformationFunctionPointer",
+    "SetX64InformationFunctionPointer",
+    "ExtraRFETable",
+    "ExtraRFETableSize",
+    "__os_arm64x_dispatch_fptr",
+    "AuxiliaryIATCopy",
+    "AuxiliaryDelayloadIAT",
+    "AuxiliaryDelayloadIATCopy",
+    "HybridImageInfoBitfield",
+];
+/// 各版本包含的字段数量，下标为版本号
+const X86_FIELD_COUNTS: [usize; 4] = [10, 10, 11, 12];
+const ARM64EC_FIELD_COUNTS: [usize; 3] = [20, 20, 23];
+
+const ARM64EC_CODE_MAP: usize = 1;
+const ARM64EC_CODE_MAP_COUNT: usize = 2;
+const ARM64EC_ENTRY_POINTS: usize = 3;
+const ARM64EC_REDIRECTIONS: usize = 4;
+const ARM64EC_ENTRY_POINTS_COUNT: usize = 12;
+const ARM64EC_REDIRECTIONS_COUNT: usize = 13;
+const X86_CODE_RANGES: usize = 1;
+const X86_CODE_RANGES_COUNT: usize = 2;
+
+impl ChpeCodeType {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ChpeCodeType::Arm64 => "ARM64",
+            ChpeCodeType::Arm64Ec => "ARM64EC",
+            ChpeCodeType::Amd64 => "x64",
+            ChpeCodeType::X86 => "x86",
+        }
+    }
+}
+
+impl ChpeMetadata {
+    /// 按CHPEMetadataPointer（VA）读取，指针为0或不在任何节中时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        metadata_va: u64,
+    ) -> anyhow::Result<Option<ChpeMetadata>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let image_base = nt_head.get_image_base();
+        if metadata_va <= image_base {
+            return Ok(None);
+        }
+        let Some(file_offset) =
+            rva_2_fo(nt_head, section_headers, (metadata_va - image_base) as u32)
+        else {
+            return Ok(None);
+        };
+        let is_x86 = nt_head.machine() == IMAGE_FILE_MACHINE_I386;
+        file.seek(SeekFrom::Start(file_offset as u64))?;
+        let version = file.read_u32::<LittleEndian>()?;
+        // 未知的新版本按已知的最新版本读取
+        let field_count = if is_x86 {
+            X86_FIELD_COUNTS[(version as usize).min(X86_FIELD_COUNTS.len() - 1)]
+        } else {
+            ARM64EC_FIELD_COUNTS[(version as usize).min(ARM64EC_FIELD_COUNTS.len() - 1)]
+        };
+        let mut fields = vec![version];
+        for _ in 1..field_count {
+            fields.push(file.read_u32::<LittleEndian>()?);
+        }
+        let reader = RvaTableReader {
+            nt_head,
+            section_headers,
+        };
+        let mut metadata = ChpeMetadata {
+            file_offset,
+            is_x86,
+            fields,
+            ..Default::default()
+        };
+        if is_x86 {
+            // 最低位为1时是ARM64原生代码
+            metadata.code_ranges = reader
+                .read::<2>(
+                    file,
+                    metadata.fields[X86_CODE_RANGES],
+                    metadata.fields[X86_CODE_RANGES_COUNT],
+                )?
+                .into_iter()
+                .map(|[start, length]| ChpeCodeRange {
+                    start_rva: start & !1,
+                    length,
+                    code_type: if start & 1 != 0 {
+                        ChpeCodeType::Arm64
+                    } else {
+                        ChpeCodeType::X86
+                    },
+                })
+                .collect();
+            return Ok(Some(metadata));
+        }
+        // 最低两位为代码类型
+        metadata.code_ranges = reader
+            .read::<2>(
+                file,
+                metadata.fields[ARM64EC_CODE_MAP],
+                metadata.fields[ARM64EC_CODE_MAP_COUNT],
+            )?
+            .into_iter()
+            .map(|[start, length]| ChpeCodeRange {
+                start_rva: start & !3,
+                length,
+                code_type: match start & 3 {
+                    0 => ChpeCodeType::Arm64,
+                    1 => ChpeCodeType::Arm64Ec,
+                    _ => ChpeCodeType::Amd64,
+                },
+            })
+            .collect();
+        metadata.entry_points = reader
+            .read::<3>(
+                file,
+                metadata.fields[ARM64EC_ENTRY_POINTS],
+                metadata.fields[ARM64EC_ENTRY_POINTS_COUNT],
+            )?
+            .into_iter()
+            .map(|[start_rva, end_rva, entry_point]| ChpeEntryPoint {
+                start_rva,
+                end_rva,
+                entry_point,
+            })
+            .collect();
+        metadata.redirections = reader
+            .read::<2>(
+                file,
+                metadata.fields[ARM64EC_REDIRECTIONS],
+                metadata.fields[ARM64EC_REDIRECTIONS_COUNT],
+            )?
+            .into_iter()
+            .map(|[source, destination]| ChpeRedirection {
+                source,
+                destination,
+            })
+            .collect();
+        Ok(Some(metadata))
+    }
+
+    /// 与 fields 一一对应的字段名
+    pub fn get_field_names(&self) -> &'static [&'static str] {
+        if self.is_x86 {
+            &X86_FIELD_NAMES
+        } else {
+            &ARM64EC_FIELD_NAMES
+        }
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.fields.first().copied().unwrap_or(0)
+    }
+}
+
+/// 读取以RVA给出、元素为N个u32的表
+struct RvaTableReader<'a, T: NtHeaders + ?Sized> {
+    nt_head: &'a T,
+    section_headers: &'a ImageSectionHeaders,
+}
+
+impl<T: NtHeaders + ?Sized> RvaTableReader<'_, T> {
+    fn read<const N: usize>(
+        &self,
+        file: &mut File,
+        table_rva: u32,
+        count: u32,
+    ) -> anyhow::Result<Vec<[u32; N]>> {
+        let mut entries = Vec::new();
+        if table_rva == 0 || count == 0 {
+            return Ok(entries);
+        }
+        let Some(table_fo) = rva_2_fo(self.nt_head, self.section_headers, table_rva) else {
+            return Ok(entries);
+        };
+        file.seek(SeekFrom::Start(table_fo as u64))?;
+        for _ in 0..count.min(MAX_TABLE_ENTRIES) {
+            let mut entry = [0u32; N];
+            for value in entry.iter_mut() {
+                *value = file.read_u32::<LittleEndian>()?;
+            }
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
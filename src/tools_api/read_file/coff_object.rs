@@ -0,0 +1,425 @@
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::nt_header::{MACHINE_32, MACHINE_64};
+use crate::tools_api::read_file::section_headers::SectionCharacteristics;
+use crate::tools_api::read_file::{
+    CoffAuxSymbol, CoffRelocation, CoffSectionDetail, CoffSymbol, CoffSymbolTable, ComdatInfo,
+    ImageCoffHeader, ImageFileHeader, ImageSectionHeader, ImageSectionHeaders,
+};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::mem::{MaybeUninit, size_of};
+
+/// IMAGE_RELOCATION 为10字节
+const RELOCATION_SIZE: u64 = 10;
+/// NumberOfRelocations 溢出时的固定值，实际数量保存在第一个重定位项中
+const RELOCATION_COUNT_OVERFLOW: u16 = 0xFFFF;
+const IMAGE_COMDAT_SELECT_ASSOCIATIVE: u8 = 5;
+
+const IMAGE_FILE_MACHINE_I386: u16 = 0x014C;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+const IMAGE_FILE_MACHINE_ARM: [u16; 3] = [0x01C0, 0x01C2, 0x01C4];
+
+const I386_RELOCATION_TYPES: [(u16, &str); 11] = [
+    (0x0000, "ABSOLUTE"),
+    (0x0001, "DIR16"),
+    (0x0002, "REL16"),
+    (0x0006, "DIR32"),
+    (0x0007, "DIR32NB"),
+    (0x0009, "SEG12"),
+    (0x000A, "SECTION"),
+    (0x000B, "SECREL"),
+    (0x000C, "TOKEN"),
+    (0x000D, "SECREL7"),
+    (0x0014, "REL32"),
+];
+const AMD64_RELOCATION_TYPES: [(u16, &str); 17] = [
+    (0x0000, "ABSOLUTE"),
+    (0x0001, "ADDR64"),
+    (0x0002, "ADDR32"),
+    (0x0003, "ADDR32NB"),
+    (0x0004, "REL32"),
+    (0x0005, "REL32_1"),
+    (0x0006, "REL32_2"),
+    (0x0007, "REL32_3"),
+    (0x0008, "REL32_4"),
+    (0x0009, "REL32_5"),
+    (0x000A, "SECTION"),
+    (0x000B, "SECREL"),
+    (0x000C, "SECREL7"),
+    (0x000D, "TOKEN"),
+    (0x000E, "SREL32"),
+    (0x000F, "PAIR"),
+    (0x0010, "SSPAN32"),
+];
+const ARM_RELOCATION_TYPES: [(u16, &str); 17] = [
+    (0x0000, "ABSOLUTE"),
+    (0x0001, "ADDR32"),
+    (0x0002, "ADDR32NB"),
+    (0x0003, "BRANCH24"),
+    (0x0004, "BRANCH11"),
+    (0x0005, "TOKEN"),
+    (0x0008, "BLX24"),
+    (0x0009, "BLX11"),
+    (0x000A, "REL32"),
+    (0x000E, "SECTION"),
+    (0x000F, "SECREL"),
+    (0x0010, "MOV32"),
+    (0x0011, "THUMB_MOV32"),
+    (0x0012, "THUMB_BRANCH20"),
+    (0x0014, "THUMB_BRANCH24"),
+    (0x0015, "THUMB_BLX23"),
+    (0x0016, "PAIR"),
+];
+const ARM64_RELOCATION_TYPES: [(u16, &str); 18] = [
+    (0x0000, "ABSOLUTE"),
+    (0x0001, "ADDR32"),
+    (0x0002, "ADDR32NB"),
+    (0x0003, "BRANCH26"),
+    (0x0004, "PAGEBASE_REL21"),
+    (0x0005, "REL21"),
+    (0x0006, "PAGEOFFSET_12A"),
+    (0x0007, "PAGEOFFSET_12L"),
+    (0x0008, "SECREL"),
+    (0x0009, "SECREL_LOW12A"),
+    (0x000A, "SECREL_HIGH12A"),
+    (0x000B, "SECREL_LOW12L"),
+    (0x000C, "TOKEN"),
+    (0x000D, "SECTION"),
+    (0x000E, "ADDR64"),
+    (0x000F, "BRANCH19"),
+    (0x0010, "BRANCH14"),
+    (0x0011, "REL32"),
+];
+const COMDAT_SELECTION_NAMES: [&str; 6] = [
+    "NODUPLICATES",
+    "ANY",
+    "SAME_SIZE",
+    "EXACT_MATCH",
+    "ASSOCIATIVE",
+    "LARGEST",
+];
+
+/// 按机器类型获取重定位类型名称
+fn get_relocation_type_name(machine: u16, relocation_type: u16) -> String {
+    let types: &[(u16, &str)] = match machine {
+        IMAGE_FILE_MACHINE_I386 => &I386_RELOCATION_TYPES,
+        IMAGE_FILE_MACHINE_AMD64 => &AMD64_RELOCATION_TYPES,
+        IMAGE_FILE_MACHINE_ARM64 => &ARM64_RELOCATION_TYPES,
+        machine if IMAGE_FILE_MACHINE_ARM.contains(&machine) => &ARM_RELOCATION_TYPES,
+        _ => &[],
+    };
+    types
+        .iter()
+        .find(|(value, _)| *value == relocation_type)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("0x{:04X}", relocation_type))
+}
+
+fn find_symbol(symbols: Option<&CoffSymbolTable>, index: u32) -> Option<&CoffSymbol> {
+    let symbols = &symbols?.symbols;
+    symbols
+        .binary_search_by_key(&index, |symbol| symbol.index)
+        .ok()
+        .map(|position| &symbols[position])
+}
+
+impl ImageCoffHeader {
+    /// 按COFF目标文件头读取文件开头，机器类型未知或存在可选头时返回None
+    pub fn new(file: &mut File) -> anyhow::Result<Option<ImageCoffHeader>> {
+        let file_size = file.metadata()?.len();
+        if file_size < size_of::<ImageFileHeader>() as u64 {
+            return Ok(None);
+        }
+        file.seek(SeekFrom::Start(0))?;
+        let file_header = unsafe {
+            let mut file_header = MaybeUninit::<ImageFileHeader>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                file_header.as_mut_ptr() as *mut u8,
+                size_of::<ImageFileHeader>(),
+            );
+            file.read_exact(bytes)?;
+            file_header.assume_init()
+        };
+        let machine = file_header.machine;
+        let section_table_end = size_of::<ImageFileHeader>() as u64
+            + file_header.number_of_sections as u64 * size_of::<ImageSectionHeader>() as u64;
+        if !(MACHINE_32.contains(&machine) || MACHINE_64.contains(&machine))
+            || file_header.size_of_optional_header != 0
+            || section_table_end > file_size
+        {
+            return Ok(None);
+        }
+        Ok(Some(ImageCoffHeader { file_header }))
+    }
+
+    pub fn is_64_bit(&self) -> bool {
+        MACHINE_64.contains(&self.file_header.machine)
+    }
+}
+
+impl ComdatInfo {
+    pub fn get_selection_name(&self) -> &'static str {
+        COMDAT_SELECTION_NAMES
+            .get((self.selection as usize).wrapping_sub(1))
+            .copied()
+            .unwrap_or("UNKNOWN")
+    }
+
+    /// 节符号之后的第一个同节符号即为COMDAT符号
+    fn new(section_number: i16, symbols: Option<&CoffSymbolTable>) -> Option<ComdatInfo> {
+        let mut section_symbols = symbols?
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.section_number == section_number);
+        let (selection, number) = section_symbols.find_map(|symbol| {
+            symbol.aux_symbols.iter().find_map(|aux| match aux {
+                CoffAuxSymbol::SectionDefinition {
+                    selection, number, ..
+                } => Some((*selection, *number)),
+                _ => None,
+            })
+        })?;
+        Some(ComdatInfo {
+            selection,
+            symbol_name: section_symbols
+                .next()
+                .map(|symbol| symbol.name.clone())
+                .unwrap_or_default(),
+            associated_section: (selection == IMAGE_COMDAT_SELECT_ASSOCIATIVE).then_some(number),
+        })
+    }
+}
+
+impl CoffSectionDetail {
+    /// 读取每个节的重定位项与COMDAT信息，所有节都没有时返回None
+    pub fn new_list<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        symbols: Option<&CoffSymbolTable>,
+    ) -> anyhow::Result<Option<Vec<CoffSectionDetail>>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let file_size = file.metadata()?.len();
+        let mut details = Vec::new();
+        for (index, section) in section_headers.0.iter().enumerate() {
+            let relocations =
+                Self::read_relocations(file, file_size, nt_head.machine(), section, symbols)?;
+            let comdat = if section.characteristics
+                & SectionCharacteristics::ImageScnLnkComdat as u32
+                != 0
+            {
+                ComdatInfo::new(index as i16 + 1, symbols)
+            } else {
+                None
+            };
+            details.push(CoffSectionDetail {
+                relocations,
+                comdat,
+            });
+        }
+        if details
+            .iter()
+            .all(|detail| detail.relocations.is_empty() && detail.comdat.is_none())
+        {
+            return Ok(None);
+        }
+        Ok(Some(details))
+    }
+
+    fn read_relocations(
+        file: &mut File,
+        file_size: u64,
+        machine: u16,
+        section: &ImageSectionHeader,
+        symbols: Option<&CoffSymbolTable>,
+    ) -> anyhow::Result<Vec<CoffRelocation>> {
+        if section.pointer_to_relocations == 0 || section.number_of_relocations == 0 {
+            return Ok(Vec::new());
+        }
+        let mut start = section.pointer_to_relocations as u64;
+        let mut count = section.number_of_relocations as u64;
+        file.seek(SeekFrom::Start(start))?;
+        if section.characteristics & SectionCharacteristics::ImageScnLnkNrelocOvfl as u32 != 0
+            && section.number_of_relocations == RELOCATION_COUNT_OVERFLOW
+        {
+            // 第一个重定位项的 VirtualAddress 为包含其自身在内的实际数量
+            let mut real_count = [0u8; 4];
+            file.read_exact(&mut real_count)?;
+            count = (u32::from_le_bytes(real_count) as u64).saturating_sub(1);
+            start += RELOCATION_SIZE;
+        }
+        // 数量可能被篡改，只读取文件中实际存在的部分
+        let size = (count * RELOCATION_SIZE).min(file_size.saturating_sub(start));
+        let mut data = Vec::new();
+        file.seek(SeekFrom::Start(start))?;
+        file.take(size).read_to_end(&mut data)?;
+        Ok(data
+            .chunks_exact(RELOCATION_SIZE as usize)
+            .map(|entry| {
+                let symbol_table_index =
+                    u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+                let relocation_type = u16::from_le_bytes([entry[8], entry[9]]);
+                CoffRelocation {
+                    virtual_address: u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                    symbol_table_index,
+                    relocation_type,
+                    type_name: get_relocation_type_name(machine, relocation_type),
+                    symbol_name: find_symbol(symbols, symbol_table_index)
+                        .map(|symbol| symbol.name.clone())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+
+    const SECTION_DATA: usize = 100;
+    const RELOCATIONS: usize = 108;
+    const SYMBOL_TABLE: usize = 118;
+    const STRING_TABLE: usize = SYMBOL_TABLE + 4 * 18;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// 两个节的x64目标文件：`/4` 长名称的COMDAT代码节带1个重定位项，.data 为空节
+    fn coff_object() -> Vec<u8> {
+        let mut data = vec![0u8; STRING_TABLE];
+        put(&mut data, 0, &IMAGE_FILE_MACHINE_AMD64.to_le_bytes());
+        put(&mut data, 2, &2u16.to_le_bytes());
+        put(&mut data, 8, &(SYMBOL_TABLE as u32).to_le_bytes());
+        put(&mut data, 12, &4u32.to_le_bytes());
+        put(&mut data, 20, b"/4");
+        put(&mut data, 36, &8u32.to_le_bytes());
+        put(&mut data, 40, &(SECTION_DATA as u32).to_le_bytes());
+        put(&mut data, 44, &(RELOCATIONS as u32).to_le_bytes());
+        put(&mut data, 52, &1u16.to_le_bytes());
+        put(&mut data, 56, &0x6000_1020u32.to_le_bytes());
+        put(&mut data, 60, b".data");
+        put(&mut data, 96, &0xC000_0040u32.to_le_bytes());
+        put(
+            &mut data,
+            SECTION_DATA,
+            &[0xE8, 0, 0, 0, 0, 0xC3, 0xCC, 0xCC],
+        );
+        // REL32，指向2号符号
+        put(&mut data, RELOCATIONS, &1u32.to_le_bytes());
+        put(&mut data, RELOCATIONS + 4, &2u32.to_le_bytes());
+        put(&mut data, RELOCATIONS + 8, &4u16.to_le_bytes());
+        // 节符号及其节定义辅助记录，Selection 为 ANY
+        let symbol = SYMBOL_TABLE;
+        put(&mut data, symbol, b".text$mn");
+        put(&mut data, symbol + 12, &1i16.to_le_bytes());
+        data[symbol + 16] = 3;
+        data[symbol + 17] = 1;
+        put(&mut data, symbol + 18, &8u32.to_le_bytes());
+        put(&mut data, symbol + 22, &1u16.to_le_bytes());
+        data[symbol + 18 + 14] = 2;
+        let symbol = SYMBOL_TABLE + 2 * 18;
+        put(&mut data, symbol + 4, &13u32.to_le_bytes());
+        put(&mut data, symbol + 12, &1i16.to_le_bytes());
+        put(&mut data, symbol + 14, &0x20u16.to_le_bytes());
+        data[symbol + 16] = 2;
+        let symbol = SYMBOL_TABLE + 3 * 18;
+        put(&mut data, symbol, b"main");
+        data[symbol + 16] = 2;
+        data.extend(32u32.to_le_bytes());
+        data.extend(b".text$mn\0long_function_name\0");
+        data
+    }
+
+    fn open_object(name: &str, data: &[u8]) -> Box<FileInfo> {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        file_info
+    }
+
+    #[test]
+    fn test_coff_object() {
+        let file_info = open_object("penguin_object.obj", &coff_object());
+        assert!(file_info.is_coff_object);
+        assert!(!file_info.is_pe_image());
+        assert_eq!(
+            file_info.section_headers.get_section_names(),
+            [".text$mn", ".data"]
+        );
+        let symbols = file_info.get_symbols().unwrap().unwrap();
+        assert_eq!(symbols.symbols.len(), 3);
+        let sections = file_info.get_coff_sections().unwrap().unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            sections[0].relocations,
+            vec![CoffRelocation {
+                virtual_address: 1,
+                symbol_table_index: 2,
+                relocation_type: 4,
+                type_name: "REL32".to_string(),
+                symbol_name: "long_function_name".to_string(),
+            }]
+        );
+        let comdat = sections[0].comdat.as_ref().unwrap();
+        assert_eq!(comdat.get_selection_name(), "ANY");
+        assert_eq!(comdat.symbol_name, "long_function_name");
+        assert_eq!(comdat.associated_section, None);
+        assert!(sections[1].relocations.is_empty());
+        assert!(sections[1].comdat.is_none());
+    }
+
+    #[test]
+    fn test_truncated_string_table() {
+        // 字符串表只剩长度字段的一部分，长名称无法解析
+        let mut data = coff_object();
+        data.truncate(STRING_TABLE + 2);
+        let file_info = open_object("penguin_object_truncated.obj", &data);
+        assert_eq!(
+            file_info.section_headers.get_section_names(),
+            ["/4", ".data"]
+        );
+        let sections = file_info.get_coff_sections().unwrap().unwrap();
+        assert_eq!(sections[0].relocations[0].symbol_name, "");
+        assert_eq!(sections[0].comdat.as_ref().unwrap().symbol_name, "");
+    }
+
+    #[test]
+    fn test_truncated_relocations() {
+        // 重定位数量被篡改时只读取文件中存在的部分
+        let mut data = coff_object();
+        put(&mut data, 52, &100u16.to_le_bytes());
+        let file_info = open_object("penguin_object_relocations.obj", &data);
+        let sections = file_info.get_coff_sections().unwrap().unwrap();
+        assert_eq!(
+            sections[0].relocations.len(),
+            (data.len() - RELOCATIONS) / RELOCATION_SIZE as usize
+        );
+    }
+
+    #[test]
+    fn test_not_coff_object() {
+        let path = std::env::temp_dir().join("penguin_not_object.obj");
+        // 存在可选头的文件不是目标文件
+        let mut data = coff_object();
+        put(&mut data, 16, &0xE0u16.to_le_bytes());
+        std::fs::write(&path, &data).unwrap();
+        let mut file = File::open(&path).unwrap();
+        assert!(ImageCoffHeader::new(&mut file).unwrap().is_none());
+        // 节表超出文件末尾
+        let mut data = coff_object();
+        put(&mut data, 2, &100u16.to_le_bytes());
+        std::fs::write(&path, &data).unwrap();
+        let mut file = File::open(&path).unwrap();
+        assert!(ImageCoffHeader::new(&mut file).unwrap().is_none());
+        let mut file = File::open("test_pe/steam_api.dll").unwrap();
+        assert!(ImageCoffHeader::new(&mut file).unwrap().is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
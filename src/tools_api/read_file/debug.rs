@@ -0,0 +1,463 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_DEBUG;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    CodeViewInfo, DataDirectory, DebugData, DebugEntry, DebugTable, ImageDebugDirectory,
+    ImageSectionHeaders, PogoEntry, PogoInfo, ReproInfo, VcFeatureInfo, rva_2_fo,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::mem::{MaybeUninit, size_of};
+use std::rc::Rc;
+
+const DEBUG_TYPE_CODEVIEW: u32 = 2;
+const DEBUG_TYPE_VC_FEATURE: u32 = 12;
+const DEBUG_TYPE_POGO: u32 = 13;
+const DEBUG_TYPE_REPRO: u32 = 16;
+const DEBUG_TYPE_EX_DLLCHARACTERISTICS: u32 = 20;
+const CODEVIEW_RSDS: &[u8; 4] = b"RSDS";
+const CODEVIEW_NB10: &[u8; 4] = b"NB10";
+/// 调试数据大小来自文件本身，防止畸形文件导致读取过多
+const MAX_DEBUG_DATA_SIZE: u32 = 0x10_0000;
+
+impl ImageDebugDirectory {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut debug_directory = MaybeUninit::<ImageDebugDirectory>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                debug_directory.as_mut_ptr() as *mut u8,
+                size_of::<ImageDebugDirectory>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(debug_directory.assume_init())
+        }
+    }
+}
+
+impl DebugEntry {
+    /// 调试类型名称
+    pub fn get_type_name(&self) -> &'static str {
+        match self.debug_type {
+            0 => "UNKNOWN",
+            1 => "COFF",
+            2 => "CODEVIEW",
+            3 => "FPO",
+            4 => "MISC",
+            5 => "EXCEPTION",
+            6 => "FIXUP",
+            7 => "OMAP_TO_SRC",
+            8 => "OMAP_FROM_SRC",
+            9 => "BORLAND",
+            10 => "RESERVED10",
+            11 => "CLSID",
+            12 => "VC_FEATURE",
+            13 => "POGO",
+            14 => "ILTCG",
+            15 => "MPX",
+            16 => "REPRO",
+            17 => "EMBEDDED_PORTABLE_PDB",
+            19 => "SPGO",
+            20 => "EX_DLLCHARACTERISTICS",
+            _ => "RESERVED",
+        }
+    }
+
+    /// EX_DLLCHARACTERISTICS 中已置位的标志名称
+    pub fn get_ex_dll_characteristics_names(&self) -> Vec<&'static str> {
+        const EX_DLL_CHARACTERISTICS: [(u32, &str); 6] = [
+            (0x01, "CET_COMPAT"),
+            (0x02, "CET_COMPAT_STRICT_MODE"),
+            (0x04, "CET_SET_CONTEXT_IP_VALIDATION_RELAXED_MODE"),
+            (0x08, "CET_DYNAMIC_APIS_ALLOW_IN_PROC"),
+            (0x40, "FORWARD_CFI_COMPAT"),
+            (0x80, "HOTPATCH_COMPATIBLE"),
+        ];
+        let DebugData::ExDllCharacteristics(value) = self.data else {
+            return Vec::new();
+        };
+        EX_DLL_CHARACTERISTICS
+            .iter()
+            .filter(|(flag, _)| value & flag != 0)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    /// 读取并按类型解码调试数据，数据不可读时返回 DebugData::Unknown
+    fn read_data<T>(
+        &self,
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+    ) -> DebugData
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let data_fo = if self.pointer_to_raw_data != 0 {
+            Some(self.pointer_to_raw_data)
+        } else {
+            rva_2_fo(nt_head, section_headers, self.address_of_raw_data)
+        };
+        let data_fo = match data_fo {
+            Some(fo) if self.size_of_data <= MAX_DEBUG_DATA_SIZE => fo,
+            _ => return DebugData::Unknown,
+        };
+        let mut buf = vec![0u8; self.size_of_data as usize];
+        if file.seek(SeekFrom::Start(data_fo as u64)).is_err() || file.read_exact(&mut buf).is_err()
+        {
+            return DebugData::Unknown;
+        }
+        let data = match self.debug_type {
+            DEBUG_TYPE_CODEVIEW => CodeViewInfo::parse(&buf).map(DebugData::CodeView),
+            DEBUG_TYPE_POGO => PogoInfo::parse(&buf).map(DebugData::Pogo),
+            DEBUG_TYPE_VC_FEATURE => VcFeatureInfo::parse(&buf).map(DebugData::VcFeature),
+            DEBUG_TYPE_REPRO => Ok(DebugData::Repro(ReproInfo::parse(&buf))),
+            DEBUG_TYPE_EX_DLLCHARACTERISTICS => Cursor::new(&buf)
+                .read_u32::<LittleEndian>()
+                .map(DebugData::ExDllCharacteristics),
+            _ => return DebugData::Unknown,
+        };
+        data.unwrap_or_default()
+    }
+}
+
+/// 读取以NUL结尾的字符串，不含NUL
+fn read_c_string(buf: &[u8]) -> String {
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).to_string()
+}
+
+impl CodeViewInfo {
+    fn parse(buf: &[u8]) -> std::io::Result<Self> {
+        let mut cursor = Cursor::new(buf);
+        let mut signature = [0u8; 4];
+        cursor.read_exact(&mut signature)?;
+        let mut info = CodeViewInfo {
+            signature: String::from_utf8_lossy(&signature).to_string(),
+            ..Default::default()
+        };
+        match &signature {
+            CODEVIEW_RSDS => {
+                let data1 = cursor.read_u32::<LittleEndian>()?;
+                let data2 = cursor.read_u16::<LittleEndian>()?;
+                let data3 = cursor.read_u16::<LittleEndian>()?;
+                let mut data4 = [0u8; 8];
+                cursor.read_exact(&mut data4)?;
+                info.guid = format!(
+                    "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{}",
+                    data1,
+                    data2,
+                    data3,
+                    data4[0],
+                    data4[1],
+                    data4[2..]
+                        .iter()
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<String>()
+                );
+                info.age = cursor.read_u32::<LittleEndian>()?;
+            }
+            CODEVIEW_NB10 => {
+                // NB10 在签名后还有一个始终为0的偏移字段
+                cursor.read_u32::<LittleEndian>()?;
+                info.nb10_signature = cursor.read_u32::<LittleEndian>()?;
+                info.age = cursor.read_u32::<LittleEndian>()?;
+            }
+            _ => return Ok(info),
+        }
+        info.pdb_path = read_c_string(&buf[cursor.position() as usize..]);
+        Ok(info)
+    }
+
+    /// 符号服务器使用的索引，RSDS 为去掉横线的GUID加age，NB10 为时间戳加age
+    pub fn get_symbol_server_key(&self) -> String {
+        if self.guid.is_empty() {
+            format!("{:08X}{:X}", self.nb10_signature, self.age)
+        } else {
+            format!("{}{:X}", self.guid.replace('-', ""), self.age)
+        }
+    }
+}
+
+impl PogoInfo {
+    fn parse(buf: &[u8]) -> std::io::Result<Self> {
+        let mut cursor = Cursor::new(buf);
+        let mut signature = [0u8; 4];
+        cursor.read_exact(&mut signature)?;
+        let mut info = PogoInfo {
+            signature: read_c_string(&signature),
+            entries: Vec::new(),
+        };
+        // 每项为 RVA、大小和以NUL结尾的名称，名称按4字节对齐
+        while (cursor.position() as usize) + 8 < buf.len() {
+            let rva = cursor.read_u32::<LittleEndian>()?;
+            let size = cursor.read_u32::<LittleEndian>()?;
+            let name_start = cursor.position() as usize;
+            let name_len = buf[name_start..]
+                .iter()
+                .position(|&b| b == 0)
+                .unwrap_or(buf.len() - name_start);
+            let name_end = (name_start + name_len + 1 + 3) & !3;
+            cursor.set_position(name_end as u64);
+            info.entries.push(PogoEntry {
+                rva,
+                size,
+                name: read_c_string(&buf[name_start..]),
+            });
+        }
+        Ok(info)
+    }
+}
+
+impl VcFeatureInfo {
+    fn parse(buf: &[u8]) -> std::io::Result<Self> {
+        let mut cursor = Cursor::new(buf);
+        Ok(VcFeatureInfo {
+            pre_vc11: cursor.read_u32::<LittleEndian>()?,
+            c_cpp: cursor.read_u32::<LittleEndian>()?,
+            gs: cursor.read_u32::<LittleEndian>()?,
+            sdl: cursor.read_u32::<LittleEndian>()?,
+            guard_n: cursor.read_u32::<LittleEndian>()?,
+        })
+    }
+}
+
+impl ReproInfo {
+    fn parse(buf: &[u8]) -> Self {
+        let mut cursor = Cursor::new(buf);
+        let hash = match cursor.read_u32::<LittleEndian>() {
+            Ok(length) => buf[4..]
+                .iter()
+                .take(length as usize)
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            Err(_) => String::new(),
+        };
+        ReproInfo { hash }
+    }
+}
+
+impl DebugTable {
+    /// 遍历 IMAGE_DIRECTORY_ENTRY_DEBUG 下的所有调试目录项
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<DebugTable>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut entries = Vec::new();
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_DEBUG);
+        let directory_size = data_dir.get_data_directory_size(DIRECTORY_DEBUG);
+        let directory_fo = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) if directory_rva != 0 && directory_size != 0 => fo,
+            _ => return Ok(DebugTable::default()),
+        };
+        let entry_size = size_of::<ImageDebugDirectory>() as u32;
+        for index in 0..directory_size / entry_size {
+            let Some(entry_fo) = index
+                .checked_mul(entry_size)
+                .and_then(|offset| directory_fo.checked_add(offset))
+            else {
+                break;
+            };
+            let debug_directory = ImageDebugDirectory::new(file, entry_fo)?;
+            let mut entry = DebugEntry {
+                file_offset: entry_fo,
+                characteristics: debug_directory.characteristics,
+                time_date_stamp: debug_directory.time_date_stamp,
+                major_version: debug_directory.major_version,
+                minor_version: debug_directory.minor_version,
+                debug_type: debug_directory.debug_type,
+                size_of_data: debug_directory.size_of_data,
+                address_of_raw_data: debug_directory.address_of_raw_data,
+                pointer_to_raw_data: debug_directory.pointer_to_raw_data,
+                data: DebugData::Unknown,
+            };
+            entry.data = entry.read_data(file, nt_head, section_headers);
+            entries.push(entry);
+        }
+        Ok(DebugTable(Rc::new(RefCell::new(entries))))
+    }
+
+    pub fn fclone(&self) -> Self {
+        DebugTable(Rc::clone(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+    use std::path::PathBuf;
+
+    /// steam_api.dll 中 CODEVIEW、VC_FEATURE、POGO 三个目录项及其数据的文件偏移
+    const CODEVIEW_ENTRY_FO: usize = 177132;
+    const VC_FEATURE_ENTRY_FO: usize = 177160;
+    const POGO_ENTRY_FO: usize = 177188;
+    const CODEVIEW_DATA_FO: usize = 178724;
+    const VC_FEATURE_DATA_FO: usize = 178828;
+    const POGO_DATA_FO: usize = 178848;
+    /// 调试数据目录项中 Size 字段的偏移
+    const DEBUG_SIZE_FO: usize = 0x1A8 + 8 + 4;
+    const DEBUG_TYPE_OFFSET: usize = 12;
+    const SIZE_OF_DATA_OFFSET: usize = 16;
+
+    fn entries_of(path: &str) -> Vec<DebugEntry> {
+        let file_info = FileInfo::new(PathBuf::from(path)).unwrap();
+        file_info.get_debug().unwrap().0.borrow().clone()
+    }
+
+    fn entries_of_patched(name: &str, patch: impl FnOnce(&mut Vec<u8>)) -> Vec<DebugEntry> {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        patch(&mut data);
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &data).unwrap();
+        let entries = entries_of(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        entries
+    }
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn codeview_of(entry: &DebugEntry) -> &CodeViewInfo {
+        match &entry.data {
+            DebugData::CodeView(codeview) => codeview,
+            data => panic!("unexpected debug data {:?}", data),
+        }
+    }
+
+    #[test]
+    fn test_debug_32() {
+        let entries = entries_of("test_pe/steam_api.dll");
+        let types: Vec<_> = entries.iter().map(|entry| entry.get_type_name()).collect();
+        assert_eq!(types, ["CODEVIEW", "VC_FEATURE", "POGO"]);
+        assert_eq!(entries[0].file_offset, CODEVIEW_ENTRY_FO as u32);
+        let codeview = codeview_of(&entries[0]);
+        assert_eq!(codeview.signature, "RSDS");
+        assert_eq!(codeview.guid, "CE723940-C196-4A37-AD74-A05618EC6E21");
+        assert_eq!(codeview.age, 2);
+        assert_eq!(
+            codeview.pdb_path,
+            r"c:\buildslave\steam_rel_client_win32\build\src\steam_api\Release\steam_api.pdb"
+        );
+        assert_eq!(
+            codeview.get_symbol_server_key(),
+            "CE723940C1964A37AD74A05618EC6E212"
+        );
+        assert_eq!(
+            entries[1].data,
+            DebugData::VcFeature(VcFeatureInfo {
+                pre_vc11: 0,
+                c_cpp: 232,
+                gs: 225,
+                sdl: 0,
+                guard_n: 225,
+            })
+        );
+        let DebugData::Pogo(pogo) = &entries[2].data else {
+            panic!("unexpected debug data {:?}", entries[2].data);
+        };
+        assert_eq!(pogo.entries.len(), 45);
+        assert_eq!(
+            pogo.entries[0],
+            PogoEntry {
+                rva: 0x1000,
+                size: 32,
+                name: ".text$di".to_string(),
+            }
+        );
+        assert_eq!(pogo.entries[44].name, ".rsrc$02");
+    }
+
+    #[test]
+    fn test_debug_64() {
+        let entries = entries_of("test_pe/steam_api64.dll");
+        assert_eq!(entries.len(), 3);
+        let codeview = codeview_of(&entries[0]);
+        assert_eq!(codeview.guid, "C108C823-2F97-4B6E-A612-AAADB534734E");
+        assert_eq!(codeview.age, 2);
+        assert!(
+            codeview
+                .pdb_path
+                .ends_with(r"\win64\Release\steam_api64.pdb")
+        );
+        let DebugData::Pogo(pogo) = &entries[2].data else {
+            panic!("unexpected debug data {:?}", entries[2].data);
+        };
+        assert_eq!(pogo.entries.len(), 48);
+    }
+
+    #[test]
+    fn test_debug_nb10() {
+        let entries = entries_of_patched("penguin_debug_nb10.dll", |data| {
+            put(data, CODEVIEW_DATA_FO, b"NB10");
+            put(data, CODEVIEW_DATA_FO + 4, &0u32.to_le_bytes());
+            put(data, CODEVIEW_DATA_FO + 8, &0x5E1A_2B3Cu32.to_le_bytes());
+            put(data, CODEVIEW_DATA_FO + 12, &7u32.to_le_bytes());
+            put(data, CODEVIEW_DATA_FO + 16, b"old.pdb\0");
+        });
+        let codeview = codeview_of(&entries[0]);
+        assert_eq!(codeview.signature, "NB10");
+        assert!(codeview.guid.is_empty());
+        assert_eq!(codeview.nb10_signature, 0x5E1A_2B3C);
+        assert_eq!(codeview.age, 7);
+        assert_eq!(codeview.pdb_path, "old.pdb");
+        assert_eq!(codeview.get_symbol_server_key(), "5E1A2B3C7");
+    }
+
+    #[test]
+    fn test_debug_repro_and_ex_dll_characteristics() {
+        let entries = entries_of_patched("penguin_debug_repro.dll", |data| {
+            put(
+                data,
+                VC_FEATURE_ENTRY_FO + DEBUG_TYPE_OFFSET,
+                &DEBUG_TYPE_EX_DLLCHARACTERISTICS.to_le_bytes(),
+            );
+            put(data, VC_FEATURE_DATA_FO, &0x41u32.to_le_bytes());
+            put(
+                data,
+                POGO_ENTRY_FO + DEBUG_TYPE_OFFSET,
+                &DEBUG_TYPE_REPRO.to_le_bytes(),
+            );
+            put(data, POGO_DATA_FO, &4u32.to_le_bytes());
+            put(data, POGO_DATA_FO + 4, &[0xDE, 0xAD, 0xBE, 0xEF]);
+        });
+        assert_eq!(entries[1].get_type_name(), "EX_DLLCHARACTERISTICS");
+        assert_eq!(entries[1].data, DebugData::ExDllCharacteristics(0x41));
+        assert_eq!(
+            entries[1].get_ex_dll_characteristics_names(),
+            ["CET_COMPAT", "FORWARD_CFI_COMPAT"]
+        );
+        assert_eq!(entries[2].get_type_name(), "REPRO");
+        assert_eq!(
+            entries[2].data,
+            DebugData::Repro(ReproInfo {
+                hash: "deadbeef".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_debug_truncated_entry() {
+        // RSDS 数据只剩签名与半个GUID，目录大小不足以容纳第三项
+        let entries = entries_of_patched("penguin_debug_truncated.dll", |data| {
+            put(
+                data,
+                CODEVIEW_ENTRY_FO + SIZE_OF_DATA_OFFSET,
+                &10u32.to_le_bytes(),
+            );
+            put(data, DEBUG_SIZE_FO, &(28u32 * 3 - 1).to_le_bytes());
+        });
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].debug_type, DEBUG_TYPE_CODEVIEW);
+        assert_eq!(entries[0].data, DebugData::Unknown);
+        assert!(matches!(entries[1].data, DebugData::VcFeature(_)));
+        assert!(CodeViewInfo::parse(b"RSDS\x01\x02").is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_DELAY_IMPORT;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, DelayImportInfo, ImageDelayLoadDescriptor, ImageSectionHeaders, ImportDll,
+    ImportFunction, rva_2_fo,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+
+/// grAttrs 中表示各字段为RVA的标志，未设置时为VC6时代的VA格式
+const DLATTR_RVA: u32 = 0x1;
+/// 描述符与名称表均以0结尾，防止畸形文件导致读取过多
+const MAX_DELAY_IMPORT_DLLS: u32 = 0x1000;
+const MAX_DELAY_IMPORT_FUNCTIONS: u64 = 0x10000;
+
+impl ImageDelayLoadDescriptor {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut descriptor = MaybeUninit::<ImageDelayLoadDescriptor>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                descriptor.as_mut_ptr() as *mut u8,
+                size_of::<ImageDelayLoadDescriptor>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(descriptor.assume_init())
+        }
+    }
+}
+
+impl DelayImportInfo {
+    /// 将描述符中的地址统一为RVA
+    fn new(descriptor: &ImageDelayLoadDescriptor, image_base: u64) -> Self {
+        let is_rva_based = descriptor.attributes & DLATTR_RVA != 0;
+        let to_rva = |address: u32| {
+            if is_rva_based || address == 0 {
+                address
+            } else {
+                (address as u64).wrapping_sub(image_base) as u32
+            }
+        };
+        DelayImportInfo {
+            attributes: descriptor.attributes,
+            is_rva_based,
+            dll_name_rva: to_rva(descriptor.dll_name_rva),
+            module_handle_rva: to_rva(descriptor.module_handle_rva),
+            import_address_table_rva: to_rva(descriptor.import_address_table_rva),
+            import_name_table_rva: to_rva(descriptor.import_name_table_rva),
+            bound_import_address_table_rva: to_rva(descriptor.bound_import_address_table_rva),
+            unload_information_table_rva: to_rva(descriptor.unload_information_table_rva),
+            time_date_stamp: descriptor.time_date_stamp,
+        }
+    }
+}
+
+impl ImportDll {
+    /// 读取 IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT 下的所有延迟加载DLL
+    pub fn new_delay_imports<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+        is_64_bit: bool,
+    ) -> anyhow::Result<Vec<ImportDll>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut dlls = Vec::new();
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_DELAY_IMPORT);
+        let directory_fo = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) if directory_rva != 0 => fo,
+            _ => return Ok(dlls),
+        };
+        let image_base = nt_head.get_image_base();
+        let descriptor_size = size_of::<ImageDelayLoadDescriptor>() as u32;
+        for index in 0..MAX_DELAY_IMPORT_DLLS {
+            // 目录位于文件偏移上限附近时描述符地址会溢出，视为目录结束
+            let Some(descriptor_fo) = index
+                .checked_mul(descriptor_size)
+                .and_then(|offset| directory_fo.checked_add(offset))
+            else {
+                break;
+            };
+            let descriptor = ImageDelayLoadDescriptor::new(file, descriptor_fo)?;
+            if descriptor.dll_name_rva == 0 {
+                break;
+            }
+            let delay_load = DelayImportInfo::new(&descriptor, image_base);
+            let name = match rva_2_fo(nt_head, section_headers, delay_load.dll_name_rva) {
+                Some(fo) => {
+                    let mut name = [0u8; 256];
+                    file.seek(SeekFrom::Start(fo as u64))?;
+                    let length = file.read(&mut name)?;
+                    let end = name[..length]
+                        .iter()
+                        .position(|&x| x == 0)
+                        .unwrap_or(length);
+                    String::from_utf8_lossy(&name[..end]).to_string()
+                }
+                None => String::new(),
+            };
+            let function_info = Self::read_delay_thunks(
+                file,
+                nt_head,
+                section_headers,
+                &delay_load,
+                &name,
+                image_base,
+                is_64_bit,
+            )?;
+            dlls.push(ImportDll {
+                name_address: delay_load.dll_name_rva,
+                name_length: name.len() as u32,
+                name,
+                time_date_stamp: delay_load.time_date_stamp,
+                forwarder_chain: 0,
+                first_thunk: delay_load.import_address_table_rva,
+                function_info,
+                function_size: 0,
+                delay_load: Some(delay_load),
+                bound_import: None,
+                stale_binding: false,
+            });
+        }
+        Ok(dlls)
+    }
+
+    /// 遍历延迟导入名称表，序号导入与普通导入表一样从序号数据库中查找名称
+    fn read_delay_thunks<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        delay_load: &DelayImportInfo,
+        dll_name: &str,
+        image_base: u64,
+        is_64_bit: bool,
+    ) -> anyhow::Result<Vec<ImportFunction>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut function_info = Vec::new();
+        let table_fo = match rva_2_fo(nt_head, section_headers, delay_load.import_name_table_rva) {
+            Some(fo) => fo as u64,
+            None => return Ok(function_info),
+        };
+        let (thunk_size, ordinal_flag) = if is_64_bit {
+            (8u64, 1u64 << 63)
+        } else {
+            (4u64, 1u64 << 31)
+        };
+        for index in 0..MAX_DELAY_IMPORT_FUNCTIONS {
+            file.seek(SeekFrom::Start(table_fo + index * thunk_size))?;
+            let thunk = if is_64_bit {
+                file.read_u64::<LittleEndian>()?
+            } else {
+                file.read_u32::<LittleEndian>()? as u64
+            };
+            if thunk == 0 {
+                break;
+            }
+            if thunk & ordinal_flag != 0 {
+                function_info.push(ImportFunction::from_ordinal(dll_name, thunk, thunk as u16));
+                continue;
+            }
+            let hint_name_rva = if delay_load.is_rva_based {
+                thunk as u32
+            } else {
+                thunk.wrapping_sub(image_base) as u32
+            };
+            if let Some(fo) = rva_2_fo(nt_head, section_headers, hint_name_rva)
+                && let Some(function) = ImportFunction::new(file, fo)?
+            {
+                function_info.push(function);
+            }
+        }
+        Ok(function_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::ImportDll;
+    use std::path::PathBuf;
+
+    /// 借用 steam_api.dll 的 .reloc 节存放构造的延迟导入目录
+    const DIRECTORY_RVA: u32 = 0x40000;
+    const DIRECTORY_FO: usize = 0x3C600;
+    /// 延迟导入数据目录项的文件偏移
+    const DELAY_IMPORT_ENTRY_FO: usize = 0x1A8 + (13 - 5) * 8;
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// 构造一个只有 delayed.dll 的延迟导入目录，`to_address` 决定字段写成RVA还是VA
+    fn delay_imports_of_patched(
+        name: &str,
+        attributes: u32,
+        to_address: impl Fn(u32) -> u32,
+    ) -> Vec<ImportDll> {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        data[DIRECTORY_FO..DIRECTORY_FO + 0x200].fill(0);
+        put_u32(&mut data, DELAY_IMPORT_ENTRY_FO, DIRECTORY_RVA);
+        put_u32(&mut data, DELAY_IMPORT_ENTRY_FO + 4, 64);
+        // 描述符之后的32字节保持为0，作为结束标志
+        let fields = [
+            attributes,
+            to_address(DIRECTORY_RVA + 0x100),
+            to_address(DIRECTORY_RVA + 0x1C0),
+            to_address(DIRECTORY_RVA + 0x180),
+            to_address(DIRECTORY_RVA + 0x140),
+            0,
+            0,
+            0x12345678,
+        ];
+        for (index, field) in fields.iter().enumerate() {
+            put_u32(&mut data, DIRECTORY_FO + index * 4, *field);
+        }
+        data[DIRECTORY_FO + 0x100..][..12].copy_from_slice(b"delayed.dll\0");
+        put_u32(
+            &mut data,
+            DIRECTORY_FO + 0x140,
+            to_address(DIRECTORY_RVA + 0x160),
+        );
+        put_u32(&mut data, DIRECTORY_FO + 0x144, 0x8000_0005);
+        data[DIRECTORY_FO + 0x162..][..12].copy_from_slice(b"DelayedFunc\0");
+
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        let imports = file_info.get_imports().unwrap();
+        let delay_imports = imports
+            .0
+            .borrow()
+            .iter()
+            .filter(|dll| dll.delay_load.is_some())
+            .cloned()
+            .collect();
+        drop(file_info);
+        std::fs::remove_file(&path).unwrap();
+        delay_imports
+    }
+
+    fn assert_delayed_dll(delay_imports: &[ImportDll], is_rva_based: bool) {
+        assert_eq!(delay_imports.len(), 1);
+        let dll = &delay_imports[0];
+        assert_eq!(dll.name, "delayed.dll");
+        assert_eq!(dll.time_date_stamp, 0x12345678);
+        assert_eq!(dll.first_thunk, DIRECTORY_RVA + 0x180);
+        let delay_load = dll.delay_load.as_ref().unwrap();
+        assert_eq!(delay_load.is_rva_based, is_rva_based);
+        assert_eq!(delay_load.dll_name_rva, DIRECTORY_RVA + 0x100);
+        assert_eq!(delay_load.module_handle_rva, DIRECTORY_RVA + 0x1C0);
+        assert_eq!(delay_load.import_name_table_rva, DIRECTORY_RVA + 0x140);
+        assert_eq!(dll.function_info.len(), 2);
+        assert_eq!(dll.function_info[0].name, "DelayedFunc");
+        assert_eq!(dll.function_info[0].ordinal, None);
+        assert_eq!(dll.function_info[1].ordinal, Some(5));
+    }
+
+    #[test]
+    fn test_delay_import_rva_based() {
+        let delay_imports = delay_imports_of_patched("penguin_delay_import_rva.dll", 1, |rva| rva);
+        assert_delayed_dll(&delay_imports, true);
+    }
+
+    #[test]
+    fn test_delay_import_va_based() {
+        // VC6 时代的描述符各字段为VA，需要减去映像基址
+        let image_base = FileInfo::new(PathBuf::from("test_pe/steam_api.dll"))
+            .unwrap()
+            .nt_head
+            .get_image_base() as u32;
+        let delay_imports =
+            delay_imports_of_patched("penguin_delay_import_va.dll", 0, |rva| rva + image_base);
+        assert_delayed_dll(&delay_imports, false);
+    }
+
+    #[test]
+    fn test_no_delay_import() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api64.dll")).unwrap();
+        let imports = file_info.get_imports().unwrap();
+        assert!(!imports.0.borrow().is_empty());
+        assert!(
+            imports
+                .0
+                .borrow()
+                .iter()
+                .all(|dll| dll.delay_load.is_none())
+        );
+    }
+}
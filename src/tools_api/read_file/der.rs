@@ -0,0 +1,359 @@
+/// 只支持定长编码和单字节标签，足以解析 Authenticode 中的 PKCS#7 与 X.509 结构
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+pub(crate) const TAG_CONTEXT_0: u8 = 0xA0;
+pub(crate) const TAG_CONTEXT_1: u8 = 0xA1;
+
+const TAG_UTF8_STRING: u8 = 0x0C;
+const TAG_PRINTABLE_STRING: u8 = 0x13;
+const TAG_T61_STRING: u8 = 0x14;
+const TAG_IA5_STRING: u8 = 0x16;
+const TAG_BMP_STRING: u8 = 0x1E;
+
+const OID_NAMES: [(&str, &str); 40] = [
+    ("1.2.840.113549.2.5", "md5"),
+    ("1.3.14.3.2.26", "sha1"),
+    ("2.16.840.1.101.3.4.2.1", "sha256"),
+    ("2.16.840.1.101.3.4.2.2", "sha384"),
+    ("2.16.840.1.101.3.4.2.3", "sha512"),
+    ("1.2.840.113549.1.1.1", "rsaEncryption"),
+    ("1.2.840.113549.1.1.4", "md5WithRSAEncryption"),
+    ("1.2.840.113549.1.1.5", "sha1WithRSAEncryption"),
+    ("1.2.840.113549.1.1.11", "sha256WithRSAEncryption"),
+    ("1.2.840.113549.1.1.12", "sha384WithRSAEncryption"),
+    ("1.2.840.113549.1.1.13", "sha512WithRSAEncryption"),
+    ("1.2.840.113549.1.1.10", "rsassaPss"),
+    ("1.2.840.10045.2.1", "ecPublicKey"),
+    ("1.2.840.10045.4.1", "ecdsa-with-SHA1"),
+    ("1.2.840.10045.4.3.2", "ecdsa-with-SHA256"),
+    ("1.2.840.10045.4.3.3", "ecdsa-with-SHA384"),
+    ("1.2.840.10045.4.3.4", "ecdsa-with-SHA512"),
+    ("1.2.840.113549.1.7.1", "pkcs7-data"),
+    ("1.2.840.113549.1.7.2", "pkcs7-signedData"),
+    ("1.2.840.113549.1.9.3", "contentType"),
+    ("1.2.840.113549.1.9.4", "messageDigest"),
+    ("1.2.840.113549.1.9.5", "signingTime"),
+    ("1.2.840.113549.1.9.6", "countersignature"),
+    ("1.2.840.113549.1.9.16.1.4", "id-ct-TSTInfo"),
+    ("1.3.6.1.4.1.311.2.1.4", "SPC_INDIRECT_DATA"),
+    ("1.3.6.1.4.1.311.2.1.15", "SPC_PE_IMAGE_DATA"),
+    ("1.3.6.1.4.1.311.2.4.1", "SPC_NESTED_SIGNATURE"),
+    ("1.3.6.1.4.1.311.3.3.1", "SPC_RFC3161_TIMESTAMP"),
+    ("2.5.4.3", "CN"),
+    ("2.5.4.5", "serialNumber"),
+    ("2.5.4.6", "C"),
+    ("2.5.4.7", "L"),
+    ("2.5.4.8", "ST"),
+    ("2.5.4.9", "street"),
+    ("2.5.4.10", "O"),
+    ("2.5.4.11", "OU"),
+    ("2.5.4.15", "businessCategory"),
+    ("1.2.840.113549.1.9.1", "emailAddress"),
+    ("1.3.6.1.4.1.311.60.2.1.2", "jurisdictionST"),
+    ("1.3.6.1.4.1.311.60.2.1.3", "jurisdictionC"),
+];
+
+/// 一个TLV
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DerValue<'a> {
+    pub(crate) tag: u8,
+    pub(crate) content: &'a [u8],
+}
+
+/// 顺序读取同一层级中的TLV
+pub(crate) struct DerReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> DerReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        DerReader { data, position: 0 }
+    }
+
+    /// 读取下一个TLV，遇到越界、不定长或多字节标签时返回None
+    pub(crate) fn read(&mut self) -> Option<DerValue<'a>> {
+        let (value, length) = self.peek_with_length()?;
+        self.position += length;
+        Some(value)
+    }
+
+    /// 读取下一个TLV并要求标签一致
+    pub(crate) fn read_tag(&mut self, tag: u8) -> Option<DerValue<'a>> {
+        self.read().filter(|value| value.tag == tag)
+    }
+
+    /// 标签一致时才读取，用于可选字段
+    pub(crate) fn read_optional(&mut self, tag: u8) -> Option<DerValue<'a>> {
+        let (value, length) = self.peek_with_length()?;
+        if value.tag != tag {
+            return None;
+        }
+        self.position += length;
+        Some(value)
+    }
+
+    fn peek_with_length(&self) -> Option<(DerValue<'a>, usize)> {
+        let data = self.data.get(self.position..)?;
+        let tag = *data.first()?;
+        if tag & 0x1F == 0x1F {
+            return None;
+        }
+        let first = *data.get(1)? as usize;
+        let (content_length, header_length) = if first < 0x80 {
+            (first, 2)
+        } else {
+            let count = first & 0x7F;
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let length = data
+                .get(2..2 + count)?
+                .iter()
+                .fold(0usize, |length, &b| (length << 8) | b as usize);
+            (length, 2 + count)
+        };
+        let content = data.get(header_length..header_length.checked_add(content_length)?)?;
+        Some((DerValue { tag, content }, header_length + content_length))
+    }
+}
+
+impl<'a> DerValue<'a> {
+    /// 构造类型的子项
+    pub(crate) fn reader(&self) -> DerReader<'a> {
+        DerReader::new(self.content)
+    }
+
+    /// OID 点分形式
+    pub(crate) fn to_oid(self) -> String {
+        let mut parts = Vec::new();
+        let mut value = 0u64;
+        for &b in self.content {
+            value = (value << 7) | (b & 0x7F) as u64;
+            if b & 0x80 != 0 {
+                continue;
+            }
+            if parts.is_empty() {
+                let first = (value / 40).min(2);
+                parts.push(first);
+                parts.push(value - first * 40);
+            } else {
+                parts.push(value);
+            }
+            value = 0;
+        }
+        parts
+            .iter()
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// 内容的大写十六进制
+    pub(crate) fn to_hex(self) -> String {
+        self.content.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+
+    /// INTEGER 去掉符号填充后的大写十六进制，用于证书序列号
+    pub(crate) fn to_serial_number(self) -> String {
+        match self.content {
+            [0, rest @ ..] if !rest.is_empty() => DerValue {
+                tag: self.tag,
+                content: rest,
+            }
+            .to_hex(),
+            _ => self.to_hex(),
+        }
+    }
+
+    /// UTCTime 或 GeneralizedTime，格式化为 YYYY-MM-DD HH:MM:SS UTC
+    pub(crate) fn to_time(self) -> String {
+        let text = String::from_utf8_lossy(self.content);
+        if !text.is_ascii() {
+            return text.to_string();
+        }
+        let digits = match self.tag {
+            TAG_UTC_TIME if text.len() >= 12 => {
+                let century = if text[..2].parse::<u32>().unwrap_or(0) < 50 {
+                    "20"
+                } else {
+                    "19"
+                };
+                format!("{}{}", century, &text[..12])
+            }
+            TAG_GENERALIZED_TIME if text.len() >= 14 => text[..14].to_string(),
+            _ => return text.to_string(),
+        };
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return text.to_string();
+        }
+        format!(
+            "{}-{}-{} {}:{}:{} UTC",
+            &digits[0..4],
+            &digits[4..6],
+            &digits[6..8],
+            &digits[8..10],
+            &digits[10..12],
+            &digits[12..14]
+        )
+    }
+
+    /// 目录名称中使用的各种字符串类型
+    pub(crate) fn to_text(self) -> String {
+        match self.tag {
+            TAG_BMP_STRING => {
+                let units: Vec<u16> = self
+                    .content
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            TAG_UTF8_STRING | TAG_PRINTABLE_STRING | TAG_T61_STRING | TAG_IA5_STRING => {
+                String::from_utf8_lossy(self.content).to_string()
+            }
+            _ => self.to_hex(),
+        }
+    }
+
+    /// X.509 Name，按编码顺序输出为 C=US, O=..., CN=...
+    pub(crate) fn to_name(self) -> String {
+        let mut attributes = Vec::new();
+        let mut rdns = self.reader();
+        while let Some(rdn) = rdns.read_tag(TAG_SET) {
+            let mut values = rdn.reader();
+            while let Some(attribute) = values.read_tag(TAG_SEQUENCE) {
+                let mut fields = attribute.reader();
+                let (Some(oid), Some(value)) = (fields.read_tag(TAG_OID), fields.read()) else {
+                    continue;
+                };
+                attributes.push(format!(
+                    "{}={}",
+                    get_oid_name(&oid.to_oid()),
+                    value.to_text()
+                ));
+            }
+        }
+        attributes.join(", ")
+    }
+
+    /// AlgorithmIdentifier 中的算法名称
+    pub(crate) fn to_algorithm(self) -> String {
+        self.reader()
+            .read_tag(TAG_OID)
+            .map(|oid| get_oid_name(&oid.to_oid()))
+            .unwrap_or_default()
+    }
+}
+
+/// 常见OID的名称，未知的OID原样返回
+pub(crate) fn get_oid_name(oid: &str) -> String {
+    OID_NAMES
+        .iter()
+        .find(|(known, _)| *known == oid)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| oid.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_short_and_long_length() {
+        let mut data = vec![TAG_INTEGER, 0x01, 0x05, TAG_OCTET_STRING, 0x81, 0x80];
+        data.extend([0xAB; 0x80]);
+        let mut reader = DerReader::new(&data);
+        let integer = reader.read_tag(TAG_INTEGER).unwrap();
+        assert_eq!(integer.content, [0x05]);
+        let octets = reader.read().unwrap();
+        assert_eq!(octets.tag, TAG_OCTET_STRING);
+        assert_eq!(octets.content.len(), 0x80);
+        assert!(reader.read().is_none());
+    }
+
+    #[test]
+    fn test_reject_malformed_values() {
+        // 不定长编码
+        assert!(
+            DerReader::new(&[TAG_SEQUENCE, 0x80, 0x00, 0x00])
+                .read()
+                .is_none()
+        );
+        // 多字节标签
+        assert!(DerReader::new(&[0x1F, 0x81, 0x01, 0x00]).read().is_none());
+        // 长度超出数据
+        assert!(
+            DerReader::new(&[TAG_OCTET_STRING, 0x05, 0x01])
+                .read()
+                .is_none()
+        );
+        assert!(
+            DerReader::new(&[TAG_OCTET_STRING, 0x84, 0xFF, 0xFF, 0xFF, 0xFF])
+                .read()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_read_optional_keeps_position() {
+        let data = [TAG_INTEGER, 0x01, 0x01, TAG_SEQUENCE, 0x00];
+        let mut reader = DerReader::new(&data);
+        assert!(reader.read_optional(TAG_CONTEXT_0).is_none());
+        assert!(reader.read_optional(TAG_INTEGER).is_some());
+        assert!(reader.read_tag(TAG_SET).is_none());
+    }
+
+    #[test]
+    fn test_oid() {
+        let oid = DerValue {
+            tag: TAG_OID,
+            content: &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02],
+        }
+        .to_oid();
+        assert_eq!(oid, "1.2.840.113549.1.7.2");
+        assert_eq!(get_oid_name(&oid), "pkcs7-signedData");
+        assert_eq!(get_oid_name("1.2.3"), "1.2.3");
+    }
+
+    #[test]
+    fn test_serial_number_and_time() {
+        let serial = DerValue {
+            tag: TAG_INTEGER,
+            content: &[0x00, 0xFF, 0x01],
+        };
+        assert_eq!(serial.to_serial_number(), "FF01");
+        let utc_time = DerValue {
+            tag: TAG_UTC_TIME,
+            content: b"211123190957Z",
+        };
+        assert_eq!(utc_time.to_time(), "2021-11-23 19:09:57 UTC");
+        let old_utc_time = DerValue {
+            tag: TAG_UTC_TIME,
+            content: b"990101000000Z",
+        };
+        assert_eq!(old_utc_time.to_time(), "1999-01-01 00:00:00 UTC");
+        let generalized_time = DerValue {
+            tag: TAG_GENERALIZED_TIME,
+            content: b"20380115120000Z",
+        };
+        assert_eq!(generalized_time.to_time(), "2038-01-15 12:00:00 UTC");
+    }
+
+    #[test]
+    fn test_name() {
+        // Name { SET { SEQUENCE { CN, UTF8String } }, SET { SEQUENCE { C, PrintableString } } }
+        let data = [
+            0x30, 0x1D, 0x31, 0x0E, 0x30, 0x0C, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x05, b'V',
+            b'a', b'l', b'v', b'e', 0x31, 0x0B, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x06, 0x13,
+            0x02, b'U', b'S',
+        ];
+        let name = DerReader::new(&data).read_tag(TAG_SEQUENCE).unwrap();
+        assert_eq!(name.to_name(), "CN=Valve, C=US");
+    }
+}
@@ -6,7 +6,7 @@ use std::mem::{MaybeUninit, size_of};
 
 impl ImageDosHeader {
     /// 获取nt头文件地址
-    pub fn get_nt_addr(&self) -> u16 {
+    pub fn get_nt_addr(&self) -> u32 {
         self.e_lfanew
     }
     /// 读取dos头
@@ -4,10 +4,13 @@ use std::io::SeekFrom;
 use std::io::{Read, Seek};
 
 impl ImageDosStub {
-    pub fn new(file: &mut File, dos_stub_end: u16) -> anyhow::Result<ImageDosStub> {
+    /// NT头与DOS头重叠时存根为空
+    pub fn new(file: &mut File, dos_stub_end: u32) -> anyhow::Result<ImageDosStub> {
         file.seek(SeekFrom::Start(64))?;
-        let mut buffer = vec![0u8; dos_stub_end as usize - 64];
-        file.read(&mut buffer)?;
+        let mut buffer = Vec::new();
+        file.by_ref()
+            .take((dos_stub_end as u64).saturating_sub(64))
+            .read_to_end(&mut buffer)?;
         Ok(ImageDosStub { buffer })
     }
 }
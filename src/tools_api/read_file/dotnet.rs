@@ -0,0 +1,920 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_COM_DESCRIPTOR;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    AssemblyRefRow, DataDirectory, DotNetInfo, ImageCor20Header, ImageSectionHeaders,
+    ManifestResourceRow, MemberRefRow, MetadataStream, MetadataTableRows, MethodDefRow, ModuleRow,
+    TypeDefRow, TypeRefRow, rva_2_fo,
+};
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+
+/// 元数据根的签名 "BSJB"
+const METADATA_SIGNATURE: u32 = 0x424A_5342;
+/// 防止畸形文件导致读取过多
+const MAX_METADATA_SIZE: u32 = 0x400_0000;
+const MAX_METADATA_STREAMS: u16 = 0x100;
+const MAX_USER_STRINGS: usize = 0x10000;
+
+const COMIMAGE_FLAGS: [(u32, &str); 7] = [
+    (0x0000_0001, "ILONLY"),
+    (0x0000_0002, "32BITREQUIRED"),
+    (0x0000_0004, "IL_LIBRARY"),
+    (0x0000_0008, "STRONGNAMESIGNED"),
+    (0x0000_0010, "NATIVE_ENTRYPOINT"),
+    (0x0001_0000, "TRACKDEBUGDATA"),
+    (0x0002_0000, "32BITPREFERRED"),
+];
+
+/// heap_sizes 中表示各堆索引为4字节的标志
+const HEAP_STRING_LARGE: u8 = 0x01;
+const HEAP_GUID_LARGE: u8 = 0x02;
+const HEAP_BLOB_LARGE: u8 = 0x04;
+/// 行数数组之后还有4字节额外数据
+const HEAP_EXTRA_DATA: u8 = 0x40;
+
+const TABLE_MODULE: u8 = 0x00;
+const TABLE_TYPE_REF: u8 = 0x01;
+const TABLE_TYPE_DEF: u8 = 0x02;
+const TABLE_FIELD: u8 = 0x04;
+const TABLE_METHOD_DEF: u8 = 0x06;
+const TABLE_PARAM: u8 = 0x08;
+const TABLE_INTERFACE_IMPL: u8 = 0x09;
+const TABLE_MEMBER_REF: u8 = 0x0A;
+const TABLE_DECL_SECURITY: u8 = 0x0E;
+const TABLE_STAND_ALONE_SIG: u8 = 0x11;
+const TABLE_EVENT: u8 = 0x14;
+const TABLE_PROPERTY: u8 = 0x17;
+const TABLE_MODULE_REF: u8 = 0x1A;
+const TABLE_TYPE_SPEC: u8 = 0x1B;
+const TABLE_ASSEMBLY: u8 = 0x20;
+const TABLE_ASSEMBLY_REF: u8 = 0x23;
+const TABLE_FILE: u8 = 0x26;
+const TABLE_EXPORTED_TYPE: u8 = 0x27;
+const TABLE_MANIFEST_RESOURCE: u8 = 0x28;
+const TABLE_GENERIC_PARAM: u8 = 0x2A;
+const TABLE_METHOD_SPEC: u8 = 0x2B;
+const TABLE_GENERIC_PARAM_CONSTRAINT: u8 = 0x2C;
+/// 编码索引中保留不用的标签
+const TABLE_UNUSED: u8 = 0xFF;
+
+const TABLE_NAMES: [&str; 45] = [
+    "Module",
+    "TypeRef",
+    "TypeDef",
+    "FieldPtr",
+    "Field",
+    "MethodPtr",
+    "MethodDef",
+    "ParamPtr",
+    "Param",
+    "InterfaceImpl",
+    "MemberRef",
+    "Constant",
+    "CustomAttribute",
+    "FieldMarshal",
+    "DeclSecurity",
+    "ClassLayout",
+    "FieldLayout",
+    "StandAloneSig",
+    "EventMap",
+    "EventPtr",
+    "Event",
+    "PropertyMap",
+    "PropertyPtr",
+    "Property",
+    "MethodSemantics",
+    "MethodImpl",
+    "ModuleRef",
+    "TypeSpec",
+    "ImplMap",
+    "FieldRVA",
+    "EncLog",
+    "EncMap",
+    "Assembly",
+    "AssemblyProcessor",
+    "AssemblyOS",
+    "AssemblyRef",
+    "AssemblyRefProcessor",
+    "AssemblyRefOS",
+    "File",
+    "ExportedType",
+    "ManifestResource",
+    "NestedClass",
+    "GenericParam",
+    "MethodSpec",
+    "GenericParamConstraint",
+];
+
+/// 编码索引：低位标签选择目标表，其余位为行号
+#[derive(Clone, Copy)]
+struct CodedIndex {
+    tag_bits: u32,
+    tables: &'static [u8],
+}
+
+const TYPE_DEF_OR_REF: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[TABLE_TYPE_DEF, TABLE_TYPE_REF, TABLE_TYPE_SPEC],
+};
+const HAS_CONSTANT: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[TABLE_FIELD, TABLE_PARAM, TABLE_PROPERTY],
+};
+const HAS_CUSTOM_ATTRIBUTE: CodedIndex = CodedIndex {
+    tag_bits: 5,
+    tables: &[
+        TABLE_METHOD_DEF,
+        TABLE_FIELD,
+        TABLE_TYPE_REF,
+        TABLE_TYPE_DEF,
+        TABLE_PARAM,
+        TABLE_INTERFACE_IMPL,
+        TABLE_MEMBER_REF,
+        TABLE_MODULE,
+        TABLE_DECL_SECURITY,
+        TABLE_PROPERTY,
+        TABLE_EVENT,
+        TABLE_STAND_ALONE_SIG,
+        TABLE_MODULE_REF,
+        TABLE_TYPE_SPEC,
+        TABLE_ASSEMBLY,
+        TABLE_ASSEMBLY_REF,
+        TABLE_FILE,
+        TABLE_EXPORTED_TYPE,
+        TABLE_MANIFEST_RESOURCE,
+        TABLE_GENERIC_PARAM,
+        TABLE_GENERIC_PARAM_CONSTRAINT,
+        TABLE_METHOD_SPEC,
+    ],
+};
+const HAS_FIELD_MARSHAL: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[TABLE_FIELD, TABLE_PARAM],
+};
+const HAS_DECL_SECURITY: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[TABLE_TYPE_DEF, TABLE_METHOD_DEF, TABLE_ASSEMBLY],
+};
+const MEMBER_REF_PARENT: CodedIndex = CodedIndex {
+    tag_bits: 3,
+    tables: &[
+        TABLE_TYPE_DEF,
+        TABLE_TYPE_REF,
+        TABLE_MODULE_REF,
+        TABLE_METHOD_DEF,
+        TABLE_TYPE_SPEC,
+    ],
+};
+const HAS_SEMANTICS: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[TABLE_EVENT, TABLE_PROPERTY],
+};
+const METHOD_DEF_OR_REF: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[TABLE_METHOD_DEF, TABLE_MEMBER_REF],
+};
+const MEMBER_FORWARDED: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[TABLE_FIELD, TABLE_METHOD_DEF],
+};
+const IMPLEMENTATION: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[TABLE_FILE, TABLE_ASSEMBLY_REF, TABLE_EXPORTED_TYPE],
+};
+const CUSTOM_ATTRIBUTE_TYPE: CodedIndex = CodedIndex {
+    tag_bits: 3,
+    tables: &[
+        TABLE_UNUSED,
+        TABLE_UNUSED,
+        TABLE_METHOD_DEF,
+        TABLE_MEMBER_REF,
+        TABLE_UNUSED,
+    ],
+};
+const RESOLUTION_SCOPE: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[
+        TABLE_MODULE,
+        TABLE_MODULE_REF,
+        TABLE_ASSEMBLY_REF,
+        TABLE_TYPE_REF,
+    ],
+};
+const TYPE_OR_METHOD_DEF: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[TABLE_TYPE_DEF, TABLE_METHOD_DEF],
+};
+
+#[derive(Clone, Copy)]
+enum Column {
+    U16,
+    U32,
+    Str,
+    Guid,
+    Blob,
+    Table(u8),
+    Coded(CodedIndex),
+}
+
+use Column::{Blob, Coded, Guid, Str, Table, U16, U32};
+
+/// ECMA-335 II.22 中各表的列，计算行大小时需要全部已知
+const TABLE_SCHEMAS: [&[Column]; 45] = [
+    &[U16, Str, Guid, Guid, Guid],
+    &[Coded(RESOLUTION_SCOPE), Str, Str],
+    &[
+        U32,
+        Str,
+        Str,
+        Coded(TYPE_DEF_OR_REF),
+        Table(TABLE_FIELD),
+        Table(TABLE_METHOD_DEF),
+    ],
+    &[Table(TABLE_FIELD)],
+    &[U16, Str, Blob],
+    &[Table(TABLE_METHOD_DEF)],
+    &[U32, U16, U16, Str, Blob, Table(TABLE_PARAM)],
+    &[Table(TABLE_PARAM)],
+    &[U16, U16, Str],
+    &[Table(TABLE_TYPE_DEF), Coded(TYPE_DEF_OR_REF)],
+    &[Coded(MEMBER_REF_PARENT), Str, Blob],
+    &[U16, Coded(HAS_CONSTANT), Blob],
+    &[
+        Coded(HAS_CUSTOM_ATTRIBUTE),
+        Coded(CUSTOM_ATTRIBUTE_TYPE),
+        Blob,
+    ],
+    &[Coded(HAS_FIELD_MARSHAL), Blob],
+    &[U16, Coded(HAS_DECL_SECURITY), Blob],
+    &[U16, U32, Table(TABLE_TYPE_DEF)],
+    &[U32, Table(TABLE_FIELD)],
+    &[Blob],
+    &[Table(TABLE_TYPE_DEF), Table(TABLE_EVENT)],
+    &[Table(TABLE_EVENT)],
+    &[U16, Str, Coded(TYPE_DEF_OR_REF)],
+    &[Table(TABLE_TYPE_DEF), Table(TABLE_PROPERTY)],
+    &[Table(TABLE_PROPERTY)],
+    &[U16, Str, Blob],
+    &[U16, Table(TABLE_METHOD_DEF), Coded(HAS_SEMANTICS)],
+    &[
+        Table(TABLE_TYPE_DEF),
+        Coded(METHOD_DEF_OR_REF),
+        Coded(METHOD_DEF_OR_REF),
+    ],
+    &[Str],
+    &[Blob],
+    &[U16, Coded(MEMBER_FORWARDED), Str, Table(TABLE_MODULE_REF)],
+    &[U32, Table(TABLE_FIELD)],
+    &[U32, U32],
+    &[U32],
+    &[U32, U16, U16, U16, U16, U32, Blob, Str, Str],
+    &[U32],
+    &[U32, U32, U32],
+    &[U16, U16, U16, U16, U32, Blob, Str, Str, Blob],
+    &[U32, Table(TABLE_ASSEMBLY_REF)],
+    &[U32, U32, U32, Table(TABLE_ASSEMBLY_REF)],
+    &[U32, Str, Blob],
+    &[U32, U32, Str, Str, Coded(IMPLEMENTATION)],
+    &[U32, U32, Str, Coded(IMPLEMENTATION)],
+    &[Table(TABLE_TYPE_DEF), Table(TABLE_TYPE_DEF)],
+    &[U16, U16, Coded(TYPE_OR_METHOD_DEF), Str],
+    &[Coded(METHOD_DEF_OR_REF), Blob],
+    &[Table(TABLE_GENERIC_PARAM), Coded(TYPE_DEF_OR_REF)],
+];
+
+/// 在元数据内存块中按小端顺序读取
+struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8], position: usize) -> Self {
+        ByteReader { data, position }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(length)?)?;
+        self.position += length;
+        Some(bytes)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        self.read_bytes(1).map(|b| b[0])
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        self.read_bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        self.read_bytes(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn read_u64(&mut self) -> Option<u64> {
+        let low = self.read_u32()? as u64;
+        let high = self.read_u32()? as u64;
+        Some((high << 32) | low)
+    }
+
+    /// 读取2或4字节的索引
+    fn read_index(&mut self, size: usize) -> Option<u32> {
+        if size == 4 {
+            self.read_u32()
+        } else {
+            self.read_u16().map(|value| value as u32)
+        }
+    }
+
+    /// 以0结尾并按4字节对齐的流名称
+    fn read_stream_name(&mut self) -> Option<String> {
+        let rest = self.data.get(self.position..)?;
+        let end = rest.iter().take(32).position(|&b| b == 0)?;
+        let name = String::from_utf8_lossy(&rest[..end]).to_string();
+        self.position += (end + 4) & !3;
+        Some(name)
+    }
+}
+
+/// #Strings、#GUID、#Blob 与 #US 四个堆
+struct Heaps<'a> {
+    strings: &'a [u8],
+    guid: &'a [u8],
+    blob: &'a [u8],
+    user_strings: &'a [u8],
+}
+
+impl Heaps<'_> {
+    fn get_string(&self, index: u32) -> String {
+        let Some(rest) = self.strings.get(index as usize..) else {
+            return String::new();
+        };
+        let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        String::from_utf8_lossy(&rest[..end]).to_string()
+    }
+
+    /// GUID 索引从1开始
+    fn get_guid(&self, index: u32) -> String {
+        let Some(b) = index
+            .checked_sub(1)
+            .and_then(|index| self.guid.get(index as usize * 16..index as usize * 16 + 16))
+        else {
+            return String::new();
+        };
+        format!(
+            "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            u16::from_le_bytes([b[4], b[5]]),
+            u16::from_le_bytes([b[6], b[7]]),
+            b[8],
+            b[9],
+            b[10],
+            b[11],
+            b[12],
+            b[13],
+            b[14],
+            b[15]
+        )
+    }
+
+    fn get_blob(&self, index: u32) -> &[u8] {
+        read_blob(self.blob, index as usize)
+            .map(|(blob, _)| blob)
+            .unwrap_or_default()
+    }
+
+    fn get_blob_hex(&self, index: u32) -> String {
+        self.get_blob(index)
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect()
+    }
+
+    /// #US 中每项为UTF-16字符串加一个结尾标志字节
+    fn get_user_strings(&self) -> Vec<String> {
+        let mut user_strings = Vec::new();
+        let mut position = 1;
+        while user_strings.len() < MAX_USER_STRINGS
+            && let Some((blob, next)) = read_blob(self.user_strings, position)
+        {
+            position = next;
+            if blob.len() < 2 {
+                continue;
+            }
+            let units: Vec<u16> = blob
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            user_strings.push(String::from_utf16_lossy(&units));
+        }
+        user_strings
+    }
+}
+
+/// 读取压缩长度前缀的blob，返回内容与下一项的位置
+fn read_blob(heap: &[u8], position: usize) -> Option<(&[u8], usize)> {
+    let first = *heap.get(position)? as usize;
+    let (length, header_length) = if first & 0x80 == 0 {
+        (first, 1)
+    } else if first & 0xC0 == 0x80 {
+        (((first & 0x3F) << 8) | *heap.get(position + 1)? as usize, 2)
+    } else if first & 0xE0 == 0xC0 {
+        let rest = heap.get(position + 1..position + 4)?;
+        (
+            ((first & 0x1F) << 24)
+                | ((rest[0] as usize) << 16)
+                | ((rest[1] as usize) << 8)
+                | rest[2] as usize,
+            4,
+        )
+    } else {
+        return None;
+    };
+    let start = position + header_length;
+    let blob = heap.get(start..start.checked_add(length)?)?;
+    Some((blob, start + length))
+}
+
+/// #~ 流中各表的位置与行大小
+struct TablesStream<'a> {
+    data: &'a [u8],
+    rows: [u32; 64],
+    offsets: [usize; 45],
+    row_sizes: [usize; 45],
+    string_size: usize,
+    guid_size: usize,
+    blob_size: usize,
+}
+
+impl<'a> TablesStream<'a> {
+    /// 解析表流头并计算各表位置，表流截断时只保留完整的表
+    fn new(data: &'a [u8], info: &mut DotNetInfo) -> Option<Self> {
+        let mut reader = ByteReader::new(data, 4);
+        info.tables_major_version = reader.read_u8()?;
+        info.tables_minor_version = reader.read_u8()?;
+        info.heap_sizes = reader.read_u8()?;
+        reader.read_u8()?;
+        info.valid_tables = reader.read_u64()?;
+        info.sorted_tables = reader.read_u64()?;
+        let mut rows = [0u32; 64];
+        for (table, row) in rows.iter_mut().enumerate() {
+            if info.valid_tables & (1 << table) != 0 {
+                *row = reader.read_u32()?;
+                info.table_rows.push(MetadataTableRows {
+                    table: table as u8,
+                    name: TABLE_NAMES
+                        .get(table)
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| format!("0x{:02X}", table)),
+                    rows: *row,
+                });
+            }
+        }
+        if info.heap_sizes & HEAP_EXTRA_DATA != 0 {
+            reader.read_u32()?;
+        }
+        let index_size = |large: bool| if large { 4 } else { 2 };
+        let mut tables = TablesStream {
+            data,
+            rows,
+            offsets: [0; 45],
+            row_sizes: [0; 45],
+            string_size: index_size(info.heap_sizes & HEAP_STRING_LARGE != 0),
+            guid_size: index_size(info.heap_sizes & HEAP_GUID_LARGE != 0),
+            blob_size: index_size(info.heap_sizes & HEAP_BLOB_LARGE != 0),
+        };
+        let mut offset = reader.position;
+        for (table, schema) in TABLE_SCHEMAS.iter().enumerate() {
+            tables.offsets[table] = offset;
+            tables.row_sizes[table] = schema
+                .iter()
+                .map(|&column| tables.get_column_size(column))
+                .sum();
+            offset = offset.saturating_add(tables.row_sizes[table] * rows[table] as usize);
+        }
+        Some(tables)
+    }
+
+    fn get_rows(&self, table: u8) -> u32 {
+        self.rows.get(table as usize).copied().unwrap_or(0)
+    }
+
+    fn get_column_size(&self, column: Column) -> usize {
+        match column {
+            U16 => 2,
+            U32 => 4,
+            Str => self.string_size,
+            Guid => self.guid_size,
+            Blob => self.blob_size,
+            Table(table) => {
+                if self.get_rows(table) > 0xFFFF {
+                    4
+                } else {
+                    2
+                }
+            }
+            Coded(coded) => {
+                let max_rows = coded
+                    .tables
+                    .iter()
+                    .map(|&table| self.get_rows(table))
+                    .max()
+                    .unwrap_or(0);
+                if max_rows >= 1 << (16 - coded.tag_bits) {
+                    4
+                } else {
+                    2
+                }
+            }
+        }
+    }
+
+    /// 读取一行的全部列，行号从1开始，越界时返回None
+    fn get_row(&self, table: u8, row: u32) -> Option<Vec<u32>> {
+        let index = table as usize;
+        if row == 0 || row > self.get_rows(table) || index >= TABLE_SCHEMAS.len() {
+            return None;
+        }
+        let position = self.offsets[index] + self.row_sizes[index] * (row - 1) as usize;
+        let mut reader = ByteReader::new(self.data, position);
+        TABLE_SCHEMAS[index]
+            .iter()
+            .map(|&column| reader.read_index(self.get_column_size(column)))
+            .collect()
+    }
+
+    /// 读取整张表，遇到截断的行即停止
+    fn get_table(&self, table: u8) -> Vec<Vec<u32>> {
+        (1..=self.get_rows(table))
+            .map_while(|row| self.get_row(table, row))
+            .collect()
+    }
+
+    /// 将编码索引拆分为表与行号
+    fn decode_coded_index(coded: CodedIndex, value: u32) -> (u8, u32) {
+        let tag = value & ((1 << coded.tag_bits) - 1);
+        let table = coded
+            .tables
+            .get(tag as usize)
+            .copied()
+            .unwrap_or(TABLE_UNUSED);
+        (table, value >> coded.tag_bits)
+    }
+
+    /// 被引用行的可读名称
+    fn describe(&self, heaps: &Heaps, table: u8, row: u32) -> String {
+        if row == 0 {
+            return String::new();
+        }
+        let Some(columns) = self.get_row(table, row) else {
+            return format!("{}[{}]", get_table_name(table), row);
+        };
+        match table {
+            TABLE_MODULE => heaps.get_string(columns[1]),
+            TABLE_MODULE_REF => heaps.get_string(columns[0]),
+            TABLE_TYPE_REF => get_full_name(heaps, columns[2], columns[1]),
+            TABLE_TYPE_DEF => get_full_name(heaps, columns[2], columns[1]),
+            TABLE_EXPORTED_TYPE => get_full_name(heaps, columns[3], columns[2]),
+            TABLE_METHOD_DEF => heaps.get_string(columns[3]),
+            TABLE_ASSEMBLY_REF => heaps.get_string(columns[6]),
+            TABLE_FILE => heaps.get_string(columns[1]),
+            _ => format!("{}[{}]", get_table_name(table), row),
+        }
+    }
+
+    fn describe_coded(&self, heaps: &Heaps, coded: CodedIndex, value: u32) -> String {
+        let (table, row) = Self::decode_coded_index(coded, value);
+        self.describe(heaps, table, row)
+    }
+}
+
+fn get_table_name(table: u8) -> String {
+    TABLE_NAMES
+        .get(table as usize)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("0x{:02X}", table))
+}
+
+fn get_full_name(heaps: &Heaps, namespace: u32, name: u32) -> String {
+    let namespace = heaps.get_string(namespace);
+    let name = heaps.get_string(name);
+    if namespace.is_empty() {
+        name
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
+impl ImageCor20Header {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut cor20_header = MaybeUninit::<ImageCor20Header>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                cor20_header.as_mut_ptr() as *mut u8,
+                size_of::<ImageCor20Header>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(cor20_header.assume_init())
+        }
+    }
+
+    /// COMIMAGE_FLAGS 的名称
+    pub fn get_flag_names(&self) -> String {
+        COMIMAGE_FLAGS
+            .iter()
+            .filter(|(flag, _)| self.flags & flag != 0)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+impl DotNetInfo {
+    /// 读取 IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR 及其元数据，非托管文件返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Option<DotNetInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_COM_DESCRIPTOR);
+        if directory_rva == 0 {
+            return Ok(None);
+        }
+        let header_fo = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) => fo,
+            None => return Ok(None),
+        };
+        let cli_header = ImageCor20Header::new(file, header_fo)?;
+        let mut dotnet_info = DotNetInfo {
+            cli_header,
+            ..Default::default()
+        };
+        let metadata_fo = match rva_2_fo(
+            nt_head,
+            section_headers,
+            dotnet_info.cli_header.metadata_rva,
+        ) {
+            Some(fo) if dotnet_info.cli_header.metadata_size <= MAX_METADATA_SIZE => fo,
+            _ => return Ok(Some(dotnet_info)),
+        };
+        dotnet_info.metadata_file_offset = metadata_fo;
+        // 元数据可能被截断，只读取文件中实际存在的部分
+        let mut metadata = Vec::new();
+        file.seek(SeekFrom::Start(metadata_fo as u64))?;
+        file.take(dotnet_info.cli_header.metadata_size as u64)
+            .read_to_end(&mut metadata)?;
+        dotnet_info.parse_metadata(&metadata);
+        Ok(Some(dotnet_info))
+    }
+
+    /// 解析元数据根，格式错误时保留已解析的部分
+    fn parse_metadata(&mut self, metadata: &[u8]) -> Option<()> {
+        let mut reader = ByteReader::new(metadata, 0);
+        if reader.read_u32()? != METADATA_SIGNATURE {
+            return None;
+        }
+        self.metadata_major_version = reader.read_u16()?;
+        self.metadata_minor_version = reader.read_u16()?;
+        reader.read_u32()?;
+        let version_length = reader.read_u32()? as usize;
+        let version = reader.read_bytes(version_length)?;
+        let end = version
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(version.len());
+        self.metadata_version = String::from_utf8_lossy(&version[..end]).to_string();
+        reader.read_u16()?;
+        let stream_count = reader.read_u16()?.min(MAX_METADATA_STREAMS);
+        for _ in 0..stream_count {
+            let offset = reader.read_u32()?;
+            let size = reader.read_u32()?;
+            let name = reader.read_stream_name()?;
+            self.streams.push(MetadataStream { name, offset, size });
+        }
+
+        let get_stream = |names: &[&str]| {
+            self.streams
+                .iter()
+                .find(|stream| names.contains(&stream.name.as_str()))
+                .and_then(|stream| {
+                    let start = stream.offset as usize;
+                    metadata.get(start..start.checked_add(stream.size as usize)?)
+                })
+        };
+        let heaps = Heaps {
+            strings: get_stream(&["#Strings"]).unwrap_or_default(),
+            guid: get_stream(&["#GUID"]).unwrap_or_default(),
+            blob: get_stream(&["#Blob"]).unwrap_or_default(),
+            user_strings: get_stream(&["#US"]).unwrap_or_default(),
+        };
+        let tables_data = get_stream(&["#~", "#-"]);
+        self.user_strings = heaps.get_user_strings();
+        let tables = TablesStream::new(tables_data?, self)?;
+        self.read_tables(&tables, &heaps);
+        Some(())
+    }
+
+    fn read_tables(&mut self, tables: &TablesStream, heaps: &Heaps) {
+        self.modules = tables
+            .get_table(TABLE_MODULE)
+            .iter()
+            .map(|columns| ModuleRow {
+                generation: columns[0] as u16,
+                name: heaps.get_string(columns[1]),
+                mvid: heaps.get_guid(columns[2]),
+            })
+            .collect();
+        self.type_refs = tables
+            .get_table(TABLE_TYPE_REF)
+            .iter()
+            .map(|columns| TypeRefRow {
+                resolution_scope: tables.describe_coded(heaps, RESOLUTION_SCOPE, columns[0]),
+                name: heaps.get_string(columns[1]),
+                namespace: heaps.get_string(columns[2]),
+            })
+            .collect();
+        self.type_defs = tables
+            .get_table(TABLE_TYPE_DEF)
+            .iter()
+            .map(|columns| TypeDefRow {
+                flags: columns[0],
+                name: heaps.get_string(columns[1]),
+                namespace: heaps.get_string(columns[2]),
+                extends: tables.describe_coded(heaps, TYPE_DEF_OR_REF, columns[3]),
+                field_list: columns[4],
+                method_list: columns[5],
+            })
+            .collect();
+        self.method_defs = tables
+            .get_table(TABLE_METHOD_DEF)
+            .iter()
+            .enumerate()
+            .map(|(index, columns)| MethodDefRow {
+                rva: columns[0],
+                impl_flags: columns[1] as u16,
+                flags: columns[2] as u16,
+                name: heaps.get_string(columns[3]),
+                type_name: self.get_method_owner(index as u32 + 1),
+                signature: heaps.get_blob_hex(columns[4]),
+                param_list: columns[5],
+            })
+            .collect();
+        self.member_refs = tables
+            .get_table(TABLE_MEMBER_REF)
+            .iter()
+            .map(|columns| MemberRefRow {
+                class: tables.describe_coded(heaps, MEMBER_REF_PARENT, columns[0]),
+                name: heaps.get_string(columns[1]),
+                signature: heaps.get_blob_hex(columns[2]),
+            })
+            .collect();
+        self.assembly_refs = tables
+            .get_table(TABLE_ASSEMBLY_REF)
+            .iter()
+            .map(|columns| AssemblyRefRow {
+                version: format!(
+                    "{}.{}.{}.{}",
+                    columns[0], columns[1], columns[2], columns[3]
+                ),
+                flags: columns[4],
+                public_key_or_token: heaps.get_blob_hex(columns[5]),
+                name: heaps.get_string(columns[6]),
+                culture: heaps.get_string(columns[7]),
+            })
+            .collect();
+        self.manifest_resources = tables
+            .get_table(TABLE_MANIFEST_RESOURCE)
+            .iter()
+            .map(|columns| ManifestResourceRow {
+                offset: columns[0],
+                flags: columns[1],
+                name: heaps.get_string(columns[2]),
+                implementation: tables.describe_coded(heaps, IMPLEMENTATION, columns[3]),
+            })
+            .collect();
+    }
+
+    /// 方法属于 MethodList 不大于其行号的最后一个类型
+    fn get_method_owner(&self, method_row: u32) -> String {
+        self.type_defs
+            .iter()
+            .rev()
+            .find(|type_def| type_def.method_list != 0 && type_def.method_list <= method_row)
+            .map(|type_def| {
+                if type_def.namespace.is_empty() {
+                    type_def.name.clone()
+                } else {
+                    format!("{}.{}", type_def.namespace, type_def.name)
+                }
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造只有表流头的 #~ 流，rows 需按表号升序
+    fn build_tables_stream(heap_sizes: u8, rows: &[(u8, u32)]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0, 2, 0, heap_sizes, 1];
+        let valid_tables = rows
+            .iter()
+            .fold(0u64, |valid, &(table, _)| valid | 1 << table);
+        data.extend(valid_tables.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+        for &(_, row) in rows {
+            data.extend(row.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_heap_index_sizes() {
+        let mut info = DotNetInfo::default();
+        let data = build_tables_stream(0, &[(TABLE_MODULE, 1)]);
+        let tables = TablesStream::new(&data, &mut info).unwrap();
+        assert_eq!(
+            (tables.string_size, tables.guid_size, tables.blob_size),
+            (2, 2, 2)
+        );
+        // Module: Generation, Name, Mvid, EncId, EncBaseId
+        assert_eq!(tables.row_sizes[TABLE_MODULE as usize], 10);
+        assert_eq!(tables.offsets[TABLE_MODULE as usize], data.len());
+
+        let data = build_tables_stream(
+            HEAP_STRING_LARGE | HEAP_GUID_LARGE | HEAP_BLOB_LARGE | HEAP_EXTRA_DATA,
+            &[(TABLE_MODULE, 1)],
+        );
+        let mut padded = data.clone();
+        padded.extend(0u32.to_le_bytes());
+        let tables = TablesStream::new(&padded, &mut info).unwrap();
+        assert_eq!(
+            (tables.string_size, tables.guid_size, tables.blob_size),
+            (4, 4, 4)
+        );
+        assert_eq!(tables.row_sizes[TABLE_MODULE as usize], 18);
+        // 额外数据的4字节位于行数之后
+        assert_eq!(tables.offsets[TABLE_MODULE as usize], data.len() + 4);
+
+        let data = build_tables_stream(HEAP_STRING_LARGE, &[]);
+        let tables = TablesStream::new(&data, &mut info).unwrap();
+        assert_eq!(
+            (tables.string_size, tables.guid_size, tables.blob_size),
+            (4, 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_coded_index_widths() {
+        let mut info = DotNetInfo::default();
+        // HasCustomAttribute 占5位标签，行数达到 2^11 时需要4字节
+        let data = build_tables_stream(0, &[(TABLE_TYPE_REF, 0x7FF)]);
+        let tables = TablesStream::new(&data, &mut info).unwrap();
+        assert_eq!(tables.get_column_size(Coded(HAS_CUSTOM_ATTRIBUTE)), 2);
+        let data = build_tables_stream(0, &[(TABLE_TYPE_REF, 0x800)]);
+        let tables = TablesStream::new(&data, &mut info).unwrap();
+        assert_eq!(tables.get_column_size(Coded(HAS_CUSTOM_ATTRIBUTE)), 4);
+        assert_eq!(tables.get_column_size(Coded(TYPE_DEF_OR_REF)), 2);
+        assert_eq!(tables.get_column_size(Table(TABLE_TYPE_REF)), 2);
+
+        // TypeDefOrRef 占2位标签，阈值为 2^14；普通表索引只看是否超过 0xFFFF 行
+        let data = build_tables_stream(0, &[(TABLE_TYPE_DEF, 0x4000), (TABLE_FIELD, 0x10000)]);
+        let tables = TablesStream::new(&data, &mut info).unwrap();
+        assert_eq!(tables.get_column_size(Coded(TYPE_DEF_OR_REF)), 4);
+        assert_eq!(tables.get_column_size(Coded(TYPE_OR_METHOD_DEF)), 2);
+        assert_eq!(tables.get_column_size(Table(TABLE_TYPE_DEF)), 2);
+        assert_eq!(tables.get_column_size(Table(TABLE_FIELD)), 4);
+        // TypeDef: Flags, Name, Namespace, Extends, FieldList, MethodList
+        assert_eq!(
+            tables.row_sizes[TABLE_TYPE_DEF as usize],
+            4 + 2 + 2 + 4 + 4 + 2
+        );
+    }
+
+    #[test]
+    fn test_decode_coded_index() {
+        assert_eq!(
+            TablesStream::decode_coded_index(TYPE_DEF_OR_REF, (5 << 2) | 1),
+            (TABLE_TYPE_REF, 5)
+        );
+        assert_eq!(
+            TablesStream::decode_coded_index(RESOLUTION_SCOPE, (1 << 2) | 2),
+            (TABLE_ASSEMBLY_REF, 1)
+        );
+        assert_eq!(
+            TablesStream::decode_coded_index(TYPE_DEF_OR_REF, 3).0,
+            TABLE_UNUSED
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_EXCEPTION;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ExceptionTable, ImageRuntimeFunction, ImageSectionHeaders, RuntimeFunction,
+    UnwindCode, UnwindInfo, rva_2_fo,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+use std::rc::Rc;
+
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+/// ARM64、ARM64EC、ARM64X
+const IMAGE_FILE_MACHINE_ARM64: [u16; 3] = [0xAA64, 0xA641, 0xA64E];
+const ARM64_ENTRY_SIZE: u32 = 8;
+
+const UNW_FLAG_EHANDLER: u8 = 0x1;
+const UNW_FLAG_UHANDLER: u8 = 0x2;
+const UNW_FLAG_CHAININFO: u8 = 0x4;
+
+const UWOP_PUSH_NONVOL: u8 = 0;
+const UWOP_ALLOC_LARGE: u8 = 1;
+const UWOP_ALLOC_SMALL: u8 = 2;
+const UWOP_SET_FPREG: u8 = 3;
+const UWOP_SAVE_NONVOL: u8 = 4;
+const UWOP_SAVE_NONVOL_FAR: u8 = 5;
+const UWOP_EPILOG: u8 = 6;
+const UWOP_SPARE_CODE: u8 = 7;
+const UWOP_SAVE_XMM128: u8 = 8;
+const UWOP_SAVE_XMM128_FAR: u8 = 9;
+const UWOP_PUSH_MACHFRAME: u8 = 10;
+
+const REGISTER_NAMES: [&str; 16] = [
+    "RAX", "RCX", "RDX", "RBX", "RSP", "RBP", "RSI", "RDI", "R8", "R9", "R10", "R11", "R12", "R13",
+    "R14", "R15",
+];
+
+impl ImageRuntimeFunction {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut runtime_function = MaybeUninit::<ImageRuntimeFunction>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                runtime_function.as_mut_ptr() as *mut u8,
+                size_of::<ImageRuntimeFunction>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(runtime_function.assume_init())
+        }
+    }
+}
+
+impl UnwindCode {
+    /// 操作码名称
+    pub fn get_op_name(&self) -> &'static str {
+        match self.unwind_op {
+            UWOP_PUSH_NONVOL => "PUSH_NONVOL",
+            UWOP_ALLOC_LARGE => "ALLOC_LARGE",
+            UWOP_ALLOC_SMALL => "ALLOC_SMALL",
+            UWOP_SET_FPREG => "SET_FPREG",
+            UWOP_SAVE_NONVOL => "SAVE_NONVOL",
+            UWOP_SAVE_NONVOL_FAR => "SAVE_NONVOL_FAR",
+            UWOP_EPILOG => "EPILOG",
+            UWOP_SPARE_CODE => "SPARE_CODE",
+            UWOP_SAVE_XMM128 => "SAVE_XMM128",
+            UWOP_SAVE_XMM128_FAR => "SAVE_XMM128_FAR",
+            UWOP_PUSH_MACHFRAME => "PUSH_MACHFRAME",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// 操作数描述，例如 RBX 或 XMM6, [RSP+0x20]
+    pub fn get_operand_description(&self) -> String {
+        let register = REGISTER_NAMES[(self.op_info & 0xF) as usize];
+        match self.unwind_op {
+            UWOP_PUSH_NONVOL => register.to_string(),
+            UWOP_ALLOC_LARGE | UWOP_ALLOC_SMALL => format!("0x{:X}", self.operand),
+            UWOP_SAVE_NONVOL | UWOP_SAVE_NONVOL_FAR => {
+                format!("{}, [RSP+0x{:X}]", register, self.operand)
+            }
+            UWOP_SAVE_XMM128 | UWOP_SAVE_XMM128_FAR => {
+                format!("XMM{}, [RSP+0x{:X}]", self.op_info, self.operand)
+            }
+            UWOP_PUSH_MACHFRAME => {
+                if self.op_info == 0 {
+                    String::new()
+                } else {
+                    "ERROR CODE".to_string()
+                }
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// 该操作码占用的槽数，与LLVM的处理一致
+    fn get_slot_count(unwind_op: u8, op_info: u8) -> usize {
+        match unwind_op {
+            UWOP_ALLOC_LARGE if op_info == 0 => 2,
+            UWOP_ALLOC_LARGE => 3,
+            UWOP_SAVE_NONVOL | UWOP_SAVE_XMM128 | UWOP_EPILOG => 2,
+            UWOP_SAVE_NONVOL_FAR | UWOP_SAVE_XMM128_FAR | UWOP_SPARE_CODE => 3,
+            _ => 1,
+        }
+    }
+}
+
+impl UnwindInfo {
+    /// 读取UNWIND_INFO，并按槽解码展开代码
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        let version_and_flags = file.read_u8()?;
+        let size_of_prolog = file.read_u8()?;
+        let count_of_codes = file.read_u8()?;
+        let frame = file.read_u8()?;
+        // 槽数按偶数对齐，之后才是处理函数或链接项
+        let aligned_count = (count_of_codes as usize + 1) & !1;
+        let mut slots = vec![0u16; aligned_count];
+        file.read_u16_into::<LittleEndian>(&mut slots)?;
+
+        let mut unwind_info = UnwindInfo {
+            version: version_and_flags & 0x7,
+            flags: version_and_flags >> 3,
+            size_of_prolog,
+            count_of_codes,
+            frame_register: frame & 0xF,
+            frame_offset: frame >> 4,
+            ..Default::default()
+        };
+        unwind_info.unwind_codes = Self::decode_codes(&slots[..count_of_codes as usize]);
+
+        if unwind_info.flags & UNW_FLAG_CHAININFO != 0 {
+            let chained_address = address + 4 + aligned_count as u32 * 2;
+            unwind_info.chained_function = Some(ImageRuntimeFunction::new(file, chained_address)?);
+        } else if unwind_info.flags & (UNW_FLAG_EHANDLER | UNW_FLAG_UHANDLER) != 0 {
+            unwind_info.handler_rva = Some(file.read_u32::<LittleEndian>()?);
+        }
+        Ok(unwind_info)
+    }
+
+    fn decode_codes(slots: &[u16]) -> Vec<UnwindCode> {
+        let mut codes = Vec::new();
+        let mut index = 0;
+        while index < slots.len() {
+            let slot = slots[index];
+            let unwind_op = ((slot >> 8) & 0xF) as u8;
+            let op_info = (slot >> 12) as u8;
+            let slot_count = UnwindCode::get_slot_count(unwind_op, op_info);
+            // 附加槽越界时视为数据损坏，停止解码
+            if index + slot_count > slots.len() {
+                break;
+            }
+            let operand = match unwind_op {
+                UWOP_ALLOC_LARGE if op_info == 0 => slots[index + 1] as u32 * 8,
+                UWOP_ALLOC_LARGE => slots[index + 1] as u32 | (slots[index + 2] as u32) << 16,
+                UWOP_ALLOC_SMALL => op_info as u32 * 8 + 8,
+                UWOP_SAVE_NONVOL => slots[index + 1] as u32 * 8,
+                UWOP_SAVE_XMM128 => slots[index + 1] as u32 * 16,
+                UWOP_SAVE_NONVOL_FAR | UWOP_SAVE_XMM128_FAR => {
+                    slots[index + 1] as u32 | (slots[index + 2] as u32) << 16
+                }
+                _ => 0,
+            };
+            codes.push(UnwindCode {
+                code_offset: slot as u8,
+                unwind_op,
+                op_info,
+                operand,
+            });
+            index += slot_count;
+        }
+        codes
+    }
+
+    /// 帧寄存器名称，未使用帧寄存器时为空
+    pub fn get_frame_register_name(&self) -> &'static str {
+        if self.frame_register == 0 {
+            ""
+        } else {
+            REGISTER_NAMES[self.frame_register as usize]
+        }
+    }
+
+    /// 标志名称
+    pub fn get_flag_names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.flags & UNW_FLAG_EHANDLER != 0 {
+            names.push("EHANDLER");
+        }
+        if self.flags & UNW_FLAG_UHANDLER != 0 {
+            names.push("UHANDLER");
+        }
+        if self.flags & UNW_FLAG_CHAININFO != 0 {
+            names.push("CHAININFO");
+        }
+        names
+    }
+}
+
+impl ExceptionTable {
+    /// 遍历 IMAGE_DIRECTORY_ENTRY_EXCEPTION 下的函数表，只有x64和ARM64的格式是已知的
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<ExceptionTable>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_EXCEPTION);
+        let directory_size = data_dir.get_data_directory_size(DIRECTORY_EXCEPTION);
+        let directory_fo = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) if directory_rva != 0 && directory_size != 0 => fo,
+            _ => return Ok(ExceptionTable::default()),
+        };
+        let functions = match nt_head.machine() {
+            IMAGE_FILE_MACHINE_AMD64 => Self::read_x64_functions(
+                file,
+                nt_head,
+                section_headers,
+                directory_fo,
+                directory_size,
+            )?,
+            machine if IMAGE_FILE_MACHINE_ARM64.contains(&machine) => Self::read_arm64_functions(
+                file,
+                nt_head,
+                section_headers,
+                directory_fo,
+                directory_size,
+            )?,
+            _ => Vec::new(),
+        };
+        Ok(ExceptionTable(Rc::new(RefCell::new(functions))))
+    }
+
+    /// x64 的 RUNTIME_FUNCTION 为12字节，指向 UNWIND_INFO
+    fn read_x64_functions<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        directory_fo: u32,
+        directory_size: u32,
+    ) -> anyhow::Result<Vec<RuntimeFunction>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut functions = Vec::new();
+        let entry_size = size_of::<ImageRuntimeFunction>() as u32;
+        for index in 0..directory_size / entry_size {
+            let Some(entry_fo) = directory_fo.checked_add(index * entry_size) else {
+                break;
+            };
+            let runtime_function = ImageRuntimeFunction::new(file, entry_fo)?;
+            if runtime_function.begin_address == 0 && runtime_function.end_address == 0 {
+                continue;
+            }
+            let unwind_info = match rva_2_fo(
+                nt_head,
+                section_headers,
+                runtime_function.unwind_info_address,
+            ) {
+                Some(fo) => UnwindInfo::new(file, fo).ok(),
+                None => None,
+            };
+            functions.push(RuntimeFunction {
+                begin_address: runtime_function.begin_address,
+                end_address: runtime_function.end_address,
+                unwind_info_address: runtime_function.unwind_info_address,
+                section_name: section_headers
+                    .get_section_name_by_rva(runtime_function.begin_address)
+                    .unwrap_or_default(),
+                unwind_info,
+            });
+        }
+        Ok(functions)
+    }
+
+    /// ARM64/ARM64EC 的 RUNTIME_FUNCTION 为8字节，结束地址要从压缩展开数据或 .xdata 中的函数长度算出
+    fn read_arm64_functions<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        directory_fo: u32,
+        directory_size: u32,
+    ) -> anyhow::Result<Vec<RuntimeFunction>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut functions = Vec::new();
+        for index in 0..directory_size / ARM64_ENTRY_SIZE {
+            let Some(entry_fo) = directory_fo.checked_add(index * ARM64_ENTRY_SIZE) else {
+                break;
+            };
+            file.seek(SeekFrom::Start(entry_fo as u64))?;
+            let begin_address = file.read_u32::<LittleEndian>()?;
+            let unwind_data = file.read_u32::<LittleEndian>()?;
+            if begin_address == 0 && unwind_data == 0 {
+                continue;
+            }
+            // 低两位为0时 unwind_data 是 .xdata 的RVA，否则是压缩的展开数据
+            let (unwind_info_address, function_length) = if unwind_data & 0x3 == 0 {
+                let length = rva_2_fo(nt_head, section_headers, unwind_data)
+                    .and_then(|fo| {
+                        file.seek(SeekFrom::Start(fo as u64)).ok()?;
+                        file.read_u32::<LittleEndian>().ok()
+                    })
+                    .map(|header| header & 0x3FFFF)
+                    .unwrap_or(0);
+                (unwind_data, length)
+            } else {
+                (0, (unwind_data >> 2) & 0x7FF)
+            };
+            functions.push(RuntimeFunction {
+                begin_address,
+                end_address: begin_address.wrapping_add(function_length * 4),
+                unwind_info_address,
+                section_name: section_headers
+                    .get_section_name_by_rva(begin_address)
+                    .unwrap_or_default(),
+                unwind_info: None,
+            });
+        }
+        Ok(functions)
+    }
+
+    pub fn fclone(&self) -> Self {
+        ExceptionTable(Rc::clone(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+    use std::path::PathBuf;
+
+    /// steam_api64.dll 的 .pdata 与 .reloc 节所在文件偏移
+    const PDATA_FO: usize = 0x42800;
+    const RELOC_RVA: u32 = 0x4B000;
+    const RELOC_FO: usize = 0x45600;
+
+    fn functions_of(path: &str) -> Vec<RuntimeFunction> {
+        let file_info = FileInfo::new(PathBuf::from(path)).unwrap();
+        file_info.get_exception().unwrap().0.borrow().clone()
+    }
+
+    fn describe(unwind_info: &UnwindInfo) -> Vec<(&'static str, String)> {
+        unwind_info
+            .unwind_codes
+            .iter()
+            .map(|code| (code.get_op_name(), code.get_operand_description()))
+            .collect()
+    }
+
+    #[test]
+    fn test_exception_64() {
+        let functions = functions_of("test_pe/steam_api64.dll");
+        assert_eq!(functions.len(), 757);
+        assert!(
+            functions
+                .iter()
+                .all(|function| function.unwind_info.is_some())
+        );
+        let first = &functions[0];
+        assert_eq!(
+            (
+                first.begin_address,
+                first.end_address,
+                first.unwind_info_address
+            ),
+            (0x1000, 0x1021, 0x31AF0)
+        );
+        assert_eq!(first.section_name, ".text");
+        let unwind_info = first.unwind_info.as_ref().unwrap();
+        assert_eq!(
+            (
+                unwind_info.version,
+                unwind_info.flags,
+                unwind_info.size_of_prolog
+            ),
+            (1, 0, 4)
+        );
+        assert_eq!(describe(unwind_info), [("ALLOC_SMALL", "0x28".to_string())]);
+    }
+
+    #[test]
+    fn test_exception_unwind_codes() {
+        let functions = functions_of("test_pe/steam_api64.dll");
+        let unwind_info = functions[140].unwind_info.as_ref().unwrap();
+        assert_eq!(unwind_info.get_flag_names(), ["UHANDLER"]);
+        assert_eq!(unwind_info.handler_rva, Some(44192));
+        assert_eq!(unwind_info.count_of_codes, 10);
+        assert_eq!(
+            describe(unwind_info),
+            [
+                ("SAVE_NONVOL", "RDI, [RSP+0x78]".to_string()),
+                ("SAVE_NONVOL", "RSI, [RSP+0x70]".to_string()),
+                ("SAVE_NONVOL", "RBP, [RSP+0x68]".to_string()),
+                ("SAVE_NONVOL", "RBX, [RSP+0x60]".to_string()),
+                ("ALLOC_SMALL", "0x50".to_string()),
+                ("PUSH_NONVOL", "R14".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exception_chained_unwind_info() {
+        let functions = functions_of("test_pe/steam_api64.dll");
+        let function = &functions[146];
+        let unwind_info = function.unwind_info.as_ref().unwrap();
+        assert_eq!(unwind_info.get_flag_names(), ["CHAININFO"]);
+        assert_eq!(unwind_info.handler_rva, None);
+        let chained = unwind_info.chained_function.as_ref().unwrap();
+        // 链接到的是同一函数在前面的主体部分
+        assert_eq!(chained.end_address, function.begin_address);
+        assert_eq!(
+            (chained.begin_address, chained.unwind_info_address),
+            (16304, 203984)
+        );
+    }
+
+    #[test]
+    fn test_exception_truncated_unwind_info() {
+        // 第一个函数的 UNWIND_INFO 指向 .reloc 节开头，声明4个槽但文件只剩1个
+        let mut data = std::fs::read("test_pe/steam_api64.dll").unwrap();
+        data[PDATA_FO + 8..PDATA_FO + 12].copy_from_slice(&RELOC_RVA.to_le_bytes());
+        data[RELOC_FO..RELOC_FO + 6].copy_from_slice(&[0x01, 4, 4, 0, 0x04, 0x42]);
+        data.truncate(RELOC_FO + 6);
+        let path = std::env::temp_dir().join("penguin_exception_truncated.dll");
+        std::fs::write(&path, &data).unwrap();
+        let functions = functions_of(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(functions.len(), 757);
+        assert_eq!(functions[0].unwind_info_address, RELOC_RVA);
+        assert!(functions[0].unwind_info.is_none());
+        assert!(functions[1].unwind_info.is_some());
+    }
+
+    #[test]
+    fn test_decode_multi_slot_codes() {
+        let codes = UnwindInfo::decode_codes(&[0x0110, 0x0020, 0x1108, 0x0000, 0x0001]);
+        let operands: Vec<_> = codes
+            .iter()
+            .map(|code| (code.get_op_name(), code.operand))
+            .collect();
+        assert_eq!(
+            operands,
+            [("ALLOC_LARGE", 0x100), ("ALLOC_LARGE", 0x1_0000)]
+        );
+        // SAVE_NONVOL 缺少偏移槽时停止解码
+        assert!(UnwindInfo::decode_codes(&[0x0405]).is_empty());
+    }
+
+    #[test]
+    fn test_no_exception_directory() {
+        assert!(functions_of("test_pe/steam_api.dll").is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::i18n;
+use crate::tools_api::read_file::nt_header::DIRECTORY_EXPORT;
 use crate::tools_api::read_file::nt_header::traits::NtHeaders;
 use crate::tools_api::read_file::{
     DataDirectory, ExportDir, ExportInfo, ExportTable, ImageSectionHeaders, rva_2_fo,
@@ -10,6 +12,11 @@ use std::io::{Read, Seek};
 use std::mem::{MaybeUninit, size_of};
 use std::rc::Rc;
 
+/// 转发字符串的最大读取长度
+const MAX_FORWARDER_LENGTH: usize = 512;
+/// 防止畸形文件中的函数数量导致读取过多
+const MAX_EXPORT_FUNCTIONS: u32 = 0x10000;
+
 impl ExportDir {
     /// 读取导出表信息
     pub fn new<T>(
@@ -59,8 +66,12 @@ impl ExportInfo {
         T: NtHeaders + ?Sized,
     {
         file.seek(SeekFrom::Start(name_file_offset as _))?;
-        let name_string_rva =
-            rva_2_fo(nt_head, section_headers, file.read_u32::<LittleEndian>()?).unwrap();
+        // 名称RVA不在任何节中时跳过该名称
+        let Some(name_string_rva) =
+            rva_2_fo(nt_head, section_headers, file.read_u32::<LittleEndian>()?)
+        else {
+            return Ok(None);
+        };
         file.seek(SeekFrom::Start(name_string_rva as u64))?;
         let mut buf = [0; 512];
         file.read(&mut buf)?;
@@ -78,21 +89,73 @@ impl ExportInfo {
             })
             .unwrap_or(0) as u32;
         let name = String::from_utf8_lossy(&buf[0..name_length]).to_string();
-        file.seek(SeekFrom::Start(function_array_file_offset as _))?;
-        let function = file.read_u32::<LittleEndian>()?;
         file.seek(SeekFrom::Start(ordinals_array_file_offset as u64))?;
         let ordinals = file.read_u16::<LittleEndian>()?;
+        // 名称对应的函数由序号数组中的下标决定，而不是名称在数组中的位置
+        let function_address = function_array_file_offset.saturating_add(ordinals as u32 * 4);
+        file.seek(SeekFrom::Start(function_address as u64))?;
+        let function = file.read_u32::<LittleEndian>()?;
         Ok(Some(ExportInfo {
             name_rva: name_file_offset,
             name_string_fo: name_string_rva,
             name_max_length,
             name,
-            function_address: function_array_file_offset,
+            function_address,
             function,
             ordinals_address: ordinals_array_file_offset,
             ordinals,
+            ordinal: 0,
+            section: String::new(),
+            forwarder: None,
         }))
     }
+
+    /// 没有名称、只能按序号导出的函数
+    fn new_unnamed(
+        function_array_file_offset: u32,
+        index: u32,
+        file: &mut File,
+    ) -> anyhow::Result<ExportInfo> {
+        let function_address = function_array_file_offset.saturating_add(index * 4);
+        file.seek(SeekFrom::Start(function_address as u64))?;
+        let function = file.read_u32::<LittleEndian>()?;
+        Ok(ExportInfo {
+            function_address,
+            function,
+            ordinals: index as u16,
+            ..Default::default()
+        })
+    }
+
+    /// 是否存在名称，无名称的函数只能按序号导入
+    pub fn is_named(&self) -> bool {
+        self.name_rva != 0
+    }
+
+    /// 函数数组中RVA为0的空位
+    pub fn is_empty_slot(&self) -> bool {
+        self.function == 0
+    }
+
+    /// 读取转发字符串，格式为 `DLL名称.函数名` 或 `DLL名称.#序号`
+    fn read_forwarder<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        function: u32,
+    ) -> anyhow::Result<Option<String>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let Some(fo) = rva_2_fo(nt_head, section_headers, function) else {
+            return Ok(None);
+        };
+        file.seek(SeekFrom::Start(fo as u64))?;
+        let mut buf = [0; MAX_FORWARDER_LENGTH];
+        let length = file.read(&mut buf)?;
+        let end = buf[..length].iter().position(|&x| x == 0).unwrap_or(length);
+        Ok(Some(String::from_utf8_lossy(&buf[..end]).to_string()))
+    }
 }
 impl ExportTable {
     /// 怎里得到导出表信息，便于传递egui
@@ -100,28 +163,37 @@ impl ExportTable {
         f: &mut File,
         nt_head: &T,
         image_section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
         export_dir: &ExportDir,
     ) -> anyhow::Result<ExportTable>
     where
         T: NtHeaders + ?Sized,
     {
         let mut export_infos = Vec::<ExportInfo>::new();
+        let export_rva = data_dir.get_export_directory_address()?;
+        let export_range = export_rva
+            ..export_rva.saturating_add(data_dir.get_data_directory_size(DIRECTORY_EXPORT));
+        // 数量为0时对应的数组不会被读取，地址无效也没有关系
+        let array_fo = |rva: u32, count: u32| match rva_2_fo(nt_head, image_section_headers, rva) {
+            Some(fo) => Ok(fo),
+            None if count == 0 => Ok(0),
+            None => Err(anyhow::anyhow!(
+                "{}: 0x{:08X}",
+                i18n::ERROR_GET_RVA_OFFSET,
+                rva
+            )),
+        };
         let mut name_array_address =
-            rva_2_fo(nt_head, image_section_headers, export_dir.address_of_names).unwrap();
-        let mut function_array_address = rva_2_fo(
-            nt_head,
-            image_section_headers,
-            export_dir.address_of_functions,
-        )
-        .unwrap();
-        let mut ordinals_array_address = rva_2_fo(
-            nt_head,
-            image_section_headers,
+            array_fo(export_dir.address_of_names, export_dir.number_of_names)?;
+        let function_array_address =
+            array_fo(export_dir.address_of_functions, export_dir.number_of_func)?;
+        let mut ordinals_array_address = array_fo(
             export_dir.address_of_name_ordinals,
-        )
-        .unwrap();
+            export_dir.number_of_names,
+        )?;
 
-        for _ in 0..export_dir.number_of_names {
+        let mut named_infos = Vec::<ExportInfo>::new();
+        for _ in 0..export_dir.number_of_names.min(MAX_EXPORT_FUNCTIONS) {
             if let Some(export_info) = ExportInfo::new(
                 name_array_address,
                 function_array_address,
@@ -130,11 +202,41 @@ impl ExportTable {
                 nt_head,
                 image_section_headers,
             )? {
+                named_infos.push(export_info);
+            }
+            name_array_address = name_array_address.saturating_add(4);
+            ordinals_array_address = ordinals_array_address.saturating_add(2);
+        }
+        // 按函数数组顺序列出所有函数，一个函数可能对应多个名称，也可能没有名称
+        named_infos.sort_by_key(|export_info| export_info.ordinals);
+        let mut named_infos = named_infos.into_iter().peekable();
+        for index in 0..export_dir.number_of_func.min(MAX_EXPORT_FUNCTIONS) {
+            let mut has_name = false;
+            while let Some(export_info) =
+                named_infos.next_if(|export_info| export_info.ordinals as u32 == index)
+            {
                 export_infos.push(export_info);
-                name_array_address += 4;
-                function_array_address += 4;
-                // todo 存疑，这里应该是2字节
-                ordinals_array_address += 2;
+                has_name = true;
+            }
+            if !has_name {
+                export_infos.push(ExportInfo::new_unnamed(function_array_address, index, f)?);
+            }
+        }
+        // 序号超出函数数组的名称同样保留
+        export_infos.extend(named_infos);
+        for export_info in export_infos.iter_mut() {
+            export_info.ordinal = export_dir.base.wrapping_add(export_info.ordinals as u32);
+            export_info.section = image_section_headers
+                .get_section_name_by_rva(export_info.function)
+                .unwrap_or_default();
+            // 函数RVA落在导出目录范围内时，指向的是转发字符串而不是代码
+            if export_range.contains(&export_info.function) {
+                export_info.forwarder = ExportInfo::read_forwarder(
+                    f,
+                    nt_head,
+                    image_section_headers,
+                    export_info.function,
+                )?;
             }
         }
         Ok(ExportTable(Rc::new(RefCell::new(export_infos))))
@@ -150,3 +252,184 @@ impl ExportTable {
     //     self.0.borrow_mut().get_mut(index)
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::ExportInfo;
+    use std::path::PathBuf;
+
+    /// 借用 steam_api.dll 的 .reloc 节存放构造的导出目录
+    const DIRECTORY_RVA: u32 = 0x40000;
+    const DIRECTORY_FO: usize = 0x3C600;
+    const DIRECTORY_SIZE: u32 = 0x200;
+    /// 导出数据目录项的文件偏移
+    const EXPORT_ENTRY_FO: usize = 0x1A8 - 5 * 8;
+    const BASE: u32 = 10;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[DIRECTORY_FO + offset..DIRECTORY_FO + offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn put_rvas(data: &mut [u8], offset: usize, rvas: &[u32]) {
+        for (index, rva) in rvas.iter().enumerate() {
+            put(data, offset + index * 4, &rva.to_le_bytes());
+        }
+    }
+
+    /// 5个函数、3个名称的导出目录，名称按字母顺序排列，与函数数组的顺序无关：
+    /// 下标0为 Zeta，1为空位，2为转发的 Forwarded，3为 Alpha，4只有序号且RVA恰好在目录末尾
+    fn synthetic_exports() -> Vec<ExportInfo> {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        data[DIRECTORY_FO..DIRECTORY_FO + DIRECTORY_SIZE as usize].fill(0);
+        data[EXPORT_ENTRY_FO..EXPORT_ENTRY_FO + 4].copy_from_slice(&DIRECTORY_RVA.to_le_bytes());
+        data[EXPORT_ENTRY_FO + 4..EXPORT_ENTRY_FO + 8]
+            .copy_from_slice(&DIRECTORY_SIZE.to_le_bytes());
+        put_rvas(
+            &mut data,
+            12,
+            &[
+                DIRECTORY_RVA + 0x100,
+                BASE,
+                5,
+                3,
+                DIRECTORY_RVA + 0x40,
+                DIRECTORY_RVA + 0x60,
+                DIRECTORY_RVA + 0x70,
+            ],
+        );
+        put_rvas(
+            &mut data,
+            0x40,
+            &[
+                0x1010,
+                0,
+                DIRECTORY_RVA + 0x180,
+                0x1030,
+                DIRECTORY_RVA + DIRECTORY_SIZE,
+            ],
+        );
+        put_rvas(
+            &mut data,
+            0x60,
+            &[
+                DIRECTORY_RVA + 0x110,
+                DIRECTORY_RVA + 0x120,
+                DIRECTORY_RVA + 0x130,
+            ],
+        );
+        for (index, ordinal) in [3u16, 2, 0].iter().enumerate() {
+            put(&mut data, 0x70 + index * 2, &ordinal.to_le_bytes());
+        }
+        put(&mut data, 0x100, b"synthetic.dll\0");
+        put(&mut data, 0x110, b"Alpha\0");
+        put(&mut data, 0x120, b"Forwarded\0");
+        put(&mut data, 0x130, b"Zeta\0");
+        put(&mut data, 0x180, b"NTDLL.RtlAllocateHeap\0");
+
+        let path = std::env::temp_dir().join("penguin_synthetic_exports.dll");
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        let exports = file_info.get_export().unwrap().0.borrow().clone();
+        drop(file_info);
+        std::fs::remove_file(&path).unwrap();
+        exports
+    }
+
+    #[test]
+    fn test_forwarded_export() {
+        let exports = synthetic_exports();
+        let forwarded = &exports[2];
+        assert_eq!(forwarded.name, "Forwarded");
+        assert_eq!(forwarded.function, DIRECTORY_RVA + 0x180);
+        assert_eq!(
+            forwarded.forwarder.as_deref(),
+            Some("NTDLL.RtlAllocateHeap")
+        );
+        // 只有RVA落在导出目录范围内的函数才是转发
+        let forwarders: Vec<_> = exports
+            .iter()
+            .filter(|export| export.forwarder.is_some())
+            .map(|export| export.ordinal)
+            .collect();
+        assert_eq!(forwarders, [BASE + 2]);
+        assert_eq!(exports[4].function, DIRECTORY_RVA + DIRECTORY_SIZE);
+        assert_eq!(exports[4].forwarder, None);
+    }
+
+    #[test]
+    fn test_ordinal_only_exports() {
+        let exports = synthetic_exports();
+        // 每个函数数组项都列出，序号为 Base + 下标
+        let ordinals: Vec<_> = exports.iter().map(|export| export.ordinal).collect();
+        assert_eq!(ordinals, [10, 11, 12, 13, 14]);
+        let unnamed = &exports[4];
+        assert!(!unnamed.is_named());
+        assert!(unnamed.name.is_empty());
+        assert_eq!(unnamed.ordinals, 4);
+        assert_eq!(unnamed.section, ".reloc");
+        assert!(!unnamed.is_empty_slot());
+    }
+
+    #[test]
+    fn test_empty_export_slot() {
+        let exports = synthetic_exports();
+        let empty = &exports[1];
+        assert!(!empty.is_named());
+        assert!(empty.is_empty_slot());
+        assert_eq!(empty.ordinal, BASE + 1);
+        assert_eq!(empty.function_address, DIRECTORY_FO as u32 + 0x40 + 4);
+        assert!(empty.section.is_empty());
+        assert_eq!(
+            exports
+                .iter()
+                .filter(|export| export.is_empty_slot())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_named_exports_use_ordinal_table() {
+        // 名称对应的函数由序号数组决定，而不是名称在名称数组中的位置
+        let exports = synthetic_exports();
+        let named: Vec<_> = exports
+            .iter()
+            .filter(|export| export.is_named())
+            .map(|export| (export.name.as_str(), export.ordinal, export.function))
+            .collect();
+        assert_eq!(
+            named,
+            [
+                ("Zeta", 10, 0x1010),
+                ("Forwarded", 12, DIRECTORY_RVA + 0x180),
+                ("Alpha", 13, 0x1030),
+            ]
+        );
+        assert_eq!(exports[0].section, ".text");
+    }
+
+    #[test]
+    fn test_named_exports_32() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        let exports = file_info.get_export().unwrap().0.borrow().clone();
+        assert_eq!(exports.len(), 1059);
+        assert!(exports.iter().all(|export| export.is_named()));
+        assert_eq!(
+            (
+                exports[0].name.as_str(),
+                exports[0].ordinal,
+                exports[0].function
+            ),
+            ("GetHSteamPipe", 1, 38208)
+        );
+        assert_eq!(
+            (
+                exports[100].name.as_str(),
+                exports[100].ordinal,
+                exports[100].function
+            ),
+            ("SteamAPI_ISteamController_RunFrame", 101, 7952)
+        );
+    }
+}
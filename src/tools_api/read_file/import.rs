@@ -6,6 +6,8 @@ use std::io::{Read, Seek};
 use std::mem::{MaybeUninit, size_of};
 use std::rc::Rc;
 
+use crate::ORDINAL_DATABASE;
+use crate::i18n;
 use crate::tools_api::is_64;
 use crate::tools_api::read_file::nt_header::traits::NtHeaders;
 use crate::tools_api::read_file::{
@@ -13,6 +15,13 @@ use crate::tools_api::read_file::{
     ImportFunction, ImportTable, rva_2_fo,
 };
 
+/// 描述符与函数数量来自文件本身，防止畸形文件导致读取过多
+const MAX_IMPORT_DESCRIPTORS: u32 = 0x1000;
+const MAX_IMPORT_FUNCTIONS: u64 = 0x10000;
+/// 导入名称表项的序号标志，PE32 与 PE32+ 的位置不同
+const IMAGE_ORDINAL_FLAG32: u64 = 1 << 31;
+const IMAGE_ORDINAL_FLAG64: u64 = 1 << 63;
+
 impl ImportDescriptor {
     pub fn new<T>(
         file: &mut File,
@@ -24,33 +33,34 @@ impl ImportDescriptor {
     where
         T: NtHeaders + ?Sized,
     {
-        let mut import_descriptor: ImportDescriptor = Default::default();
-        if let Some(fo) = rva_2_fo(
-            nt_head,
-            image_section_headers,
-            data_dir.get_import_directory_address()?,
-        ) {
-            file.seek(SeekFrom::Start((fo + index * 0x14) as u64))?;
-            import_descriptor = unsafe {
-                let mut import_descriptor = MaybeUninit::<ImportDescriptor>::uninit();
-                let bytes = std::slice::from_raw_parts_mut(
-                    import_descriptor.as_mut_ptr() as *mut u8,
-                    size_of::<ImportDescriptor>(),
-                );
-                file.read_exact(bytes)?;
-                import_descriptor.assume_init()
-            };
-            // 特殊的情况，有时pe的data dic的大小并不完全代表着他import dll的个数，而是类似列表最后为0来结束
-            if import_descriptor.name_address == 0 {
-                return Ok(None);
-            }
+        let import_directory_address = data_dir.get_import_directory_address()?;
+        // 没有导入表、描述符过多或导入表不在任何节中时结束
+        if import_directory_address == 0 || index >= MAX_IMPORT_DESCRIPTORS {
+            return Ok(None);
+        }
+        let Some(fo) = rva_2_fo(nt_head, image_section_headers, import_directory_address) else {
+            return Ok(None);
+        };
+        file.seek(SeekFrom::Start(fo as u64 + index as u64 * 0x14))?;
+        let import_descriptor = unsafe {
+            let mut import_descriptor = MaybeUninit::<ImportDescriptor>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                import_descriptor.as_mut_ptr() as *mut u8,
+                size_of::<ImportDescriptor>(),
+            );
+            file.read_exact(bytes)?;
+            import_descriptor.assume_init()
+        };
+        // 特殊的情况，有时pe的data dic的大小并不完全代表着他import dll的个数，而是类似列表最后为0来结束
+        if import_descriptor.name_address == 0 {
+            return Ok(None);
         }
         Ok(Some(import_descriptor))
     }
 }
 impl ImportFunction {
     pub fn new(file: &mut File, addr: u32) -> anyhow::Result<Option<ImportFunction>> {
-        file.seek(SeekFrom::Start((addr + 2) as u64))?;
+        file.seek(SeekFrom::Start(addr as u64 + 2))?;
         let mut buf = [0; 256];
         file.read(&mut buf)?;
         let mut flag = 1;
@@ -68,12 +78,36 @@ impl ImportFunction {
             .unwrap_or(0) as u32;
         let name = String::from_utf8_lossy(&buf[0..name_length]).to_string();
         Ok(Some(ImportFunction {
-            name_address: addr + 2,
+            name_address: addr.saturating_add(2),
             name_length: name.len() as u32,
             name_max_length,
             name,
+            ordinal: None,
         }))
     }
+
+    /// 按序号导入的函数，从序号数据库中查找函数名
+    pub fn from_ordinal(dll_name: &str, thunk: u64, ordinal: u16) -> ImportFunction {
+        ImportFunction {
+            name_address: thunk as u32,
+            name_length: 0,
+            name_max_length: 0,
+            name: ORDINAL_DATABASE
+                .get_name(dll_name, ordinal)
+                .unwrap_or_default()
+                .to_string(),
+            ordinal: Some(ordinal),
+        }
+    }
+
+    /// 用于显示的名称，按序号导入时附带序号
+    pub fn get_display_name(&self) -> String {
+        match self.ordinal {
+            Some(ordinal) if self.name.is_empty() => format!("#{}", ordinal),
+            Some(ordinal) => format!("{} (#{})", self.name, ordinal),
+            None => self.name.clone(),
+        }
+    }
 }
 
 impl ImportDll {
@@ -89,15 +123,36 @@ impl ImportDll {
     {
         let mut function_info = Vec::new();
         let mut addr;
+        // OriginalFirstThunk 为0或无效时加载器使用 FirstThunk
         let function_info_address =
-            match rva_2_fo(nt_head, section_headers, import_descriptor.dummy_union_name) {
-                None => return Err(anyhow::anyhow!("End")),
-                Some(ret) => ret,
-            };
+            rva_2_fo(nt_head, section_headers, import_descriptor.dummy_union_name)
+                .or_else(|| rva_2_fo(nt_head, section_headers, import_descriptor.first_thunk))
+                .ok_or(anyhow::anyhow!(
+                    "{}: 0x{:08X}",
+                    i18n::ERROR_GET_RVA_OFFSET,
+                    import_descriptor.first_thunk
+                ))?;
+        let name_file_offset = rva_2_fo(nt_head, section_headers, import_descriptor.name_address)
+            .ok_or(anyhow::anyhow!(
+            "{}: 0x{:08X}",
+            i18n::ERROR_GET_RVA_OFFSET,
+            import_descriptor.name_address
+        ))?;
+        // 序号导入需要根据DLL名称查找函数名，因此先读取DLL名称
+        let mut name = [0u8; 256];
+        file.seek(SeekFrom::Start(name_file_offset as u64))?;
+        file.read(&mut name)? as u64;
+        let name: String =
+            String::from_utf8_lossy(name.split(|x| *x == 0).next().unwrap()).parse()?;
         file.seek(SeekFrom::Start(function_info_address as u64))?;
-        let mut i = 0;
-        loop {
-            if is_64(file, image_dos_header)? {
+        let is_64_bit = is_64(file, image_dos_header)?;
+        let ordinal_flag = if is_64_bit {
+            IMAGE_ORDINAL_FLAG64
+        } else {
+            IMAGE_ORDINAL_FLAG32
+        };
+        for i in 0..MAX_IMPORT_FUNCTIONS {
+            if is_64_bit {
                 file.seek(SeekFrom::Start(function_info_address as u64 + i * 8u64))?;
 
                 addr = file.read_u64::<LittleEndian>()?;
@@ -108,20 +163,13 @@ impl ImportDll {
             if addr == 0 {
                 break;
             }
-            i += 1;
 
-            //pe文件存在值为 0x8000000000000003的情况，此时的值是无效的，只截取后位即可
-            let addr: u32 = if addr > 0x0FFFFFFF {
-                function_info.push(ImportFunction {
-                    name_address: addr as u32,
-                    name_length: 0,
-                    name_max_length: 0,
-                    name: format!("0x{:x}", addr & 0x0FFFFFFF),
-                });
+            // 最高位为序号标志，此时低16位为序号，否则为 IMAGE_IMPORT_BY_NAME 的RVA
+            if addr & ordinal_flag != 0 {
+                function_info.push(ImportFunction::from_ordinal(&name, addr, addr as u16));
                 continue;
-            } else {
-                addr as u32
-            };
+            }
+            let addr = addr as u32;
             if let Some(addr) = rva_2_fo(nt_head, section_headers, addr) {
                 match ImportFunction::new(file, addr)? {
                     None => {
@@ -133,21 +181,18 @@ impl ImportDll {
                 }
             }
         }
-        let mut name = [0u8; 256];
-        file.seek(SeekFrom::Start(
-            rva_2_fo(nt_head, section_headers, import_descriptor.name_address).unwrap() as u64,
-        ))?;
-        file.read(&mut name)? as u64;
-        let name = String::from_utf8_lossy(name.split(|x| *x == 0).next().unwrap()).parse()?;
         Ok(ImportDll {
             name_address: import_descriptor.name_address,
             name_length: 0,
             name,
-            time_date_stamp: 0,
-            forwarder_chain: 0,
-            first_thunk: 0,
+            time_date_stamp: import_descriptor.time_date_stamp,
+            forwarder_chain: import_descriptor.forwarder_chain,
+            first_thunk: import_descriptor.first_thunk,
             function_info,
             function_size: 0,
+            delay_load: None,
+            bound_import: None,
+            stale_binding: false,
         })
     }
 }
@@ -0,0 +1,347 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_LOAD_CONFIG;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, GuardTableEntry, ImageLoadConfigDirectory, ImageLoadConfigDirectory64,
+    ImageSectionHeaders, LoadConfigInfo, rva_2_fo,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+
+/// 表项数量来自文件本身，防止畸形文件导致读取过多
+const MAX_TABLE_ENTRIES: u64 = 0x10_0000;
+/// GuardFlags 高4位为每个表项附加信息的字节数
+const GUARD_CF_FUNCTION_TABLE_SIZE_MASK: u32 = 0xF000_0000;
+const GUARD_CF_FUNCTION_TABLE_SIZE_SHIFT: u32 = 28;
+
+/// 按首字段size读取加载配置目录，size之外的字段保持为0
+fn read_versioned<T: Default>(file: &mut File, address: u32) -> anyhow::Result<T> {
+    file.seek(SeekFrom::Start(address as u64))?;
+    let size = file.read_u32::<LittleEndian>()? as usize;
+    let read_size = size.min(size_of::<T>());
+    file.seek(SeekFrom::Start(address as u64))?;
+    unsafe {
+        let mut load_config = MaybeUninit::<T>::zeroed();
+        let bytes = std::slice::from_raw_parts_mut(load_config.as_mut_ptr() as *mut u8, read_size);
+        file.read_exact(bytes)?;
+        Ok(load_config.assume_init())
+    }
+}
+
+impl ImageLoadConfigDirectory {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        read_versioned(file, address)
+    }
+}
+
+impl ImageLoadConfigDirectory64 {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        read_versioned(file, address)
+    }
+}
+
+impl From<ImageLoadConfigDirectory> for LoadConfigInfo {
+    fn from(dir: ImageLoadConfigDirectory) -> Self {
+        LoadConfigInfo {
+            size: dir.size,
+            time_date_stamp: dir.time_date_stamp,
+            major_version: dir.major_version,
+            minor_version: dir.minor_version,
+            global_flags_clear: dir.global_flags_clear,
+            global_flags_set: dir.global_flags_set,
+            critical_section_default_timeout: dir.critical_section_default_timeout,
+            process_heap_flags: dir.process_heap_flags,
+            csd_version: dir.csd_version,
+            dependent_load_flags: dir.dependent_load_flags,
+            lock_prefix_table: dir.lock_prefix_table as u64,
+            edit_list: dir.edit_list as u64,
+            security_cookie: dir.security_cookie as u64,
+            se_handler_table: dir.se_handler_table as u64,
+            se_handler_count: dir.se_handler_count as u64,
+            guard_cf_check_function_pointer: dir.guard_cf_check_function_pointer as u64,
+            guard_cf_dispatch_function_pointer: dir.guard_cf_dispatch_function_pointer as u64,
+            guard_cf_function_table: dir.guard_cf_function_table as u64,
+            guard_cf_function_count: dir.guard_cf_function_count as u64,
+            guard_flags: dir.guard_flags,
+            guard_address_taken_iat_entry_table: dir.guard_address_taken_iat_entry_table as u64,
+            guard_address_taken_iat_entry_count: dir.guard_address_taken_iat_entry_count as u64,
+            guard_long_jump_target_table: dir.guard_long_jump_target_table as u64,
+            guard_long_jump_target_count: dir.guard_long_jump_target_count as u64,
+            dynamic_value_reloc_table: dir.dynamic_value_reloc_table as u64,
+            dynamic_value_reloc_table_offset: dir.dynamic_value_reloc_table_offset,
+            dynamic_value_reloc_table_section: dir.dynamic_value_reloc_table_section,
+            chpe_metadata_pointer: dir.chpe_metadata_pointer as u64,
+            volatile_metadata_pointer: dir.volatile_metadata_pointer as u64,
+            guard_eh_continuation_table: dir.guard_eh_continuation_table as u64,
+            guard_eh_continuation_count: dir.guard_eh_continuation_count as u64,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ImageLoadConfigDirectory64> for LoadConfigInfo {
+    fn from(dir: ImageLoadConfigDirectory64) -> Self {
+        LoadConfigInfo {
+            size: dir.size,
+            time_date_stamp: dir.time_date_stamp,
+            major_version: dir.major_version,
+            minor_version: dir.minor_version,
+            global_flags_clear: dir.global_flags_clear,
+            global_flags_set: dir.global_flags_set,
+            critical_section_default_timeout: dir.critical_section_default_timeout,
+            process_heap_flags: dir.process_heap_flags,
+            csd_version: dir.csd_version,
+            dependent_load_flags: dir.dependent_load_flags,
+            lock_prefix_table: dir.lock_prefix_table,
+            edit_list: dir.edit_list,
+            security_cookie: dir.security_cookie,
+            se_handler_table: dir.se_handler_table,
+            se_handler_count: dir.se_handler_count,
+            guard_cf_check_function_pointer: dir.guard_cf_check_function_pointer,
+            guard_cf_dispatch_function_pointer: dir.guard_cf_dispatch_function_pointer,
+            guard_cf_function_table: dir.guard_cf_function_table,
+            guard_cf_function_count: dir.guard_cf_function_count,
+            guard_flags: dir.guard_flags,
+            guard_address_taken_iat_entry_table: dir.guard_address_taken_iat_entry_table,
+            guard_address_taken_iat_entry_count: dir.guard_address_taken_iat_entry_count,
+            guard_long_jump_target_table: dir.guard_long_jump_target_table,
+            guard_long_jump_target_count: dir.guard_long_jump_target_count,
+            dynamic_value_reloc_table: dir.dynamic_value_reloc_table,
+            dynamic_value_reloc_table_offset: dir.dynamic_value_reloc_table_offset,
+            dynamic_value_reloc_table_section: dir.dynamic_value_reloc_table_section,
+            chpe_metadata_pointer: dir.chpe_metadata_pointer,
+            volatile_metadata_pointer: dir.volatile_metadata_pointer,
+            guard_eh_continuation_table: dir.guard_eh_continuation_table,
+            guard_eh_continuation_count: dir.guard_eh_continuation_count,
+            ..Default::default()
+        }
+    }
+}
+
+impl LoadConfigInfo {
+    /// 读取 IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG，文件不含加载配置时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+        is_64_bit: bool,
+    ) -> anyhow::Result<Option<LoadConfigInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_LOAD_CONFIG);
+        if directory_rva == 0 {
+            return Ok(None);
+        }
+        let file_offset = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) => fo,
+            None => return Ok(None),
+        };
+        let mut load_config: LoadConfigInfo = if is_64_bit {
+            ImageLoadConfigDirectory64::new(file, file_offset)?.into()
+        } else {
+            ImageLoadConfigDirectory::new(file, file_offset)?.into()
+        };
+        load_config.file_offset = file_offset;
+
+        let image_base = nt_head.get_image_base();
+        let reader = TableReader {
+            nt_head,
+            section_headers,
+            image_base,
+        };
+        // SafeSEH 表只存在于32位映像，表项为不带附加信息的RVA
+        if !is_64_bit {
+            load_config.se_handlers = reader
+                .read(
+                    file,
+                    load_config.se_handler_table,
+                    load_config.se_handler_count,
+                    0,
+                )?
+                .into_iter()
+                .map(|entry| entry.rva)
+                .collect();
+        }
+        let extra_size = (load_config.guard_flags & GUARD_CF_FUNCTION_TABLE_SIZE_MASK)
+            >> GUARD_CF_FUNCTION_TABLE_SIZE_SHIFT;
+        load_config.guard_cf_functions = reader.read(
+            file,
+            load_config.guard_cf_function_table,
+            load_config.guard_cf_function_count,
+            extra_size,
+        )?;
+        load_config.guard_address_taken_iat_entries = reader.read(
+            file,
+            load_config.guard_address_taken_iat_entry_table,
+            load_config.guard_address_taken_iat_entry_count,
+            extra_size,
+        )?;
+        load_config.guard_long_jump_targets = reader.read(
+            file,
+            load_config.guard_long_jump_target_table,
+            load_config.guard_long_jump_target_count,
+            extra_size,
+        )?;
+        load_config.guard_eh_continuations = reader.read(
+            file,
+            load_config.guard_eh_continuation_table,
+            load_config.guard_eh_continuation_count,
+            extra_size,
+        )?;
+        Ok(Some(load_config))
+    }
+
+    /// GuardFlags 中已置位的标志名称
+    pub fn get_guard_flag_names(&self) -> Vec<&'static str> {
+        const GUARD_FLAGS: [(u32, &str); 17] = [
+            (0x0000_0100, "CF_INSTRUMENTED"),
+            (0x0000_0200, "CFW_INSTRUMENTED"),
+            (0x0000_0400, "CF_FUNCTION_TABLE_PRESENT"),
+            (0x0000_0800, "SECURITY_COOKIE_UNUSED"),
+            (0x0000_1000, "PROTECT_DELAYLOAD_IAT"),
+            (0x0000_2000, "DELAYLOAD_IAT_IN_ITS_OWN_SECTION"),
+            (0x0000_4000, "CF_EXPORT_SUPPRESSION_INFO_PRESENT"),
+            (0x0000_8000, "CF_ENABLE_EXPORT_SUPPRESSION"),
+            (0x0001_0000, "CF_LONGJUMP_TABLE_PRESENT"),
+            (0x0002_0000, "RF_INSTRUMENTED"),
+            (0x0004_0000, "RF_ENABLE"),
+            (0x0008_0000, "RF_STRICT"),
+            (0x0010_0000, "RETPOLINE_PRESENT"),
+            (0x0040_0000, "EH_CONTINUATION_TABLE_PRESENT"),
+            (0x0080_0000, "XFG_ENABLED"),
+            (0x0100_0000, "CASTGUARD_PRESENT"),
+            (0x0200_0000, "MEMCPY_PRESENT"),
+        ];
+        GUARD_FLAGS
+            .iter()
+            .filter(|(flag, _)| self.guard_flags & flag != 0)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+/// 读取以VA给出、元素为RVA的各类表
+struct TableReader<'a, T: NtHeaders + ?Sized> {
+    nt_head: &'a T,
+    section_headers: &'a ImageSectionHeaders,
+    image_base: u64,
+}
+
+impl<T: NtHeaders + ?Sized> TableReader<'_, T> {
+    fn read(
+        &self,
+        file: &mut File,
+        table_va: u64,
+        count: u64,
+        extra_size: u32,
+    ) -> anyhow::Result<Vec<GuardTableEntry>> {
+        let mut entries = Vec::new();
+        if table_va < self.image_base || count == 0 {
+            return Ok(entries);
+        }
+        let table_rva = (table_va - self.image_base) as u32;
+        let table_fo = match rva_2_fo(self.nt_head, self.section_headers, table_rva) {
+            Some(fo) => fo,
+            None => return Ok(entries),
+        };
+        file.seek(SeekFrom::Start(table_fo as u64))?;
+        let mut extra = vec![0u8; extra_size as usize];
+        for _ in 0..count.min(MAX_TABLE_ENTRIES) {
+            let rva = file.read_u32::<LittleEndian>()?;
+            file.read_exact(&mut extra)?;
+            entries.push(GuardTableEntry {
+                rva,
+                flags: extra.first().copied().unwrap_or(0),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+    use std::mem::offset_of;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_read_versioned_size() {
+        // 只声明了到 SecurityCookie 为止的0x40字节，之后的数据不属于该结构
+        let mut data = vec![0xCCu8; 0x80];
+        data[..4].copy_from_slice(&0x40u32.to_le_bytes());
+        data[0x3C..0x40].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+        let path = std::env::temp_dir().join("penguin_load_config_size.bin");
+        std::fs::write(&path, &data).unwrap();
+        let load_config = ImageLoadConfigDirectory::new(&mut File::open(&path).unwrap(), 0);
+        std::fs::remove_file(&path).unwrap();
+        let load_config = load_config.unwrap();
+        assert_eq!(load_config.size, 0x40);
+        assert_eq!(load_config.security_cookie, 0x1234_5678);
+        assert_eq!(load_config.se_handler_table, 0);
+        assert_eq!(load_config.se_handler_count, 0);
+        assert_eq!(load_config.guard_flags, 0);
+    }
+
+    #[test]
+    fn test_load_config_32() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        let load_config = file_info.get_load_config().unwrap().unwrap();
+        assert_eq!(load_config.size, 188);
+        assert_eq!(load_config.security_cookie, 0x3B43_C214);
+        assert_eq!(load_config.se_handler_table, 0x3B42_C57C);
+        assert_eq!(load_config.se_handler_count, 10);
+        assert_eq!(
+            load_config.se_handlers,
+            [
+                49728, 49885, 50416, 55392, 150837, 150885, 150912, 150975, 151008, 151020
+            ]
+        );
+        assert_eq!(load_config.get_guard_flag_names(), ["CF_INSTRUMENTED"]);
+    }
+
+    #[test]
+    fn test_load_config_64() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api64.dll")).unwrap();
+        let load_config = file_info.get_load_config().unwrap().unwrap();
+        assert_eq!(load_config.size, 312);
+        assert_eq!(load_config.security_cookie, 0x1_3B44_3408);
+        assert!(load_config.se_handlers.is_empty());
+        assert!(load_config.guard_cf_functions.is_empty());
+        assert_eq!(load_config.get_guard_flag_names(), ["CF_INSTRUMENTED"]);
+    }
+
+    #[test]
+    fn test_corrupt_chpe_metadata() {
+        // CHPEMetadataPointer 指向 .reloc 节开头，文件在元数据读完之前结束
+        let original = std::fs::read("test_pe/steam_api64.dll").unwrap();
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api64.dll")).unwrap();
+        let image_base = file_info.nt_head.get_image_base();
+        let load_config_fo = file_info.get_load_config().unwrap().unwrap().file_offset as usize;
+        let pointer_fo =
+            load_config_fo + offset_of!(ImageLoadConfigDirectory64, chpe_metadata_pointer);
+        let mut data = original.clone();
+        data[pointer_fo..pointer_fo + 8].copy_from_slice(&(image_base + 0x4B000).to_le_bytes());
+        data.truncate(0x45600 + 8);
+        let path = std::env::temp_dir().join("penguin_load_config_chpe.dll");
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        let load_config = file_info.get_load_config();
+        std::fs::remove_file(&path).unwrap();
+
+        let load_config = load_config.unwrap().unwrap();
+        assert_eq!(load_config.chpe_metadata_pointer, image_base + 0x4B000);
+        assert!(load_config.chpe_metadata.is_none());
+        assert_eq!(load_config.security_cookie, 0x1_3B44_3408);
+        let anomalies = file_info.anomalies.borrow();
+        let anomaly = anomalies
+            .iter()
+            .find(|anomaly| anomaly.field == "CHPEMetadataPointer")
+            .unwrap();
+        assert_eq!(anomaly.offset, pointer_fo as u64);
+    }
+}
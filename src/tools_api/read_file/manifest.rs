@@ -0,0 +1,356 @@
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ImageSectionHeaders, ManifestDependency, ManifestInfo, ResourceTree, SupportedOs,
+};
+use std::fs::File;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+/// 防止畸形文件导致嵌套过深
+const MAX_ELEMENT_DEPTH: usize = 64;
+
+/// compatibility 中 supportedOS 的GUID
+const SUPPORTED_OS_NAMES: [(&str, &str); 5] = [
+    ("{e2011457-1546-43c5-a5fe-008deee3d3f0}", "Windows Vista"),
+    ("{35138b9a-5d96-4fbd-8e2d-a2440225f93a}", "Windows 7"),
+    ("{4a2f28e3-53b9-4441-ba9c-d69d4a4a6e38}", "Windows 8"),
+    ("{1f676c76-80e1-4239-95bb-83d0f6d0da78}", "Windows 8.1"),
+    ("{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}", "Windows 10/11"),
+];
+
+/// 清单中的一个元素，名称与属性名均去掉命名空间前缀
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    parent: Option<usize>,
+}
+
+impl XmlElement {
+    fn get_attribute(&self, name: &str) -> String {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 解析标签内容 `name attr="value" ...`
+fn parse_tag(tag: &str) -> (String, Vec<(String, String)>) {
+    let tag = tag.trim_end_matches('/');
+    let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+    let mut attributes = Vec::new();
+    let mut rest = &tag[name_end..];
+    while let Some(eq) = rest.find('=') {
+        let key = local_name(rest[..eq].trim()).to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(value_end) = after[1..].find(quote) else {
+            break;
+        };
+        attributes.push((key, decode_entities(&after[1..value_end + 1])));
+        rest = &after[value_end + 2..];
+    }
+    (local_name(&tag[..name_end]).to_string(), attributes)
+}
+
+/// 标签结尾的 `>` 位置，属性值中的 `>` 不算
+fn find_tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 清单结构简单，只需按顺序扫描标签并记录父子关系
+fn parse_xml(xml: &str) -> Vec<XmlElement> {
+    let mut elements: Vec<XmlElement> = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+    // 超过深度限制而没有入栈的元素数，它们的结束标签不能弹出栈中的元素
+    let mut skipped_depth = 0usize;
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        if let Some(&current) = stack.last() {
+            let text = decode_entities(rest[..start].trim());
+            elements[current].text.push_str(&text);
+        }
+        rest = &rest[start..];
+        let (terminator, skip) = if rest.starts_with("<!--") {
+            ("-->", true)
+        } else if rest.starts_with("<![CDATA[") {
+            ("]]>", false)
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            (">", true)
+        } else {
+            (">", false)
+        };
+        let end = if terminator == ">" {
+            find_tag_end(rest)
+        } else {
+            rest.find(terminator)
+        };
+        let Some(end) = end else {
+            break;
+        };
+        let content = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+        if skip {
+            continue;
+        }
+        if let Some(cdata) = content.strip_prefix("![CDATA[") {
+            if let Some(&current) = stack.last() {
+                elements[current].text.push_str(cdata.trim());
+            }
+        } else if let Some(name) = content.strip_prefix('/') {
+            if skipped_depth > 0 {
+                skipped_depth -= 1;
+                continue;
+            }
+            // 只关闭同名的元素，中间未闭合的子元素一并结束，找不到同名元素时忽略
+            let name = local_name(name.trim());
+            if let Some(position) = stack
+                .iter()
+                .rposition(|&index| elements[index].name == name)
+            {
+                stack.truncate(position);
+            }
+        } else {
+            let (name, attributes) = parse_tag(content);
+            elements.push(XmlElement {
+                name,
+                attributes,
+                text: String::new(),
+                parent: stack.last().copied(),
+            });
+            if content.ends_with('/') {
+                continue;
+            }
+            if stack.len() < MAX_ELEMENT_DEPTH {
+                stack.push(elements.len() - 1);
+            } else {
+                skipped_depth += 1;
+            }
+        }
+    }
+    elements
+}
+
+/// 清单一般为UTF-8，少数以UTF-16保存
+fn decode_manifest(data: &[u8]) -> String {
+    if let Some(data) = data.strip_prefix(UTF16_LE_BOM) {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let data = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+    String::from_utf8_lossy(data)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn get_supported_os_name(id: &str) -> String {
+    SUPPORTED_OS_NAMES
+        .iter()
+        .find(|(guid, _)| guid.eq_ignore_ascii_case(id.trim()))
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+impl ManifestInfo {
+    /// 读取第一个 RT_MANIFEST 资源，文件不含清单时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Option<ManifestInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let resource_rva = data_dir.get_resource_directory_address()?;
+        if resource_rva == 0 {
+            return Ok(None);
+        }
+        let resource_tree = ResourceTree::get_resource_tree(
+            file,
+            resource_rva,
+            nt_head,
+            section_headers,
+            data_dir,
+        )?;
+        let Some((resource_name, entry)) = resource_tree
+            .get_type_entries("RT_MANIFEST")
+            .first()
+            .copied()
+        else {
+            return Ok(None);
+        };
+        let data = entry.read_data(file)?;
+        Ok(Some(Self::parse(resource_name, &decode_manifest(&data))))
+    }
+
+    fn parse(resource_name: &str, xml: &str) -> ManifestInfo {
+        let elements = parse_xml(xml);
+        let find = |name: &str| elements.iter().find(|element| element.name == name);
+        let mut manifest = ManifestInfo {
+            resource_name: resource_name.to_string(),
+            xml: xml.to_string(),
+            ..Default::default()
+        };
+        if let Some(level) = find("requestedExecutionLevel") {
+            manifest.requested_execution_level = level.get_attribute("level");
+            manifest.ui_access = level.get_attribute("uiAccess");
+        }
+        if let Some(dpi_aware) = find("dpiAware") {
+            manifest.dpi_aware = dpi_aware.text.clone();
+        }
+        if let Some(dpi_awareness) = find("dpiAwareness") {
+            manifest.dpi_awareness = dpi_awareness.text.clone();
+        }
+        for element in &elements {
+            match element.name.as_str() {
+                "assemblyIdentity"
+                    if element
+                        .parent
+                        .is_some_and(|parent| elements[parent].name == "dependentAssembly") =>
+                {
+                    manifest.dependencies.push(ManifestDependency {
+                        name: element.get_attribute("name"),
+                        version: element.get_attribute("version"),
+                        assembly_type: element.get_attribute("type"),
+                        processor_architecture: element.get_attribute("processorArchitecture"),
+                        public_key_token: element.get_attribute("publicKeyToken"),
+                        language: element.get_attribute("language"),
+                    });
+                }
+                "supportedOS" => {
+                    let id = element.get_attribute("Id");
+                    manifest.supported_os.push(SupportedOs {
+                        name: get_supported_os_name(&id),
+                        id,
+                    });
+                }
+                _ => {}
+            }
+        }
+        manifest
+    }
+
+    /// requireAdministrator 与 highestAvailable 会在启动时请求提升权限
+    pub fn requires_elevation(&self) -> bool {
+        matches!(
+            self.requested_execution_level.as_str(),
+            "requireAdministrator" | "highestAvailable"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <!-- <dependency> in a comment -->
+  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel level="asInvoker" uiAccess="false"></requestedExecutionLevel>
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+  <dependency>
+    <dependentAssembly>
+      <assemblyIdentity type="win32" name="Microsoft.Windows.Common-Controls" version="6.0.0.0" processorArchitecture="*" publicKeyToken="6595b64144ccf1df" language="*"/>
+    </dependentAssembly>
+  </dependency>
+  <compatibility xmlns="urn:schemas-microsoft-com:compatibility.v1">
+    <application>
+      <supportedOS Id="{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}"/>
+    </application>
+  </compatibility>
+  <asmv3:application xmlns:asmv3="urn:schemas-microsoft-com:asm.v3">
+    <asmv3:windowsSettings>
+      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true/pm</dpiAware>
+    </asmv3:windowsSettings>
+  </asmv3:application>
+</assembly>"#;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = ManifestInfo::parse("ID_1", MANIFEST);
+        assert_eq!(manifest.requested_execution_level, "asInvoker");
+        assert_eq!(manifest.ui_access, "false");
+        assert!(!manifest.requires_elevation());
+        assert_eq!(manifest.dpi_aware, "true/pm");
+        assert_eq!(manifest.dependencies.len(), 1);
+        assert_eq!(
+            manifest.dependencies[0].name,
+            "Microsoft.Windows.Common-Controls"
+        );
+        assert_eq!(
+            manifest.dependencies[0].public_key_token,
+            "6595b64144ccf1df"
+        );
+        assert_eq!(manifest.supported_os.len(), 1);
+        assert_eq!(manifest.supported_os[0].name, "Windows 10/11");
+    }
+
+    #[test]
+    fn test_quoted_greater_than() {
+        let elements = parse_xml(r#"<a title="x > y" name='1>2'><b/></a>"#);
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].get_attribute("title"), "x > y");
+        assert_eq!(elements[0].get_attribute("name"), "1>2");
+        assert_eq!(elements[1].name, "b");
+        assert_eq!(elements[1].parent, Some(0));
+    }
+
+    #[test]
+    fn test_mismatched_closing_tags() {
+        // 多余的结束标签被忽略，缺少结束标签的子元素随父元素一起结束
+        let elements = parse_xml("<a><b></c><d></b><e/></a><f/>");
+        let names: Vec<&str> = elements.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "d", "e", "f"]);
+        assert_eq!(elements[2].parent, Some(1));
+        assert_eq!(elements[3].parent, Some(0));
+        assert_eq!(elements[4].parent, None);
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let depth = MAX_ELEMENT_DEPTH + 4;
+        let xml = format!(
+            "<root>{}{}<sibling/></root>",
+            "<a>".repeat(depth),
+            "</a>".repeat(depth)
+        );
+        let elements = parse_xml(&xml);
+        let sibling = elements.last().unwrap();
+        assert_eq!(sibling.name, "sibling");
+        assert_eq!(sibling.parent, Some(0));
+    }
+}
@@ -2,8 +2,8 @@ use crate::i18n;
 use crate::tools_api::read_file::SerializableNtHeaders;
 use crate::tools_api::read_file::nt_header::traits::NtHeaders;
 use crate::tools_api::read_file::{
-    DataDirectory, ImageDataDirectory, ImageDosHeader, ImageFileHeader, ImageNtHeaders,
-    ImageNtHeaders64,
+    DataDirectory, ImageCoffHeader, ImageDataDirectory, ImageDosHeader, ImageFileHeader,
+    ImageNtHeaders, ImageNtHeaders64,
 };
 
 use std::fmt::Display;
@@ -12,14 +12,29 @@ use std::io::SeekFrom;
 use std::io::{Read, Seek};
 use std::mem::{MaybeUninit, size_of};
 
-const DIRECTORY_EXPORT: usize = 0;
-const DIRECTORY_IMPORT: usize = 1;
-const DIRECTORY_RESOURCE: usize = 2;
-pub(crate) const MACHINE_32: [u16; 21] = [
-    0x014C, 0x162, 0x166, 0x168, 0x169, 0x184, 0x1a2, 0x1a3, 0x1a4, 0x1a6, 0x1a8, 0x1c0, 0x1c2,
-    0x1c4, 0x1d3, 0x1f0, 0x1f1, 0x266, 0x366, 0x466, 0x520,
+pub(crate) const DIRECTORY_EXPORT: usize = 0;
+pub(crate) const DIRECTORY_IMPORT: usize = 1;
+pub(crate) const DIRECTORY_RESOURCE: usize = 2;
+pub(crate) const DIRECTORY_EXCEPTION: usize = 3;
+pub(crate) const DIRECTORY_SECURITY: usize = 4;
+pub(crate) const DIRECTORY_BASERELOC: usize = 5;
+pub(crate) const DIRECTORY_DEBUG: usize = 6;
+pub(crate) const DIRECTORY_TLS: usize = 9;
+pub(crate) const DIRECTORY_LOAD_CONFIG: usize = 10;
+pub(crate) const DIRECTORY_BOUND_IMPORT: usize = 11;
+pub(crate) const DIRECTORY_DELAY_IMPORT: usize = 13;
+pub(crate) const DIRECTORY_COM_DESCRIPTOR: usize = 14;
+pub(crate) const NUMBER_OF_DIRECTORY_ENTRIES: usize = 16;
+/// 已知的32位机器类型，映像的位数由可选头 Magic 决定，这里只用于识别目标文件
+pub(crate) const MACHINE_32: [u16; 30] = [
+    0x014C, 0x160, 0x162, 0x166, 0x168, 0x169, 0x184, 0x1a2, 0x1a3, 0x1a4, 0x1a6, 0x1a8, 0x1c0,
+    0x1c2, 0x1c4, 0x1d3, 0x1f0, 0x1f1, 0x1f2, 0x266, 0x366, 0x466, 0x520, 0x0CEF, 0x0EBC, 0x3A64,
+    0x5032, 0x6232, 0x9041, 0xC0EE,
+];
+/// 已知的64位机器类型
+pub(crate) const MACHINE_64: [u16; 9] = [
+    0x8664, 0x0200, 0x0284, 0xAA64, 0xA641, 0xA64E, 0x5064, 0x5128, 0x6264,
 ];
-pub(crate) const MACHINE_64: [u16; 4] = [0x8664, 0x0200, 0x0284, 0xAA64];
 
 pub(crate) enum Characteristics {
     ImageFileRelocsStripped = 0x0001,       // 重定位信息被剥离
@@ -208,33 +223,47 @@ fn get_dll_characteristics_description(dll_characteristics: u16) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
-fn get_machine_descriptions(machine: u16) -> &'static str {
+pub(crate) fn get_machine_descriptions(machine: u16) -> &'static str {
     match machine {
         0x14c => i18n::MACHINE_X86_32,
-        0x0162 => i18n::MACHINE_MIPS_BIG_ENDIAN,
+        0x0160 => i18n::MACHINE_MIPS_BIG_ENDIAN,
+        0x0162 => i18n::MACHINE_MIPS_LITTLE_ENDIAN,
         0x0166 => i18n::MACHINE_MIPS_LITTLE_ENDIAN,
         0x0168 => i18n::MACHINE_MIPS_LITTLE_ENDIAN,
         0x0169 => i18n::MACHINE_MIPS_LITTLE_ENDIAN,
         0x0184 => i18n::MACHINE_ALPHA,
         0x01a2 => i18n::MACHINE_SH3_LITTLE_ENDIAN,
-        0x01a3 => i18n::MACHINE_SH3_LITTLE_ENDIAN,
+        0x01a3 => i18n::MACHINE_SH3_DSP,
         0x01a4 => i18n::MACHINE_SH3E_LITTLE_ENDIAN,
         0x01a6 => i18n::MACHINE_SH4_LITTLE_ENDIAN,
         0x01a8 => i18n::MACHINE_SH5,
         0x01c0 => i18n::MACHINE_ARM_LITTLE_ENDIAN,
         0x01c2 => i18n::MACHINE_ARM_THUMB_LITTLE_ENDIAN,
         0x01c4 => i18n::MACHINE_ARM_THUMB_LITTLE_ENDIAN,
-        0x01d3 => i18n::MACHINE_ARM,
+        0x01d3 => i18n::MACHINE_AM33,
         0x01F0 => i18n::MACHINE_IBM,
         0x01f1 => i18n::MACHINE_POWERCFP,
+        0x01f2 => i18n::MACHINE_POWERPC_BIG_ENDIAN,
         0x0200 => i18n::MACHINE_INTEL_64,
         0x0266 => i18n::MACHINE_MIPS,
         0x0284 => i18n::MACHINE_ALPHA64,
         0x0366 => i18n::MACHINE_MIPS,
         0x0466 => i18n::MACHINE_MIPS,
         0x0520 => i18n::MACHINE_INFINEON,
+        0x0CEF => i18n::MACHINE_CEF,
+        0x0EBC => i18n::MACHINE_EBC,
+        0x3A64 => i18n::MACHINE_CHPE_X86,
+        0x5032 => i18n::MACHINE_RISCV32,
+        0x5064 => i18n::MACHINE_RISCV64,
+        0x5128 => i18n::MACHINE_RISCV128,
+        0x6232 => i18n::MACHINE_LOONGARCH32,
+        0x6264 => i18n::MACHINE_LOONGARCH64,
         0x8664 => i18n::MACHINE_X64_64,
+        0x9041 => i18n::MACHINE_M32R,
+        0xA641 => i18n::MACHINE_ARM64EC,
+        0xA64E => i18n::MACHINE_ARM64X,
         0xAA64 => i18n::MACHINE_ARM64_LITTLE_ENDIAN,
+        0xC0EE => i18n::MACHINE_CEE,
         _ => i18n::MACHINE_UNKNOWN,
     }
 }
@@ -249,7 +278,17 @@ pub mod traits {
         /// 读取段数量
         fn section_number(&self) -> u16;
         /// 读取段开始文件地址
-        fn section_start(&self, nt_start: u16) -> u32;
+        fn section_start(&self, nt_start: u32) -> u32;
+        /// 读取COFF符号表的文件偏移与符号数量
+        fn symbol_table(&self) -> (u32, u32);
+        /// 机器类型原始值
+        fn machine(&self) -> u16;
+        /// 可选头大小原始值，节表紧跟在可选头之后
+        fn size_of_optional_header(&self) -> u16;
+        /// 映像大小原始值，目标文件为0
+        fn size_of_image(&self) -> u32;
+        /// 头部大小原始值，目标文件为0
+        fn size_of_headers(&self) -> u32;
         fn get_signature(&self) -> &str;
         fn get_machine(&self) -> &str;
         fn get_number_of_sections(&self) -> String;
@@ -301,8 +340,8 @@ impl ImageFileHeader {
         file: &mut File,
         image_dos_header: &ImageDosHeader,
     ) -> anyhow::Result<ImageFileHeader> {
-        let file_image_addr = image_dos_header.get_nt_addr() + 4u16;
-        file.seek(SeekFrom::Start(file_image_addr as u64))?;
+        let file_image_addr = image_dos_header.get_nt_addr() as u64 + 4;
+        file.seek(SeekFrom::Start(file_image_addr))?;
         unsafe {
             let mut image_file_header = MaybeUninit::<ImageFileHeader>::uninit();
             let bytes = std::slice::from_raw_parts_mut(
@@ -316,6 +355,13 @@ impl ImageFileHeader {
 }
 
 impl DataDirectory {
+    /// 目标文件没有数据目录，使用全0的目录项，使各目录都按不存在处理
+    pub(crate) fn new_empty() -> Self {
+        DataDirectory(vec![
+            ImageDataDirectory::default();
+            NUMBER_OF_DIRECTORY_ENTRIES
+        ])
+    }
     /// 去除async
     /// 添加data_directory内容
     pub(crate) fn add(&mut self, data: ImageDataDirectory) {
@@ -324,36 +370,45 @@ impl DataDirectory {
     pub(crate) fn get_export_directory_address(&self) -> anyhow::Result<u32> {
         Ok(self
             .0
-            .get(crate::tools_api::read_file::nt_header::DIRECTORY_EXPORT)
-            .unwrap()
-            .virtual_address)
+            .get(DIRECTORY_EXPORT)
+            .map(|dir| dir.virtual_address)
+            .unwrap_or(0))
     }
     pub(crate) fn get_import_directory_address(&self) -> anyhow::Result<u32> {
         Ok(self
             .0
-            .get(crate::tools_api::read_file::nt_header::DIRECTORY_IMPORT)
-            .unwrap()
-            .virtual_address)
+            .get(DIRECTORY_IMPORT)
+            .map(|dir| dir.virtual_address)
+            .unwrap_or(0))
     }
     pub(crate) fn get_resource_directory_address(&self) -> anyhow::Result<u32> {
         Ok(self
             .0
-            .get(crate::tools_api::read_file::nt_header::DIRECTORY_RESOURCE)
-            .unwrap()
-            .virtual_address)
+            .get(DIRECTORY_RESOURCE)
+            .map(|dir| dir.virtual_address)
+            .unwrap_or(0))
     }
     pub(crate) fn get_import_directory_size(&self) -> anyhow::Result<u32> {
-        Ok(self.0.get(DIRECTORY_IMPORT).unwrap().size)
+        Ok(self
+            .0
+            .get(DIRECTORY_IMPORT)
+            .map(|dir| dir.size)
+            .unwrap_or(0))
     }
     /// 获取导入dll数量
     pub fn get_import_directory_num(&self) -> anyhow::Result<usize> {
         Ok((self.get_import_directory_size()? / 0x14) as usize)
     }
-    pub fn get_data_directory_size(&self, index: u32) -> anyhow::Result<u32> {
-        Ok(self.0.get(index as usize).unwrap().size)
+    /// 文件可能不包含该目录（NumberOfRvaAndSizes 较小），此时视为空目录
+    pub fn get_data_directory_size(&self, index: usize) -> u32 {
+        self.0.get(index).map(|dir| dir.size).unwrap_or(0)
     }
-    pub fn get_data_directory_virtual_address(&self, index: u32) -> anyhow::Result<u32> {
-        Ok(self.0.get(index as usize).unwrap().virtual_address)
+    /// 安全目录中保存的是文件偏移而不是RVA
+    pub fn get_data_directory_virtual_address(&self, index: usize) -> u32 {
+        self.0
+            .get(index)
+            .map(|dir| dir.virtual_address)
+            .unwrap_or(0)
     }
 }
 impl Display for ImageNtHeaders {
@@ -417,8 +472,30 @@ impl NtHeaders for ImageNtHeaders {
     fn section_number(&self) -> u16 {
         self.file_header.number_of_sections
     }
-    fn section_start(&self, nt_start: u16) -> u32 {
-        self.num_of_rva() * 0x8 + nt_start as u32 + size_of::<ImageNtHeaders>() as u32
+    fn symbol_table(&self) -> (u32, u32) {
+        (
+            self.file_header.pointer_to_symbol_table,
+            self.file_header.number_of_symbols,
+        )
+    }
+    fn machine(&self) -> u16 {
+        self.file_header.machine
+    }
+    fn size_of_optional_header(&self) -> u16 {
+        self.file_header.size_of_optional_header
+    }
+    fn size_of_image(&self) -> u32 {
+        self.optional_header.size_of_image
+    }
+    fn size_of_headers(&self) -> u32 {
+        self.optional_header.size_of_headers
+    }
+    /// 加载器按 SizeOfOptionalHeader 定位节表，而不是数据目录的数量
+    fn section_start(&self, nt_start: u32) -> u32 {
+        nt_start.saturating_add(
+            4 + size_of::<ImageFileHeader>() as u32
+                + self.file_header.size_of_optional_header as u32,
+        )
     }
 
     fn get_signature(&self) -> &str {
@@ -600,8 +677,30 @@ impl NtHeaders for ImageNtHeaders64 {
     fn section_number(&self) -> u16 {
         self.file_header.number_of_sections
     }
-    fn section_start(&self, nt_start: u16) -> u32 {
-        self.num_of_rva() * 0x8 + nt_start as u32 + size_of::<ImageNtHeaders64>() as u32
+    fn symbol_table(&self) -> (u32, u32) {
+        (
+            self.file_header.pointer_to_symbol_table,
+            self.file_header.number_of_symbols,
+        )
+    }
+    fn machine(&self) -> u16 {
+        self.file_header.machine
+    }
+    fn size_of_optional_header(&self) -> u16 {
+        self.file_header.size_of_optional_header
+    }
+    fn size_of_image(&self) -> u32 {
+        self.optional_header.size_of_image
+    }
+    fn size_of_headers(&self) -> u32 {
+        self.optional_header.size_of_headers
+    }
+    /// 加载器按 SizeOfOptionalHeader 定位节表，而不是数据目录的数量
+    fn section_start(&self, nt_start: u32) -> u32 {
+        nt_start.saturating_add(
+            4 + size_of::<ImageFileHeader>() as u32
+                + self.file_header.size_of_optional_header as u32,
+        )
     }
     fn get_signature(&self) -> &str {
         "PE\0\0"
@@ -774,9 +873,191 @@ impl NtHeaders for ImageNtHeaders64 {
     }
 }
 
+impl Display for ImageCoffHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CoffHeader {{
+            machine: {:?},
+            number_of_sections: {:?},
+            time_date_stamp: {:?},
+            pointer_to_symbol_table: {:?},
+            number_of_symbols: {:?},
+            size_of_optional_header: {:?},
+            characteristics: {:?}
+        }}",
+            self.get_machine(),
+            self.get_number_of_sections(),
+            self.get_time_date_stamp(),
+            self.get_pointer_to_symbol_table(),
+            self.get_number_of_symbols(),
+            self.get_size_of_optional_header(),
+            self.get_characteristics()
+        )
+    }
+}
+/// 目标文件只有文件头，可选头中的字段都显示为空
+impl NtHeaders for ImageCoffHeader {
+    fn num_of_rva(&self) -> u32 {
+        0
+    }
+    fn section_number(&self) -> u16 {
+        self.file_header.number_of_sections
+    }
+    fn symbol_table(&self) -> (u32, u32) {
+        (
+            self.file_header.pointer_to_symbol_table,
+            self.file_header.number_of_symbols,
+        )
+    }
+    fn machine(&self) -> u16 {
+        self.file_header.machine
+    }
+    fn size_of_optional_header(&self) -> u16 {
+        self.file_header.size_of_optional_header
+    }
+    fn size_of_image(&self) -> u32 {
+        0
+    }
+    fn size_of_headers(&self) -> u32 {
+        0
+    }
+    fn section_start(&self, nt_start: u32) -> u32 {
+        nt_start.saturating_add(
+            size_of::<ImageFileHeader>() as u32 + self.file_header.size_of_optional_header as u32,
+        )
+    }
+    fn get_signature(&self) -> &str {
+        ""
+    }
+    fn get_machine(&self) -> &str {
+        get_machine_descriptions(self.file_header.machine)
+    }
+    fn get_number_of_sections(&self) -> String {
+        format!("{}", self.file_header.number_of_sections)
+    }
+    fn get_time_date_stamp(&self) -> String {
+        format!("{}", self.file_header.time_date_stamp)
+    }
+    fn get_pointer_to_symbol_table(&self) -> String {
+        format!("{}", self.file_header.pointer_to_symbol_table)
+    }
+    fn get_number_of_symbols(&self) -> String {
+        format!("{}", self.file_header.number_of_symbols)
+    }
+    fn get_size_of_optional_header(&self) -> String {
+        format!("{}", self.file_header.size_of_optional_header)
+    }
+    fn get_characteristics(&self) -> String {
+        format!("0x{:X}", self.file_header.characteristics)
+    }
+    fn get_characteristics_hover(&self) -> String {
+        get_characteristics_descriptions(self.file_header.characteristics)
+    }
+    fn get_magic(&self) -> u16 {
+        0
+    }
+    fn get_magic_hover(&self) -> String {
+        String::from("COFF")
+    }
+    fn get_major_linker_version(&self) -> String {
+        String::new()
+    }
+    fn get_long_minor_linker_version(&self) -> String {
+        String::new()
+    }
+    fn get_size_of_code(&self) -> String {
+        String::new()
+    }
+    fn get_size_of_initialized_data(&self) -> String {
+        String::new()
+    }
+    fn get_size_of_uninitialized_data(&self) -> String {
+        String::new()
+    }
+    fn get_address_of_entry_point(&self) -> u32 {
+        0
+    }
+    fn get_base_of_code(&self) -> u32 {
+        0
+    }
+    fn get_base_of_data(&self) -> u64 {
+        0
+    }
+    fn get_image_base(&self) -> u64 {
+        0
+    }
+    fn get_section_alignment(&self) -> u32 {
+        0
+    }
+    fn get_file_alignment(&self) -> u32 {
+        0
+    }
+    fn get_major_os_version(&self) -> String {
+        String::new()
+    }
+    fn get_minor_os_version(&self) -> String {
+        String::new()
+    }
+    fn get_major_image_version(&self) -> String {
+        String::new()
+    }
+    fn get_minor_image_version(&self) -> String {
+        String::new()
+    }
+    fn get_major_subsystem_version(&self) -> String {
+        String::new()
+    }
+    fn get_minor_subsystem_version(&self) -> String {
+        String::new()
+    }
+    fn get_win32_version_value(&self) -> String {
+        String::new()
+    }
+    fn get_size_of_image(&self) -> String {
+        String::new()
+    }
+    fn get_size_of_headers(&self) -> String {
+        String::new()
+    }
+    fn get_checksum(&self) -> String {
+        String::new()
+    }
+    fn get_subsystem(&self) -> String {
+        String::new()
+    }
+    fn get_dll_characteristics(&self) -> String {
+        String::new()
+    }
+    fn get_dll_characteristics_hover(&self) -> String {
+        String::new()
+    }
+    fn get_size_of_stack_reserve(&self) -> u64 {
+        0
+    }
+    fn get_size_of_stack_commit(&self) -> u64 {
+        0
+    }
+    fn get_size_of_heap_reserve(&self) -> u64 {
+        0
+    }
+    fn get_size_of_heap_commit(&self) -> u64 {
+        0
+    }
+    fn get_loader_flags(&self) -> u32 {
+        0
+    }
+    fn get_number_of_rva_and_sizes(&self) -> u32 {
+        0
+    }
+    fn serde_serialize(&self) -> SerializableNtHeaders {
+        SerializableNtHeaders::CoffObject(self.clone())
+    }
+}
+
 pub(crate) fn read_nt_head<T>(
     file: &mut File,
-    start_addr: u16,
+    start_addr: u32,
 ) -> anyhow::Result<(T, DataDirectory)>
 where
     T: NtHeaders + Default,
@@ -791,15 +1072,18 @@ where
         nt_head.assume_init()
     };
 
+    // 加载器最多使用16个目录，多出的部分忽略；目录被截断时只保留完整读取的项
     let mut data_dictionary = DataDirectory(Vec::new());
-    for _ in 0..nt_head.num_of_rva() {
+    for _ in 0..(nt_head.num_of_rva() as usize).min(NUMBER_OF_DIRECTORY_ENTRIES) {
         let image_data = unsafe {
             let mut image_data = MaybeUninit::<ImageDataDirectory>::uninit();
             let bytes = std::slice::from_raw_parts_mut(
                 image_data.as_mut_ptr() as *mut u8,
                 size_of::<ImageDataDirectory>(),
             );
-            file.read_exact(bytes)?;
+            if file.read_exact(bytes).is_err() {
+                break;
+            }
             image_data.assume_init()
         };
         data_dictionary.add(image_data);
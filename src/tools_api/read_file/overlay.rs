@@ -0,0 +1,213 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_SECURITY;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ImageSectionHeaders, OverlayInfo, OverlayRegion, ResourceTree,
+};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const READ_CHUNK_SIZE: usize = 0x10000;
+/// 识别文件类型时读取的文件头长度
+const FILE_TYPE_HEADER_SIZE: u64 = 0x100;
+/// 证书表按8字节对齐，之前不足8字节的填充不算附加数据
+const CERTIFICATE_ALIGNMENT: u64 = 8;
+
+/// 计算文件中一段数据的香农熵
+fn calc_entropy(file: &mut File, offset: u64, size: u64) -> anyhow::Result<f64> {
+    let mut counts = [0u64; 256];
+    file.seek(SeekFrom::Start(offset))?;
+    let mut reader = file.take(size);
+    let mut buf = vec![0u8; READ_CHUNK_SIZE];
+    loop {
+        let length = reader.read(&mut buf)?;
+        if length == 0 {
+            break;
+        }
+        buf[..length].iter().for_each(|&b| counts[b as usize] += 1);
+    }
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        return Ok(0.0);
+    }
+    Ok(counts
+        .iter()
+        .filter(|&&count| count != 0)
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            p * (1.0 / p).log2()
+        })
+        .sum())
+}
+
+impl OverlayRegion {
+    fn new(file: &mut File, offset: u64, size: u64) -> anyhow::Result<Self> {
+        let mut header = Vec::new();
+        file.seek(SeekFrom::Start(offset))?;
+        file.take(size.min(FILE_TYPE_HEADER_SIZE))
+            .read_to_end(&mut header)?;
+        Ok(OverlayRegion {
+            offset,
+            size,
+            entropy: calc_entropy(file, offset, size)?,
+            file_type: ResourceTree::detect_file_type(&header).map(str::to_string),
+        })
+    }
+
+    /// 将该段附加数据写入文件
+    pub fn extract(&self, file: &mut File, output_path: &Path) -> anyhow::Result<()> {
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut output = File::create(output_path)?;
+        io::copy(&mut file.take(self.size), &mut output)?;
+        Ok(())
+    }
+}
+
+impl OverlayInfo {
+    /// 计算映像在文件中的结束位置，之后除证书表以外的数据都是附加数据，不存在时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Option<OverlayInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let file_size = file.metadata()?.len();
+        let headers_end = nt_head.get_size_of_headers().parse::<u64>().unwrap_or(0);
+        let image_end = section_headers
+            .0
+            .iter()
+            .filter(|section| section.size_of_raw_data != 0)
+            .map(|section| section.pointer_to_raw_data as u64 + section.size_of_raw_data as u64)
+            .max()
+            .unwrap_or(0)
+            .max(headers_end);
+        // 证书表使用文件偏移，不会被映射到内存中，需要单独排除
+        let certificate_offset =
+            data_dir.get_data_directory_virtual_address(DIRECTORY_SECURITY) as u64;
+        let certificate_size = data_dir.get_data_directory_size(DIRECTORY_SECURITY) as u64;
+        let ranges = if certificate_offset == 0 || certificate_size == 0 {
+            vec![(image_end, file_size)]
+        } else {
+            let before_end = certificate_offset.min(file_size);
+            let before_start = if before_end.saturating_sub(image_end) < CERTIFICATE_ALIGNMENT {
+                before_end
+            } else {
+                image_end
+            };
+            vec![
+                (before_start, before_end),
+                (
+                    (certificate_offset + certificate_size).max(image_end),
+                    file_size,
+                ),
+            ]
+        };
+        let mut regions = Vec::new();
+        for (start, end) in ranges {
+            if end > start {
+                regions.push(OverlayRegion::new(file, start, end - start)?);
+            }
+        }
+        if regions.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(OverlayInfo {
+            image_end,
+            certificate_offset,
+            certificate_size,
+            regions,
+        }))
+    }
+
+    /// 所有附加数据的总大小
+    pub fn get_total_size(&self) -> u64 {
+        self.regions.iter().map(|region| region.size).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::OverlayInfo;
+    use std::path::PathBuf;
+
+    /// steam_api.dll 的映像在0x3E000处结束，证书表紧随其后直到文件末尾
+    const IMAGE_END: u64 = 0x3E000;
+    const CERTIFICATE_SIZE: u64 = 0x23A8;
+    /// .reloc 节头中 SizeOfRawData 字段的文件偏移
+    const RELOC_RAW_SIZE_FO: usize = 0x2B0;
+
+    fn overlay_of_patched(name: &str, patch: impl FnOnce(&mut Vec<u8>)) -> Option<OverlayInfo> {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        patch(&mut data);
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        let overlay = file_info.get_overlay().unwrap();
+        drop(file_info);
+        std::fs::remove_file(&path).unwrap();
+        overlay
+    }
+
+    /// 缩短 .reloc 节的原始数据，使映像结束位置与证书表之间留出 `gap` 字节
+    fn shrink_last_section(data: &mut [u8], gap: u32) {
+        let field = &mut data[RELOC_RAW_SIZE_FO..RELOC_RAW_SIZE_FO + 4];
+        let size = u32::from_le_bytes(field.try_into().unwrap());
+        field.copy_from_slice(&(size - gap).to_le_bytes());
+    }
+
+    #[test]
+    fn test_no_overlay() {
+        for path in ["test_pe/steam_api.dll", "test_pe/steam_api64.dll"] {
+            let file_info = FileInfo::new(PathBuf::from(path)).unwrap();
+            assert_eq!(file_info.get_overlay().unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_overlay_after_certificate() {
+        let overlay = overlay_of_patched("penguin_overlay_appended.dll", |data| {
+            data.extend_from_slice(b"PK\x03\x04");
+            data.extend_from_slice(&[0u8; 28]);
+        })
+        .unwrap();
+        assert_eq!(overlay.image_end, IMAGE_END);
+        assert_eq!(overlay.certificate_offset, IMAGE_END);
+        assert_eq!(overlay.certificate_size, CERTIFICATE_SIZE);
+        // 证书表之前没有数据，只有证书表之后的一段
+        assert_eq!(overlay.regions.len(), 1);
+        let region = &overlay.regions[0];
+        assert_eq!(region.offset, IMAGE_END + CERTIFICATE_SIZE);
+        assert_eq!(region.size, 32);
+        assert_eq!(region.file_type.as_deref(), Some(".zip"));
+        assert!(region.entropy > 0.0 && region.entropy < 2.0);
+        assert_eq!(overlay.get_total_size(), 32);
+    }
+
+    #[test]
+    fn test_certificate_alignment_padding() {
+        // 映像结束位置按8字节对齐到证书表时的填充不算附加数据
+        let overlay = overlay_of_patched("penguin_overlay_padding.dll", |data| {
+            shrink_last_section(data, 4);
+        });
+        assert_eq!(overlay, None);
+    }
+
+    #[test]
+    fn test_overlay_before_certificate() {
+        // 超过对齐填充的部分是位于证书表之前的附加数据
+        let overlay = overlay_of_patched("penguin_overlay_before_certificate.dll", |data| {
+            shrink_last_section(data, 8);
+        })
+        .unwrap();
+        assert_eq!(overlay.image_end, IMAGE_END - 8);
+        assert_eq!(overlay.regions.len(), 1);
+        assert_eq!(
+            (overlay.regions[0].offset, overlay.regions[0].size),
+            (IMAGE_END - 8, 8)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_BASERELOC;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ImageBaseRelocation, ImageSectionHeaders, RelocationBlock, RelocationEntry,
+    RelocationTable, rva_2_fo,
+};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+use std::rc::Rc;
+
+const IMAGE_REL_BASED_HIGHADJ: u8 = 4;
+
+impl ImageBaseRelocation {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut base_relocation = MaybeUninit::<ImageBaseRelocation>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                base_relocation.as_mut_ptr() as *mut u8,
+                size_of::<ImageBaseRelocation>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(base_relocation.assume_init())
+        }
+    }
+}
+
+impl RelocationEntry {
+    /// 重定位类型名称，同一个值在不同架构下含义不同，这里按最常见的解释
+    pub fn get_type_name(&self) -> &'static str {
+        match self.reloc_type {
+            0 => "ABSOLUTE",
+            1 => "HIGH",
+            2 => "LOW",
+            3 => "HIGHLOW",
+            4 => "HIGHADJ",
+            5 => "MIPS_JMPADDR/ARM_MOV32",
+            7 => "THUMB_MOV32",
+            8 => "RISCV_LOW12S",
+            9 => "MIPS_JMPADDR16",
+            10 => "DIR64",
+            _ => "RESERVED",
+        }
+    }
+}
+
+impl RelocationTable {
+    /// 遍历 IMAGE_DIRECTORY_ENTRY_BASERELOC 下的所有重定位块
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<RelocationTable>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut blocks = Vec::new();
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_BASERELOC);
+        let directory_size = data_dir.get_data_directory_size(DIRECTORY_BASERELOC);
+        let directory_fo = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) if directory_rva != 0 && directory_size != 0 => fo,
+            _ => return Ok(RelocationTable::default()),
+        };
+
+        let header_size = size_of::<ImageBaseRelocation>() as u32;
+        let mut offset: u32 = 0;
+        while directory_size - offset >= header_size {
+            let Some(block_fo) = directory_fo.checked_add(offset) else {
+                break;
+            };
+            let base_relocation = ImageBaseRelocation::new(file, block_fo)?;
+            // 块大小不足块头时无法继续前进，视为目录结束
+            if base_relocation.size_of_block < header_size {
+                break;
+            }
+            // 块大小来自文件本身，超出目录的部分截断，避免按畸形大小分配内存
+            let remaining = directory_size - offset;
+            let block_size = base_relocation.size_of_block.min(remaining);
+            let mut buf = Vec::new();
+            file.by_ref()
+                .take(((block_size - header_size) & !1) as u64)
+                .read_to_end(&mut buf)?;
+
+            let mut slots = buf
+                .chunks_exact(2)
+                .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]));
+            let mut entries = Vec::new();
+            while let Some(value) = slots.next() {
+                let reloc_type = (value >> 12) as u8;
+                // HIGHADJ 的下一个槽是它的参数，不是独立的重定位项
+                let parameter = if reloc_type == IMAGE_REL_BASED_HIGHADJ {
+                    slots.next()
+                } else {
+                    None
+                };
+                let offset = value & 0x0FFF;
+                let target_rva = base_relocation.virtual_address.wrapping_add(offset as u32);
+                entries.push(RelocationEntry {
+                    page_rva: base_relocation.virtual_address,
+                    reloc_type,
+                    offset,
+                    target_rva,
+                    file_offset: rva_2_fo(nt_head, section_headers, target_rva),
+                    parameter,
+                });
+            }
+
+            blocks.push(RelocationBlock {
+                page_rva: base_relocation.virtual_address,
+                size_of_block: base_relocation.size_of_block,
+                file_offset: block_fo,
+                section_name: section_headers
+                    .get_section_name_by_rva(base_relocation.virtual_address)
+                    .unwrap_or_default(),
+                entries,
+            });
+            // 块越过目录末尾时后面不会再有合法的块
+            if base_relocation.size_of_block > remaining {
+                break;
+            }
+            offset += base_relocation.size_of_block;
+        }
+        Ok(RelocationTable(Rc::new(RefCell::new(blocks))))
+    }
+
+    pub fn fclone(&self) -> Self {
+        RelocationTable(Rc::clone(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::RelocationBlock;
+    use std::path::PathBuf;
+
+    /// steam_api.dll 重定位目录所在文件偏移及其数据目录项中 Size 字段的偏移
+    const RELOC_FO: usize = 0x3C600;
+    const RELOC_SIZE_FO: usize = 0x1A8 + 4;
+
+    fn blocks_of(file_info: &FileInfo) -> Vec<RelocationBlock> {
+        file_info.get_relocations().unwrap().0.borrow().clone()
+    }
+
+    /// 修改 steam_api.dll 的副本后解析其重定位表
+    fn blocks_of_patched(name: &str, patch: impl FnOnce(&mut Vec<u8>)) -> Vec<RelocationBlock> {
+        let mut data = std::fs::read("test_pe/steam_api.dll").unwrap();
+        patch(&mut data);
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        let blocks = blocks_of(&file_info);
+        drop(file_info);
+        std::fs::remove_file(&path).unwrap();
+        blocks
+    }
+
+    #[test]
+    fn test_relocation_blocks_32() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        let blocks = blocks_of(&file_info);
+        assert_eq!(blocks.len(), 44);
+        assert_eq!(
+            blocks.iter().map(|block| block.size_of_block).sum::<u32>(),
+            0x1900
+        );
+        let first = &blocks[0];
+        assert_eq!(first.page_rva, 0x1000);
+        assert_eq!(first.size_of_block, 100);
+        assert_eq!(first.file_offset, RELOC_FO as u32);
+        assert_eq!(first.entries.len(), 46);
+        let head: Vec<_> = first.entries[..4]
+            .iter()
+            .map(|entry| (entry.reloc_type, entry.offset, entry.target_rva))
+            .collect();
+        assert_eq!(
+            head,
+            [
+                (3, 0x001, 0x1001),
+                (3, 0x007, 0x1007),
+                (3, 0x00C, 0x100C),
+                (3, 0x094, 0x1094)
+            ]
+        );
+        assert_eq!(first.entries[0].get_type_name(), "HIGHLOW");
+        assert!(first.entries[0].file_offset.is_some());
+        assert_eq!((blocks[1].page_rva, blocks[1].size_of_block), (0x4000, 12));
+        assert_eq!((blocks[2].page_rva, blocks[2].size_of_block), (0x5000, 68));
+        let slots: usize = blocks.iter().map(|block| block.entries.len()).sum();
+        assert_eq!(slots, 3024);
+    }
+
+    #[test]
+    fn test_relocation_blocks_64() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api64.dll")).unwrap();
+        let blocks = blocks_of(&file_info);
+        assert_eq!(blocks.len(), 10);
+        assert_eq!(
+            (blocks[0].page_rva, blocks[0].size_of_block),
+            (0x27000, 116)
+        );
+        assert_eq!(
+            (blocks[1].page_rva, blocks[1].size_of_block),
+            (0x28000, 256)
+        );
+        assert_eq!(
+            (blocks[2].page_rva, blocks[2].size_of_block),
+            (0x2A000, 264)
+        );
+        let first = &blocks[0].entries[0];
+        assert_eq!((first.reloc_type, first.offset), (10, 0x2F0));
+        assert_eq!(first.get_type_name(), "DIR64");
+        let slots: usize = blocks.iter().map(|block| block.entries.len()).sum();
+        assert_eq!(slots, 898);
+    }
+
+    #[test]
+    fn test_relocation_highadj_parameter() {
+        let blocks = blocks_of_patched("penguin_reloc_highadj.dll", |data| {
+            data[RELOC_FO + 8..RELOC_FO + 10].copy_from_slice(&0x4010u16.to_le_bytes());
+            data[RELOC_FO + 10..RELOC_FO + 12].copy_from_slice(&0xBEEFu16.to_le_bytes());
+        });
+        let entries = &blocks[0].entries;
+        // 参数槽不再算作独立的重定位项
+        assert_eq!(entries.len(), 45);
+        assert_eq!(entries[0].get_type_name(), "HIGHADJ");
+        assert_eq!(entries[0].offset, 0x010);
+        assert_eq!(entries[0].parameter, Some(0xBEEF));
+        assert_eq!((entries[1].reloc_type, entries[1].offset), (3, 0x00C));
+        assert_eq!(entries[1].parameter, None);
+    }
+
+    #[test]
+    fn test_relocation_zero_size_block() {
+        let blocks = blocks_of_patched("penguin_reloc_zero_size.dll", |data| {
+            let second = RELOC_FO + 100;
+            data[second + 4..second + 8].copy_from_slice(&0u32.to_le_bytes());
+        });
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].entries.len(), 46);
+    }
+
+    #[test]
+    fn test_relocation_truncated_directory() {
+        // 目录大小只够容纳第一个块和第二个块的块头
+        let blocks = blocks_of_patched("penguin_reloc_truncated.dll", |data| {
+            data[RELOC_SIZE_FO..RELOC_SIZE_FO + 4].copy_from_slice(&108u32.to_le_bytes());
+        });
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].entries.len(), 46);
+        assert_eq!((blocks[1].page_rva, blocks[1].size_of_block), (0x4000, 12));
+        assert!(blocks[1].entries.is_empty());
+    }
+}
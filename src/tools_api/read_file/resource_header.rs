@@ -2,9 +2,12 @@ use crate::i18n;
 use crate::tools_api::read_file::DataDirectory;
 use crate::tools_api::read_file::ImageResourceDataEntry;
 use crate::tools_api::read_file::ImageSectionHeaders;
+use crate::tools_api::read_file::ResourceString;
 use crate::tools_api::read_file::ResourceTree;
 use crate::tools_api::read_file::nt_header::traits::NtHeaders;
 use crate::tools_api::read_file::rva_2_fo;
+use crate::tools_api::read_file::{Accelerator, DialogResource, MenuResource};
+use crate::tools_api::read_file::{Anomaly, AnomalySeverity};
 use crate::tools_api::read_file::{ImageResourceDirectory, ImageResourceDirectoryEntry};
 use std::collections::HashMap;
 use std::fs;
@@ -13,6 +16,13 @@ use std::io::{Read, Seek, SeekFrom};
 use std::mem::{MaybeUninit, size_of};
 use std::path::{Path, PathBuf};
 
+/// 单个资源数据项的大小上限，防止畸形文件导致读取过多
+const MAX_RESOURCE_DATA_SIZE: u32 = 0x400_0000;
+/// 资源目录正常只有类型、名称、语言三层，超过该深度视为畸形
+const MAX_RESOURCE_DEPTH: u32 = 8;
+/// 整棵资源树的目录项数量上限，每个目录最多可以声明131070项
+const MAX_RESOURCE_ENTRIES: u32 = 0x1_0000;
+
 /// ICO/CUR 文件头（6字节）
 #[allow(dead_code)]
 #[repr(C, packed)]
@@ -153,7 +163,7 @@ impl ResourceTree {
     }
 
     /// 根据文件内容的魔数推断文件类型
-    fn detect_file_type(data: &[u8]) -> Option<&'static str> {
+    pub(crate) fn detect_file_type(data: &[u8]) -> Option<&'static str> {
         if data.len() < 4 {
             return None;
         }
@@ -242,6 +252,36 @@ impl ResourceTree {
             return Some(".zip");
         }
 
+        // 7z: 37 7A BC AF 27 1C
+        if data.len() >= 6 && data[..6] == [0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C] {
+            return Some(".7z");
+        }
+
+        // RAR: 52 61 72 21 1A 07 (Rar!)
+        if data.len() >= 6 && data[..6] == [0x52, 0x61, 0x72, 0x21, 0x1A, 0x07] {
+            return Some(".rar");
+        }
+
+        // CAB: 4D 53 43 46 (MSCF)
+        if data[..4] == [0x4D, 0x53, 0x43, 0x46] {
+            return Some(".cab");
+        }
+
+        // GZIP: 1F 8B
+        if data[0] == 0x1F && data[1] == 0x8B {
+            return Some(".gz");
+        }
+
+        // MSI等OLE复合文档: D0 CF 11 E0
+        if data[..4] == [0xD0, 0xCF, 0x11, 0xE0] {
+            return Some(".msi");
+        }
+
+        // PDF: 25 50 44 46 (%PDF)
+        if data[..4] == [0x25, 0x50, 0x44, 0x46] {
+            return Some(".pdf");
+        }
+
         // 尝试检测是否是文本文件（UTF-8 BOM: EF BB BF）
         if data.len() >= 3 && data[0] == 0xEF && data[1] == 0xBB && data[2] == 0xBF {
             return Some(".txt");
@@ -320,8 +360,8 @@ impl ResourceTree {
         name_offset: u32,
     ) -> anyhow::Result<String> {
         // name_offset的低31位是相对于资源段基址的偏移
-        let offset = base_offset + (name_offset & 0x7FFFFFFF);
-        file.seek(SeekFrom::Start(offset as u64))?;
+        let offset = base_offset as u64 + (name_offset & 0x7FFFFFFF) as u64;
+        file.seek(SeekFrom::Start(offset))?;
 
         // 前两个字节是字符串长度
         let mut len_buf = [0u8; 2];
@@ -361,102 +401,44 @@ impl ResourceTree {
         }
     }
 
-    /// 递归解析资源目录树
-    fn parse_resource_directory<E>(
+    pub fn get_resource_tree<E>(
         file: &mut File,
-        base_offset: u32,
-        relative_offset: u32,
+        address: u32,
         nt_head: &E,
         image_section_headers: &ImageSectionHeaders,
-        resource_base_rva: u32,
-        is_type_level: bool,
+        _data_dir: &DataDirectory,
     ) -> anyhow::Result<Self>
     where
         E: NtHeaders + ?Sized,
     {
-        let current_offset = base_offset + relative_offset;
-        let resource_directory = ImageResourceDirectory::new(file, current_offset)?;
-
-        let mut resource_root = Self::new("Directory".to_string(), true, current_offset, 0);
-        let total_entries =
-            resource_directory.number_of_named_entries + resource_directory.number_of_id_entries;
-
-        let entries_offset = current_offset + size_of::<ImageResourceDirectory>() as u32;
-
-        for i in 0..total_entries {
-            let entry_offset =
-                entries_offset + (i as u32 * size_of::<ImageResourceDirectoryEntry>() as u32);
-            let entry = ImageResourceDirectoryEntry::new(file, entry_offset)?;
-
-            let entry_name =
-                Self::get_entry_name(file, base_offset, entry.name_offset, is_type_level)?;
-
-            // 检查是否是子目录
-            if entry.offset_to_data & 0x80000000 != 0 {
-                let subdir_offset = entry.offset_to_data & 0x7FFFFFFF;
-                let subdirectory = Self::parse_resource_directory(
-                    file,
-                    base_offset,
-                    subdir_offset,
-                    nt_head,
-                    image_section_headers,
-                    resource_base_rva,
-                    false,
-                )?;
-
-                let mut subdir_node = Self::new(entry_name, true, entry_offset, 0);
-                for child in subdirectory.children.unwrap_or_default() {
-                    subdir_node.add_child(child);
-                }
-                resource_root.add_child(subdir_node);
-            } else {
-                let data_entry_offset = entry.offset_to_data & 0x7FFFFFFF;
-                let data_entry_file_offset = base_offset + data_entry_offset;
-
-                file.seek(SeekFrom::Start(data_entry_file_offset as u64))?;
-                let data_entry = unsafe {
-                    let mut data_entry = MaybeUninit::<ImageResourceDataEntry>::uninit();
-                    let bytes = std::slice::from_raw_parts_mut(
-                        data_entry.as_mut_ptr() as *mut u8,
-                        size_of::<ImageResourceDataEntry>(),
-                    );
-                    file.read_exact(bytes)?;
-                    data_entry.assume_init()
-                };
-                let data_file_offset =
-                    rva_2_fo(nt_head, image_section_headers, data_entry.data_offset).unwrap_or(0);
-
-                let data_node =
-                    Self::new(entry_name, false, data_file_offset, data_entry.data_size);
-                resource_root.add_child(data_node);
-            }
-        }
-
-        Ok(resource_root)
+        Ok(
+            Self::get_resource_tree_with_anomalies(file, address, nt_head, image_section_headers)?
+                .0,
+        )
     }
 
-    pub fn get_resource_tree<E>(
+    /// 解析资源目录树，同时返回被跳过的畸形目录项
+    pub(crate) fn get_resource_tree_with_anomalies<E>(
         file: &mut File,
         address: u32,
         nt_head: &E,
         image_section_headers: &ImageSectionHeaders,
-        _data_dir: &DataDirectory,
-    ) -> anyhow::Result<Self>
+    ) -> anyhow::Result<(Self, Vec<Anomaly>)>
     where
         E: NtHeaders + ?Sized,
     {
         let base_offset = rva_2_fo(nt_head, image_section_headers, address)
             .ok_or(anyhow::anyhow!(i18n::ERROR_GET_RVA_OFFSET))?;
-
-        Self::parse_resource_directory(
-            file,
-            base_offset,
-            0,
+        let mut walker = ResourceWalker {
             nt_head,
-            image_section_headers,
-            address,
-            true,
-        )
+            section_headers: image_section_headers,
+            base_offset,
+            ancestors: Vec::new(),
+            remaining_entries: MAX_RESOURCE_ENTRIES,
+            anomalies: Vec::new(),
+        };
+        let resource_tree = walker.parse_directory(file, 0, 0)?;
+        Ok((resource_tree, walker.anomalies))
     }
 
     /// 从RT_GROUP_ICON或RT_GROUP_CURSOR数据构建完整的ICO/CUR文件
@@ -581,6 +563,77 @@ impl ResourceTree {
         icon_map
     }
 
+    /// 收集某一资源类型下的所有数据项，返回 (资源名称, 语言数据项)
+    pub(crate) fn get_type_entries(&self, resource_type: &str) -> Vec<(&str, &ResourceTree)> {
+        let mut entries = Vec::new();
+        let type_nodes = self.children.iter().flatten();
+        for type_node in type_nodes.filter(|node| node.name == resource_type) {
+            for name_node in type_node.children.iter().flatten() {
+                match &name_node.children {
+                    Some(languages) => entries.extend(
+                        languages
+                            .iter()
+                            .filter(|language| language.children.is_none())
+                            .map(|language| (name_node.name.as_str(), language)),
+                    ),
+                    None => entries.push((name_node.name.as_str(), name_node)),
+                }
+            }
+        }
+        entries
+    }
+
+    /// 从 "ID_123" 形式的名称中取出ID，命名资源返回None
+    pub(crate) fn parse_id(name: &str) -> Option<u32> {
+        name.strip_prefix("ID_")?.parse().ok()
+    }
+
+    /// 将可解码的资源转换为JSON，无法解码时返回None
+    fn decode_to_json(
+        buffer: &[u8],
+        current_path: &str,
+        parent_type: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        let json = match parent_type {
+            Some("RT_STRING") => {
+                let block_id = current_path
+                    .rsplit('/')
+                    .next()
+                    .and_then(Self::parse_id)
+                    .unwrap_or(0);
+                let strings = ResourceString::from_string_block(block_id, buffer);
+                (!strings.is_empty()).then(|| serde_json::to_string_pretty(&strings))
+            }
+            Some("RT_MESSAGETABLE") => {
+                let strings = ResourceString::from_message_table(buffer);
+                (!strings.is_empty()).then(|| serde_json::to_string_pretty(&strings))
+            }
+            Some("RT_DIALOG") => {
+                DialogResource::parse(buffer).map(|dialog| serde_json::to_string_pretty(&dialog))
+            }
+            Some("RT_MENU") => {
+                MenuResource::parse(buffer).map(|menu| serde_json::to_string_pretty(&menu))
+            }
+            Some("RT_ACCELERATOR") => {
+                let entries = Accelerator::parse_table(buffer);
+                (!entries.is_empty()).then(|| serde_json::to_string_pretty(&entries))
+            }
+            _ => None,
+        };
+        Ok(json.transpose()?)
+    }
+
+    /// 读取数据项的内容，data_address 在解析时已转换为文件偏移
+    pub(crate) fn read_data(&self, file: &mut File) -> anyhow::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        if self.data_address == 0 || self.size > MAX_RESOURCE_DATA_SIZE {
+            return Ok(buffer);
+        }
+        file.seek(SeekFrom::Start(self.data_address as u64))?;
+        file.take(self.size as u64).read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
     /// 提取资源到指定目录
     pub fn extract_resources<T>(
         &self,
@@ -718,6 +771,17 @@ impl ResourceTree {
                     }
                 }
 
+                // 字符串表、消息表与界面资源解码后以JSON保存
+                if let Some(json) = Self::decode_to_json(&buffer, current_path, parent_type)? {
+                    let file_path = output_dir.join(format!("{}.json", new_path));
+                    if let Some(parent) = file_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&file_path, json)?;
+                    extracted_files.push(file_path);
+                    return Ok(());
+                }
+
                 // 生成智能文件名（带扩展名）
                 let filename = Self::generate_filename(&new_path, &buffer, parent_type);
                 let file_path = output_dir.join(&filename);
@@ -745,3 +809,223 @@ impl ResourceTree {
 //     pub data_address: u32,
 //     pub resource_type: String,
 // }
+
+/// 遍历资源目录树，跳过循环引用、嵌套过深和无法读取的目录项
+struct ResourceWalker<'a, E: NtHeaders + ?Sized> {
+    nt_head: &'a E,
+    section_headers: &'a ImageSectionHeaders,
+    /// 资源目录的文件偏移，目录项中的偏移都相对于它
+    base_offset: u32,
+    /// 当前路径上各级目录的文件偏移，子目录指回其中之一才是循环引用，
+    /// 多个目录项共享同一子目录是合法的
+    ancestors: Vec<u32>,
+    remaining_entries: u32,
+    anomalies: Vec<Anomaly>,
+}
+
+impl<E: NtHeaders + ?Sized> ResourceWalker<'_, E> {
+    fn report(&mut self, offset: u32, description: String) {
+        self.anomalies.push(Anomaly::new(
+            "IMAGE_RESOURCE_DIRECTORY_ENTRY",
+            offset as u64,
+            AnomalySeverity::Error,
+            description,
+        ));
+    }
+
+    /// 递归解析资源目录，depth为0时是类型层级
+    fn parse_directory(
+        &mut self,
+        file: &mut File,
+        relative_offset: u32,
+        depth: u32,
+    ) -> anyhow::Result<ResourceTree> {
+        let current_offset = self.base_offset.saturating_add(relative_offset);
+        let resource_directory = ImageResourceDirectory::new(file, current_offset)?;
+        self.ancestors.push(current_offset);
+
+        let mut resource_root = ResourceTree::new("Directory".to_string(), true, current_offset, 0);
+        let total_entries = resource_directory.number_of_named_entries as u32
+            + resource_directory.number_of_id_entries as u32;
+
+        let entries_offset = current_offset as u64 + size_of::<ImageResourceDirectory>() as u64;
+
+        for i in 0..total_entries {
+            let entry_offset =
+                entries_offset + i as u64 * size_of::<ImageResourceDirectoryEntry>() as u64;
+            let Ok(entry_offset) = u32::try_from(entry_offset) else {
+                break;
+            };
+            if self.remaining_entries == 0 {
+                self.report(
+                    entry_offset,
+                    i18n::ANOMALY_RESOURCE_TOO_MANY_ENTRIES
+                        .replace("{}", &MAX_RESOURCE_ENTRIES.to_string()),
+                );
+                break;
+            }
+            self.remaining_entries -= 1;
+            let entry = match ImageResourceDirectoryEntry::new(file, entry_offset) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    self.report(
+                        entry_offset,
+                        i18n::ANOMALY_PARSE_FAILED.replace("{}", &e.to_string()),
+                    );
+                    break;
+                }
+            };
+
+            let entry_name = match ResourceTree::get_entry_name(
+                file,
+                self.base_offset,
+                entry.name_offset,
+                depth == 0,
+            ) {
+                Ok(entry_name) => entry_name,
+                Err(e) => {
+                    self.report(
+                        entry_offset,
+                        i18n::ANOMALY_PARSE_FAILED.replace("{}", &e.to_string()),
+                    );
+                    continue;
+                }
+            };
+
+            // 检查是否是子目录
+            if entry.offset_to_data & 0x80000000 != 0 {
+                let subdir_offset = entry.offset_to_data & 0x7FFFFFFF;
+                let subdir_file_offset = self.base_offset.saturating_add(subdir_offset);
+                if self.ancestors.contains(&subdir_file_offset) {
+                    self.report(
+                        entry_offset,
+                        i18n::ANOMALY_RESOURCE_LOOP
+                            .replace("{}", &format!("0x{:08X}", subdir_file_offset)),
+                    );
+                    continue;
+                }
+                if depth + 1 >= MAX_RESOURCE_DEPTH {
+                    self.report(
+                        entry_offset,
+                        i18n::ANOMALY_RESOURCE_TOO_DEEP
+                            .replace("{}", &format!("0x{:08X}", subdir_file_offset)),
+                    );
+                    continue;
+                }
+                let subdirectory = match self.parse_directory(file, subdir_offset, depth + 1) {
+                    Ok(subdirectory) => subdirectory,
+                    Err(e) => {
+                        self.report(
+                            entry_offset,
+                            i18n::ANOMALY_PARSE_FAILED.replace("{}", &e.to_string()),
+                        );
+                        continue;
+                    }
+                };
+
+                let mut subdir_node = ResourceTree::new(entry_name, true, entry_offset, 0);
+                for child in subdirectory.children.unwrap_or_default() {
+                    subdir_node.add_child(child);
+                }
+                resource_root.add_child(subdir_node);
+            } else {
+                let data_entry_offset = entry.offset_to_data & 0x7FFFFFFF;
+                let data_entry_file_offset = self.base_offset as u64 + data_entry_offset as u64;
+
+                let data_entry = match Self::read_data_entry(file, data_entry_file_offset) {
+                    Ok(data_entry) => data_entry,
+                    Err(e) => {
+                        self.report(
+                            entry_offset,
+                            i18n::ANOMALY_PARSE_FAILED.replace("{}", &e.to_string()),
+                        );
+                        continue;
+                    }
+                };
+                let data_file_offset =
+                    rva_2_fo(self.nt_head, self.section_headers, data_entry.data_offset)
+                        .unwrap_or(0);
+
+                let data_node =
+                    ResourceTree::new(entry_name, false, data_file_offset, data_entry.data_size);
+                resource_root.add_child(data_node);
+            }
+        }
+
+        self.ancestors.pop();
+        Ok(resource_root)
+    }
+
+    fn read_data_entry(file: &mut File, offset: u64) -> anyhow::Result<ImageResourceDataEntry> {
+        file.seek(SeekFrom::Start(offset))?;
+        unsafe {
+            let mut data_entry = MaybeUninit::<ImageResourceDataEntry>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                data_entry.as_mut_ptr() as *mut u8,
+                size_of::<ImageResourceDataEntry>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(data_entry.assume_init())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::read_file::ImageNtHeaders;
+
+    fn walk(data: &[u8], name: &str) -> (ResourceTree, Vec<Anomaly>) {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, data).unwrap();
+        let nt_head = ImageNtHeaders::default();
+        let section_headers = ImageSectionHeaders::default();
+        let mut walker = ResourceWalker {
+            nt_head: &nt_head,
+            section_headers: &section_headers,
+            base_offset: 0,
+            ancestors: Vec::new(),
+            remaining_entries: MAX_RESOURCE_ENTRIES,
+            anomalies: Vec::new(),
+        };
+        let tree = walker
+            .parse_directory(&mut File::open(&path).unwrap(), 0, 0)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        (tree, walker.anomalies)
+    }
+
+    fn directory(id_entries: u16, entries: &[(u32, u32)]) -> Vec<u8> {
+        let mut data = vec![0u8; 14];
+        data.extend_from_slice(&id_entries.to_le_bytes());
+        for (name_offset, offset_to_data) in entries {
+            data.extend_from_slice(&name_offset.to_le_bytes());
+            data.extend_from_slice(&offset_to_data.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_shared_subdirectory_is_not_loop() {
+        // 两个类型共享0x20处的子目录，子目录中的项指回根目录
+        let mut data = directory(2, &[(1, 0x8000_0020), (2, 0x8000_0020)]);
+        data.extend(directory(1, &[(0x409, 0x8000_0000)]));
+        let (tree, anomalies) = walk(&data, "penguin_resource_shared.bin");
+
+        let children = tree.children.unwrap();
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().all(|child| child.children.is_some()));
+        // 只有指回上级目录的项被报告为循环
+        assert_eq!(anomalies.len(), 2);
+        assert!(anomalies.iter().all(|anomaly| anomaly.offset == 0x30));
+    }
+
+    #[test]
+    fn test_self_referencing_directory() {
+        let data = directory(1, &[(3, 0x8000_0000)]);
+        let (tree, anomalies) = walk(&data, "penguin_resource_self.bin");
+        assert!(tree.children.unwrap().is_empty());
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].offset, 0x10);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ImageSectionHeaders, ResourceString, ResourceStringTable, ResourceStrings,
+    ResourceTree,
+};
+use std::fs::File;
+
+/// 每个 RT_STRING 块固定包含16个字符串
+const STRINGS_PER_BLOCK: u32 = 16;
+/// MESSAGE_RESOURCE_BLOCK: LowId、HighId、OffsetToEntries
+const MESSAGE_BLOCK_SIZE: usize = 12;
+/// MESSAGE_RESOURCE_ENTRY: Length、Flags
+const MESSAGE_ENTRY_HEADER_SIZE: usize = 4;
+const MESSAGE_RESOURCE_UNICODE: u16 = 0x0001;
+const MESSAGE_RESOURCE_UTF8: u16 = 0x0002;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn decode_utf16(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+impl ResourceString {
+    /// 解码一个字符串块，块ID从1开始，字符串ID为 (块ID - 1) * 16 + 序号
+    pub(crate) fn from_string_block(block_id: u32, data: &[u8]) -> Vec<ResourceString> {
+        let mut strings = Vec::new();
+        let mut offset = 0;
+        for index in 0..STRINGS_PER_BLOCK {
+            let Some(length) = read_u16(data, offset) else {
+                break;
+            };
+            let start = offset + 2;
+            let end = (start + length as usize * 2).min(data.len());
+            offset = end;
+            if length == 0 {
+                continue;
+            }
+            strings.push(ResourceString {
+                id: block_id
+                    .saturating_sub(1)
+                    .wrapping_mul(STRINGS_PER_BLOCK)
+                    .wrapping_add(index),
+                text: decode_utf16(&data[start..end]),
+            });
+        }
+        strings
+    }
+
+    /// 解码 MESSAGE_RESOURCE_DATA
+    pub(crate) fn from_message_table(data: &[u8]) -> Vec<ResourceString> {
+        let mut strings = Vec::new();
+        let Some(number_of_blocks) = read_u32(data, 0) else {
+            return strings;
+        };
+        // 块数量不可能超过数据能容纳的数量
+        let number_of_blocks = (number_of_blocks as usize).min(data.len() / MESSAGE_BLOCK_SIZE);
+        for block in 0..number_of_blocks {
+            let block_offset = 4 + block * MESSAGE_BLOCK_SIZE;
+            let (Some(low_id), Some(high_id), Some(entries_offset)) = (
+                read_u32(data, block_offset),
+                read_u32(data, block_offset + 4),
+                read_u32(data, block_offset + 8),
+            ) else {
+                break;
+            };
+            let mut offset = entries_offset as usize;
+            for id in low_id..=high_id {
+                let (Some(length), Some(flags)) =
+                    (read_u16(data, offset), read_u16(data, offset + 2))
+                else {
+                    break;
+                };
+                let length = length as usize;
+                if length < MESSAGE_ENTRY_HEADER_SIZE {
+                    break;
+                }
+                let end = (offset + length).min(data.len());
+                let text = &data[offset + MESSAGE_ENTRY_HEADER_SIZE..end];
+                let text = match flags {
+                    MESSAGE_RESOURCE_UNICODE => decode_utf16(text),
+                    MESSAGE_RESOURCE_UTF8 => String::from_utf8_lossy(text).to_string(),
+                    _ => text.iter().map(|&b| b as char).collect(),
+                };
+                strings.push(ResourceString {
+                    id,
+                    text: text.trim_end_matches(['\0', '\r', '\n']).to_string(),
+                });
+                offset = end;
+            }
+        }
+        strings
+    }
+}
+
+impl ResourceStrings {
+    /// 按语言汇总所有字符串表与消息表，两者都不存在时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Option<ResourceStrings>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let resource_rva = data_dir.get_resource_directory_address()?;
+        if resource_rva == 0 {
+            return Ok(None);
+        }
+        let resource_tree = ResourceTree::get_resource_tree(
+            file,
+            resource_rva,
+            nt_head,
+            section_headers,
+            data_dir,
+        )?;
+        let mut resource_strings = ResourceStrings::default();
+        for (name, entry) in resource_tree.get_type_entries("RT_STRING") {
+            let block_id = ResourceTree::parse_id(name).unwrap_or(0);
+            let strings = ResourceString::from_string_block(block_id, &entry.read_data(file)?);
+            Self::add_strings(&mut resource_strings.string_tables, entry, strings);
+        }
+        for (_, entry) in resource_tree.get_type_entries("RT_MESSAGETABLE") {
+            let strings = ResourceString::from_message_table(&entry.read_data(file)?);
+            Self::add_strings(&mut resource_strings.message_tables, entry, strings);
+        }
+        if resource_strings.string_tables.is_empty() && resource_strings.message_tables.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(resource_strings))
+    }
+
+    /// 将字符串加入对应语言的表中，并保持按ID排序
+    fn add_strings(
+        tables: &mut Vec<ResourceStringTable>,
+        entry: &ResourceTree,
+        strings: Vec<ResourceString>,
+    ) {
+        let language = ResourceTree::parse_id(&entry.name).unwrap_or(0) as u16;
+        let index = match tables.iter().position(|table| table.language == language) {
+            Some(index) => index,
+            None => {
+                tables.push(ResourceStringTable {
+                    language,
+                    strings: Vec::new(),
+                });
+                tables.len() - 1
+            }
+        };
+        tables[index].strings.extend(strings);
+        tables[index].strings.sort_by_key(|string| string.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect()
+    }
+
+    /// 长度前缀的UTF-16字符串，空字符串只占长度字段
+    fn build_string_block(strings: &[&str]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for text in strings {
+            data.extend((text.encode_utf16().count() as u16).to_le_bytes());
+            data.extend(utf16(text));
+        }
+        data
+    }
+
+    fn build_message_entry(flags: u16, text: &[u8]) -> Vec<u8> {
+        let mut entry = Vec::new();
+        let length = (MESSAGE_ENTRY_HEADER_SIZE + text.len()).next_multiple_of(4);
+        entry.extend((length as u16).to_le_bytes());
+        entry.extend(flags.to_le_bytes());
+        entry.extend(text);
+        entry.resize(length, 0);
+        entry
+    }
+
+    #[test]
+    fn test_string_block() {
+        let mut names = [""; STRINGS_PER_BLOCK as usize];
+        names[0] = "Open";
+        names[3] = "Save As…";
+        names[15] = "Exit";
+        let strings = ResourceString::from_string_block(2, &build_string_block(&names));
+        let strings: Vec<(u32, &str)> = strings.iter().map(|s| (s.id, s.text.as_str())).collect();
+        assert_eq!(strings, [(16, "Open"), (19, "Save As…"), (31, "Exit")]);
+    }
+
+    #[test]
+    fn test_truncated_string_block() {
+        let mut data = build_string_block(&["Open", "Close"]);
+        // 第二个字符串声明的长度超出数据
+        data.truncate(data.len() - 4);
+        let strings = ResourceString::from_string_block(1, &data);
+        assert_eq!(strings.len(), 2);
+        assert_eq!(strings[0].text, "Open");
+        assert_eq!(strings[1].text, "Clo");
+        assert!(ResourceString::from_string_block(1, &[0x05]).is_empty());
+    }
+
+    #[test]
+    fn test_message_table() {
+        let block_1 = [
+            build_message_entry(MESSAGE_RESOURCE_UNICODE, &utf16("File not found.\r\n\0")),
+            build_message_entry(MESSAGE_RESOURCE_UTF8, "Zugriff verweigert.\r\n".as_bytes()),
+        ]
+        .concat();
+        let block_2 = build_message_entry(0, b"Caf\xE9\0");
+        let entries_offset = 4 + 2 * MESSAGE_BLOCK_SIZE;
+        let mut data = Vec::new();
+        data.extend(2u32.to_le_bytes());
+        for (low_id, high_id, offset) in [
+            (1u32, 2u32, entries_offset),
+            (0x100, 0x100, entries_offset + block_1.len()),
+        ] {
+            data.extend(low_id.to_le_bytes());
+            data.extend(high_id.to_le_bytes());
+            data.extend((offset as u32).to_le_bytes());
+        }
+        data.extend(block_1);
+        data.extend(block_2);
+
+        let strings = ResourceString::from_message_table(&data);
+        let strings: Vec<(u32, &str)> = strings.iter().map(|s| (s.id, s.text.as_str())).collect();
+        assert_eq!(
+            strings,
+            [
+                (1, "File not found."),
+                (2, "Zugriff verweigert."),
+                (0x100, "Café")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_message_table() {
+        // 块数量远超数据大小，入口偏移越界
+        let mut data = Vec::new();
+        data.extend(u32::MAX.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(u32::MAX.to_le_bytes());
+        data.extend(0x1000u32.to_le_bytes());
+        assert!(ResourceString::from_message_table(&data).is_empty());
+        assert!(ResourceString::from_message_table(&[0x01]).is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+use crate::tools_api::calc::calc_rich_header_hash;
+use crate::tools_api::read_file::{RichEntry, RichHeader};
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+
+const RICH_SIGNATURE: &[u8; 4] = b"Rich";
+/// 解密后的 "DanS"
+const DANS_SIGNATURE: u32 = 0x536E_6144;
+/// DanS 之后固定有三个解密后为0的填充项
+const DANS_PADDING_SIZE: usize = 12;
+const RICH_ENTRY_SIZE: usize = 8;
+/// 计算校验和时跳过DOS头中的 e_lfanew
+const E_LFANEW_RANGE: std::ops::Range<usize> = 0x3C..0x40;
+const DOS_HEADER_SIZE: usize = 0x40;
+
+/// 已知的产品ID名称，未列出的按十六进制显示
+const PRODUCT_NAMES: [(u16, &str); 67] = [
+    (0x0000, "Unknown"),
+    (0x0001, "Import0"),
+    (0x0002, "Linker510"),
+    (0x0003, "Cvtomf510"),
+    (0x0004, "Linker600"),
+    (0x0005, "Cvtomf600"),
+    (0x0006, "Cvtres500"),
+    (0x0007, "Utc11_Basic"),
+    (0x0008, "Utc11_C"),
+    (0x0009, "Utc12_Basic"),
+    (0x000A, "Utc12_C"),
+    (0x000B, "Utc12_CPP"),
+    (0x000C, "AliasObj60"),
+    (0x000D, "VisualBasic60"),
+    (0x000E, "Masm613"),
+    (0x000F, "Masm710"),
+    (0x0010, "Linker511"),
+    (0x0011, "Cvtomf511"),
+    (0x0012, "Masm614"),
+    (0x0013, "Linker512"),
+    (0x0014, "Cvtomf512"),
+    (0x0015, "Utc12_C_Std"),
+    (0x0016, "Utc12_CPP_Std"),
+    (0x0019, "Implib700"),
+    (0x001A, "Cvtomf700"),
+    (0x001B, "Utc13_Basic"),
+    (0x001C, "Utc13_C"),
+    (0x001D, "Utc13_CPP"),
+    (0x003D, "Linker700"),
+    (0x003F, "Export700"),
+    (0x0040, "Masm700"),
+    (0x0045, "Cvtres700"),
+    (0x005A, "Linker710"),
+    (0x005B, "Cvtomf710"),
+    (0x005C, "Export710"),
+    (0x005D, "Implib710"),
+    (0x005E, "Cvtres710"),
+    (0x005F, "Utc1310_C"),
+    (0x0060, "Utc1310_CPP"),
+    (0x006D, "Utc1400_C"),
+    (0x006E, "Utc1400_CPP"),
+    (0x0078, "Linker800"),
+    (0x007A, "Export800"),
+    (0x007B, "Implib800"),
+    (0x007C, "Cvtres800"),
+    (0x007D, "Masm800"),
+    (0x0083, "Utc1500_C"),
+    (0x0084, "Utc1500_CPP"),
+    (0x0091, "Linker900"),
+    (0x0092, "Export900"),
+    (0x0093, "Implib900"),
+    (0x0094, "Cvtres900"),
+    (0x0095, "Masm900"),
+    (0x009A, "Cvtres1000"),
+    (0x009B, "Export1000"),
+    (0x009C, "Implib1000"),
+    (0x009D, "Linker1000"),
+    (0x009E, "Masm1000"),
+    (0x00AA, "Utc1600_C"),
+    (0x00AB, "Utc1600_CPP"),
+    (0x00FF, "Cvtres1400"),
+    (0x0100, "Export1400"),
+    (0x0101, "Implib1400"),
+    (0x0102, "Linker1400"),
+    (0x0103, "Masm1400"),
+    (0x0104, "Utc1900_C"),
+    (0x0105, "Utc1900_CPP"),
+];
+
+/// 同一版本工具链的产品ID是连续分配的，按范围即可估计Visual Studio版本
+const VS_VERSIONS: [(u16, u16, &str); 10] = [
+    (0x0002, 0x0003, "Visual Studio 97"),
+    (0x0004, 0x0018, "Visual Studio 6.0"),
+    (0x0019, 0x0059, "Visual Studio .NET 2002"),
+    (0x005A, 0x006C, "Visual Studio .NET 2003"),
+    (0x006D, 0x0082, "Visual Studio 2005"),
+    (0x0083, 0x0097, "Visual Studio 2008"),
+    (0x0098, 0x00AF, "Visual Studio 2010"),
+    (0x00B0, 0x00CF, "Visual Studio 2012"),
+    (0x00D0, 0x00FE, "Visual Studio 2013"),
+    (0x00FF, 0x010E, "Visual Studio 2015+"),
+];
+
+/// VS2015 之后的版本共用产品ID，只能按构建号区分
+const VS14_BUILDS: [(u16, &str); 3] = [
+    (25000, "Visual Studio 2015"),
+    (27500, "Visual Studio 2017"),
+    (30300, "Visual Studio 2019"),
+];
+
+impl RichEntry {
+    fn new(comp_id: u32, count: u32) -> Self {
+        let product_id = (comp_id >> 16) as u16;
+        let build = comp_id as u16;
+        RichEntry {
+            product_id,
+            build,
+            count,
+            product_name: get_product_name(product_id),
+            vs_version: get_vs_version(product_id, build),
+        }
+    }
+}
+
+fn get_product_name(product_id: u16) -> String {
+    PRODUCT_NAMES
+        .iter()
+        .find(|(id, _)| *id == product_id)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("0x{:04X}", product_id))
+}
+
+fn get_vs_version(product_id: u16, build: u16) -> String {
+    let Some((_, end, name)) = VS_VERSIONS
+        .iter()
+        .find(|(start, end, _)| (*start..=*end).contains(&product_id))
+    else {
+        return String::new();
+    };
+    if *end != 0x010E {
+        return name.to_string();
+    }
+    VS14_BUILDS
+        .iter()
+        .find(|(limit, _)| build < *limit)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| "Visual Studio 2022".to_string())
+}
+
+impl RichHeader {
+    /// 在NT头之前查找Rich头，不存在或格式错误时返回None
+    pub fn new(file: &mut File, nt_addr: u32) -> anyhow::Result<Option<RichHeader>> {
+        // e_lfanew 可能很大，按文件实际内容读取而不是预先分配
+        let mut data = Vec::new();
+        file.seek(SeekFrom::Start(0))?;
+        file.by_ref().take(nt_addr as u64).read_to_end(&mut data)?;
+        Ok(Self::parse(&data))
+    }
+
+    fn parse(data: &[u8]) -> Option<RichHeader> {
+        let read_u32 = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let rich_offset = (DOS_HEADER_SIZE..data.len().saturating_sub(7))
+            .step_by(4)
+            .find(|&offset| &data[offset..offset + 4] == RICH_SIGNATURE)?;
+        let xor_key = read_u32(rich_offset + 4)?;
+        // 从 "Rich" 向前解密，直到遇到 "DanS"
+        let dans_offset = (DOS_HEADER_SIZE..rich_offset)
+            .step_by(4)
+            .rev()
+            .find(|&offset| read_u32(offset).is_some_and(|v| v ^ xor_key == DANS_SIGNATURE))?;
+        let clear_data: Vec<u8> = (dans_offset..rich_offset)
+            .step_by(4)
+            .flat_map(|offset| (read_u32(offset).unwrap_or(0) ^ xor_key).to_le_bytes())
+            .collect();
+        let entries: Vec<RichEntry> = clear_data
+            .get(4 + DANS_PADDING_SIZE..)?
+            .chunks_exact(RICH_ENTRY_SIZE)
+            .map(|entry| {
+                RichEntry::new(
+                    u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                    u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+                )
+            })
+            .collect();
+
+        let mut checksum = dans_offset as u32;
+        for (index, &b) in data[..dans_offset].iter().enumerate() {
+            if E_LFANEW_RANGE.contains(&index) {
+                continue;
+            }
+            checksum = checksum.wrapping_add((b as u32).rotate_left(index as u32));
+        }
+        for entry in &entries {
+            let comp_id = ((entry.product_id as u32) << 16) | entry.build as u32;
+            checksum = checksum.wrapping_add(comp_id.rotate_left(entry.count));
+        }
+        Some(RichHeader {
+            file_offset: dans_offset as u32,
+            size: (rich_offset + 8 - dans_offset) as u32,
+            xor_key,
+            checksum,
+            checksum_valid: checksum == xor_key,
+            entries,
+            hash: calc_rich_header_hash(&clear_data),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XOR_KEY: u32 = 0xA914_C0AF;
+    const ENTRIES: [(u32, u32); 3] = [(0x0104_784B, 3), (0x0105_784B, 12), (0x0102_784B, 1)];
+
+    /// DOS头、标准DOS存根，以及位于0x80的Rich头
+    fn build_rich_header() -> Vec<u8> {
+        let mut data = vec![0u8; DOS_HEADER_SIZE];
+        data[..2].copy_from_slice(b"MZ");
+        data[E_LFANEW_RANGE].copy_from_slice(&0x100u32.to_le_bytes());
+        data.extend(b"\x0e\x1f\xba\x0e\x00\xb4\x09\xcd\x21\xb8\x01\x4c\xcd\x21");
+        data.extend(b"This program cannot be run in DOS mode.\r\r\n$");
+        data.resize(0x80, 0);
+        data.extend((DANS_SIGNATURE ^ XOR_KEY).to_le_bytes());
+        for _ in 0..3 {
+            data.extend(XOR_KEY.to_le_bytes());
+        }
+        for (comp_id, count) in ENTRIES {
+            data.extend((comp_id ^ XOR_KEY).to_le_bytes());
+            data.extend((count ^ XOR_KEY).to_le_bytes());
+        }
+        data.extend(RICH_SIGNATURE);
+        data.extend(XOR_KEY.to_le_bytes());
+        data.resize(0x100, 0);
+        data
+    }
+
+    #[test]
+    fn test_parse_rich_header() {
+        let rich_header = RichHeader::parse(&build_rich_header()).unwrap();
+        assert_eq!(rich_header.file_offset, 0x80);
+        assert_eq!(rich_header.size, 0x10 + 3 * 8 + 8);
+        assert_eq!(rich_header.xor_key, XOR_KEY);
+        assert_eq!(rich_header.entries.len(), 3);
+        let entry = &rich_header.entries[1];
+        assert_eq!(
+            (entry.product_id, entry.build, entry.count),
+            (0x0105, 30795, 12)
+        );
+        assert_eq!(entry.product_name, "Utc1900_CPP");
+        assert_eq!(entry.vs_version, "Visual Studio 2022");
+    }
+
+    #[test]
+    fn test_rich_header_checksum() {
+        let mut data = build_rich_header();
+        let rich_header = RichHeader::parse(&data).unwrap();
+        assert_eq!(rich_header.checksum, XOR_KEY);
+        assert!(rich_header.checksum_valid);
+
+        // e_lfanew 不参与校验和
+        data[E_LFANEW_RANGE].copy_from_slice(&0x200u32.to_le_bytes());
+        assert!(RichHeader::parse(&data).unwrap().checksum_valid);
+
+        // 修改DOS存根后校验和不再等于异或密钥
+        data[0x4E] = b't';
+        assert!(!RichHeader::parse(&data).unwrap().checksum_valid);
+    }
+
+    #[test]
+    fn test_missing_rich_header() {
+        let mut data = build_rich_header();
+        data[0x80..].fill(0);
+        assert!(RichHeader::parse(&data).is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::tools_api::read_file::{
-    _SectionData, ImageSectionHeader, ImageSectionHeaders, SectionCharAddr,
+    _SectionData, ImageSectionHeader, ImageSectionHeaders, SectionCharAddr, symbol,
 };
 use std::fs::File;
 use std::io::SeekFrom;
@@ -80,19 +80,48 @@ impl ImageSectionHeader {
 }
 
 impl ImageSectionHeaders {
+    /// string_table_offset 为COFF字符串表的位置，用于解析 `/123` 形式的长节名
     pub fn new(
         file: &mut File,
         section_addr: u32,
         section_num: u16,
+        string_table_offset: Option<u64>,
     ) -> anyhow::Result<ImageSectionHeaders> {
         file.seek(SeekFrom::Start(section_addr as u64))?;
         let mut section_headers: ImageSectionHeaders = Default::default();
         let mut section_chara_addr = section_addr + OFFSET_CHAR;
         for _ in 0..section_num {
-            section_headers.add(ImageSectionHeader::new(file)?);
+            // 节表被截断时只保留完整读取的节
+            let Ok(section_header) = ImageSectionHeader::new(file) else {
+                break;
+            };
+            section_headers.add(section_header);
             section_headers.add_addr(section_chara_addr as u64);
             section_chara_addr += size_of::<ImageSectionHeader>() as u32;
         }
+        let names: Vec<String> = section_headers
+            .0
+            .iter()
+            .map(|section| {
+                String::from_utf8_lossy(&section.name)
+                    .trim_end_matches('\0')
+                    .to_string()
+            })
+            .collect();
+        let string_table = match string_table_offset {
+            Some(offset) if names.iter().any(|name| name.starts_with('/')) => {
+                symbol::read_string_table(file, offset)?
+            }
+            _ => Vec::new(),
+        };
+        section_headers.2 = names
+            .into_iter()
+            .map(|name| {
+                symbol::parse_long_name_offset(&name)
+                    .and_then(|offset| symbol::get_string(&string_table, offset))
+                    .unwrap_or(name)
+            })
+            .collect();
         Ok(section_headers)
     }
     pub fn add(&mut self, section_header: ImageSectionHeader) {
@@ -106,14 +135,31 @@ impl ImageSectionHeaders {
         Ok(self.0.len())
     }
     pub fn get_virtual_rva_end(&self, index: usize) -> u32 {
-        self.0[index].virtual_address + self.0[index].size_of_raw_data
+        self.0[index]
+            .virtual_address
+            .saturating_add(self.0[index].size_of_raw_data)
     }
     // 制取方法
     pub(crate) fn get_section_name(&self, index: usize) -> anyhow::Result<String> {
+        if let Some(name) = self.2.get(index) {
+            return Ok(name.clone());
+        }
         let section_name = self.0.get(index).unwrap().name;
         let section_name = String::from_utf8(section_name.to_vec())?;
         Ok(section_name)
     }
+
+    /// 所有节的名称，长节名已解析
+    pub(crate) fn get_section_names(&self) -> &[String] {
+        &self.2
+    }
+    /// 查找RVA所在节的名称
+    pub(crate) fn get_section_name_by_rva(&self, rva: u32) -> Option<String> {
+        let index = (0..self.0.len()).find(|&i| {
+            rva >= self.get_section_virtual_address(i) && rva < self.get_virtual_rva_end(i)
+        })?;
+        self.get_section_name(index).ok()
+    }
     ///文件地址或当前节在内存中未对齐时的大小，即真实大小
     pub fn get_section_misc(&self, index: usize) -> anyhow::Result<u32> {
         Ok(self.0.get(index).unwrap().misc.virtual_size)
@@ -0,0 +1,447 @@
+use crate::tools_api::read_file::{CoffAuxSymbol, CoffSymbol, CoffSymbolTable};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+/// IMAGE_SYMBOL 与辅助记录均为18字节
+const SYMBOL_SIZE: u64 = 18;
+/// 字符串表以4字节的总长度开头，长度包含这4个字节
+const STRING_TABLE_SIZE_FIELD: usize = 4;
+
+const IMAGE_SYM_UNDEFINED: i16 = 0;
+const IMAGE_SYM_ABSOLUTE: i16 = -1;
+const IMAGE_SYM_DEBUG: i16 = -2;
+/// 复杂类型位于 Type 的高位，0x20 表示函数
+const IMAGE_SYM_DTYPE_FUNCTION: u16 = 0x20;
+
+const IMAGE_SYM_CLASS_EXTERNAL: u8 = 2;
+const IMAGE_SYM_CLASS_STATIC: u8 = 3;
+const IMAGE_SYM_CLASS_FUNCTION: u8 = 101;
+const IMAGE_SYM_CLASS_FILE: u8 = 103;
+const IMAGE_SYM_CLASS_WEAK_EXTERNAL: u8 = 105;
+
+const STORAGE_CLASS_NAMES: [(u8, &str); 27] = [
+    (0xFF, "END_OF_FUNCTION"),
+    (0, "NULL"),
+    (1, "AUTOMATIC"),
+    (2, "EXTERNAL"),
+    (3, "STATIC"),
+    (4, "REGISTER"),
+    (5, "EXTERNAL_DEF"),
+    (6, "LABEL"),
+    (7, "UNDEFINED_LABEL"),
+    (8, "MEMBER_OF_STRUCT"),
+    (9, "ARGUMENT"),
+    (10, "STRUCT_TAG"),
+    (11, "MEMBER_OF_UNION"),
+    (12, "UNION_TAG"),
+    (13, "TYPE_DEFINITION"),
+    (14, "UNDEFINED_STATIC"),
+    (15, "ENUM_TAG"),
+    (16, "MEMBER_OF_ENUM"),
+    (17, "REGISTER_PARAM"),
+    (18, "BIT_FIELD"),
+    (100, "BLOCK"),
+    (101, "FUNCTION"),
+    (102, "END_OF_STRUCT"),
+    (103, "FILE"),
+    (104, "SECTION"),
+    (105, "WEAK_EXTERNAL"),
+    (107, "CLR_TOKEN"),
+];
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+fn trim_name(data: &[u8]) -> String {
+    let end = data.iter().position(|&x| x == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).to_string()
+}
+
+/// 字符串表紧跟在符号表之后，文件不含符号表时返回None
+pub(crate) fn get_string_table_offset(
+    pointer_to_symbol_table: u32,
+    number_of_symbols: u32,
+) -> Option<u64> {
+    (pointer_to_symbol_table != 0)
+        .then(|| pointer_to_symbol_table as u64 + number_of_symbols as u64 * SYMBOL_SIZE)
+}
+
+/// 读取整个字符串表（包含开头的长度字段），超出文件的部分会被截断
+pub(crate) fn read_string_table(file: &mut File, offset: u64) -> anyhow::Result<Vec<u8>> {
+    let file_size = file.metadata()?.len();
+    if offset + STRING_TABLE_SIZE_FIELD as u64 > file_size {
+        return Ok(Vec::new());
+    }
+    let mut size = [0u8; STRING_TABLE_SIZE_FIELD];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut size)?;
+    let size = (u32::from_le_bytes(size) as u64).min(file_size - offset);
+    let mut table = Vec::new();
+    file.seek(SeekFrom::Start(offset))?;
+    file.take(size).read_to_end(&mut table)?;
+    Ok(table)
+}
+
+/// 按偏移读取字符串表中以0结尾的字符串，偏移从长度字段开始计算
+pub(crate) fn get_string(string_table: &[u8], offset: usize) -> Option<String> {
+    if offset < STRING_TABLE_SIZE_FIELD {
+        return None;
+    }
+    Some(trim_name(string_table.get(offset..)?))
+}
+
+/// 解析节名称中的长名称偏移，`/123` 为十进制，`//AAAAAA` 为base64
+pub(crate) fn parse_long_name_offset(name: &str) -> Option<usize> {
+    if let Some(encoded) = name.strip_prefix("//") {
+        return encoded.bytes().try_fold(0usize, |offset, c| {
+            let digit = match c {
+                b'A'..=b'Z' => c - b'A',
+                b'a'..=b'z' => c - b'a' + 26,
+                b'0'..=b'9' => c - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            };
+            offset.checked_mul(64)?.checked_add(digit as usize)
+        });
+    }
+    name.strip_prefix('/')?.parse().ok()
+}
+
+impl CoffAuxSymbol {
+    fn parse(symbol: &CoffSymbol, data: &[u8]) -> CoffAuxSymbol {
+        match symbol.storage_class {
+            IMAGE_SYM_CLASS_EXTERNAL if symbol.is_function() && symbol.section_number > 0 => {
+                CoffAuxSymbol::FunctionDefinition {
+                    tag_index: read_u32(data, 0),
+                    total_size: read_u32(data, 4),
+                    pointer_to_line_number: read_u32(data, 8),
+                    pointer_to_next_function: read_u32(data, 12),
+                }
+            }
+            IMAGE_SYM_CLASS_FUNCTION => CoffAuxSymbol::FunctionLine {
+                line_number: read_u16(data, 4),
+                pointer_to_next_function: read_u32(data, 12),
+            },
+            IMAGE_SYM_CLASS_WEAK_EXTERNAL => CoffAuxSymbol::WeakExternal {
+                tag_index: read_u32(data, 0),
+                characteristics: read_u32(data, 4),
+            },
+            IMAGE_SYM_CLASS_EXTERNAL
+                if symbol.section_number == IMAGE_SYM_UNDEFINED && symbol.value == 0 =>
+            {
+                CoffAuxSymbol::WeakExternal {
+                    tag_index: read_u32(data, 0),
+                    characteristics: read_u32(data, 4),
+                }
+            }
+            IMAGE_SYM_CLASS_STATIC => CoffAuxSymbol::SectionDefinition {
+                length: read_u32(data, 0),
+                number_of_relocations: read_u16(data, 4),
+                number_of_line_numbers: read_u16(data, 6),
+                check_sum: read_u32(data, 8),
+                number: read_u16(data, 12),
+                selection: data[14],
+            },
+            _ => CoffAuxSymbol::Unknown {
+                data: data.to_vec(),
+            },
+        }
+    }
+
+    /// 用于显示的简要说明
+    pub fn get_summary(&self) -> String {
+        match self {
+            CoffAuxSymbol::FunctionDefinition {
+                tag_index,
+                total_size,
+                ..
+            } => format!("Function size 0x{:X}, tag {}", total_size, tag_index),
+            CoffAuxSymbol::FunctionLine { line_number, .. } => format!("Line {}", line_number),
+            CoffAuxSymbol::WeakExternal {
+                tag_index,
+                characteristics,
+            } => format!(
+                "Weak -> #{}, characteristics {}",
+                tag_index, characteristics
+            ),
+            CoffAuxSymbol::File { file_name } => file_name.clone(),
+            CoffAuxSymbol::SectionDefinition {
+                length,
+                number_of_relocations,
+                number,
+                selection,
+                ..
+            } => {
+                let summary = format!(
+                    "Length 0x{:X}, relocations {}",
+                    length, number_of_relocations
+                );
+                // 只有COMDAT节的 Selection 不为0
+                if *selection == 0 {
+                    summary
+                } else {
+                    format!("{}, COMDAT #{} selection {}", summary, number, selection)
+                }
+            }
+            CoffAuxSymbol::Unknown { data } => data
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+impl CoffSymbol {
+    pub fn get_storage_class_name(&self) -> &'static str {
+        STORAGE_CLASS_NAMES
+            .iter()
+            .find(|(class, _)| *class == self.storage_class)
+            .map(|(_, name)| *name)
+            .unwrap_or("UNKNOWN")
+    }
+
+    /// 节序号的显示名称，特殊序号显示为 UNDEFINED/ABSOLUTE/DEBUG
+    pub fn get_section_name(&self, section_names: &[String]) -> String {
+        match self.section_number {
+            IMAGE_SYM_UNDEFINED => "UNDEFINED".to_string(),
+            IMAGE_SYM_ABSOLUTE => "ABSOLUTE".to_string(),
+            IMAGE_SYM_DEBUG => "DEBUG".to_string(),
+            number => section_names
+                .get(number as usize - 1)
+                .cloned()
+                .unwrap_or_else(|| format!("#{}", number)),
+        }
+    }
+
+    pub fn is_function(&self) -> bool {
+        self.symbol_type & 0xF0 == IMAGE_SYM_DTYPE_FUNCTION
+    }
+}
+
+impl CoffSymbolTable {
+    /// 读取 FileHeader 指向的COFF符号表，不存在时返回None
+    pub fn new(
+        file: &mut File,
+        pointer_to_symbol_table: u32,
+        number_of_symbols: u32,
+    ) -> anyhow::Result<Option<CoffSymbolTable>> {
+        let Some(string_table_offset) =
+            get_string_table_offset(pointer_to_symbol_table, number_of_symbols)
+        else {
+            return Ok(None);
+        };
+        if number_of_symbols == 0 {
+            return Ok(None);
+        }
+        let file_size = file.metadata()?.len();
+        // 符号数量可能被篡改，只读取文件中实际存在的部分
+        let table_size = (number_of_symbols as u64 * SYMBOL_SIZE)
+            .min(file_size.saturating_sub(pointer_to_symbol_table as u64));
+        let mut data = Vec::new();
+        file.seek(SeekFrom::Start(pointer_to_symbol_table as u64))?;
+        file.take(table_size).read_to_end(&mut data)?;
+        let string_table = read_string_table(file, string_table_offset)?;
+        let records: Vec<&[u8]> = data.chunks_exact(SYMBOL_SIZE as usize).collect();
+        let mut symbols = Vec::new();
+        let mut index = 0;
+        while index < records.len() {
+            let record = records[index];
+            let name = if read_u32(record, 0) == 0 {
+                get_string(&string_table, read_u32(record, 4) as usize).unwrap_or_default()
+            } else {
+                trim_name(&record[..8])
+            };
+            let mut symbol = CoffSymbol {
+                index: index as u32,
+                name,
+                value: read_u32(record, 8),
+                section_number: read_u16(record, 12) as i16,
+                symbol_type: read_u16(record, 14),
+                storage_class: record[16],
+                aux_symbols: Vec::new(),
+            };
+            let aux_end = (index + 1 + record[17] as usize).min(records.len());
+            let aux_records = &records[index + 1..aux_end];
+            if symbol.storage_class == IMAGE_SYM_CLASS_FILE && !aux_records.is_empty() {
+                // 文件名可能跨越多个辅助记录
+                let file_name = trim_name(&aux_records.concat());
+                symbol.aux_symbols.push(CoffAuxSymbol::File { file_name });
+            } else {
+                symbol.aux_symbols = aux_records
+                    .iter()
+                    .map(|aux| CoffAuxSymbol::parse(&symbol, aux))
+                    .collect();
+            }
+            symbols.push(symbol);
+            index = aux_end;
+        }
+        Ok(Some(CoffSymbolTable {
+            pointer_to_symbol_table,
+            number_of_symbols,
+            string_table_size: string_table.len() as u32,
+            symbols,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 符号表紧跟在文件头之后，共4条记录（含1条辅助记录）
+    const SYMBOL_TABLE: u32 = 20;
+    const STRING_TABLE: usize = SYMBOL_TABLE as usize + 4 * SYMBOL_SIZE as usize;
+    /// 字符串表中 "long_function_name" 的偏移
+    const LONG_NAME_OFFSET: u32 = 13;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn symbol(
+        name: &[u8],
+        value: u32,
+        section_number: i16,
+        symbol_type: u16,
+        storage_class: u8,
+        aux_count: u8,
+    ) -> Vec<u8> {
+        let mut record = vec![0u8; SYMBOL_SIZE as usize];
+        put(&mut record, 0, name);
+        put(&mut record, 8, &value.to_le_bytes());
+        put(&mut record, 12, &section_number.to_le_bytes());
+        put(&mut record, 14, &symbol_type.to_le_bytes());
+        record[16] = storage_class;
+        record[17] = aux_count;
+        record
+    }
+
+    /// 最小的x64目标文件：无节，只有符号表与字符串表
+    fn coff_object() -> Vec<u8> {
+        let mut data = vec![0u8; SYMBOL_TABLE as usize];
+        put(&mut data, 0, &0x8664u16.to_le_bytes());
+        put(&mut data, 8, &SYMBOL_TABLE.to_le_bytes());
+        put(&mut data, 12, &4u32.to_le_bytes());
+        // 名称恰好8个字符时没有结尾的0
+        data.extend(symbol(b".text$mn", 0, 1, 0, IMAGE_SYM_CLASS_STATIC, 1));
+        let mut aux = vec![0u8; SYMBOL_SIZE as usize];
+        put(&mut aux, 0, &0x10u32.to_le_bytes());
+        put(&mut aux, 4, &2u16.to_le_bytes());
+        aux[14] = 2;
+        data.extend(aux);
+        let mut long_name = [0u8; 8];
+        put(&mut long_name, 4, &LONG_NAME_OFFSET.to_le_bytes());
+        data.extend(symbol(
+            &long_name,
+            4,
+            1,
+            IMAGE_SYM_DTYPE_FUNCTION,
+            IMAGE_SYM_CLASS_EXTERNAL,
+            0,
+        ));
+        data.extend(symbol(b"main", 0, 0, 0, IMAGE_SYM_CLASS_EXTERNAL, 0));
+        assert_eq!(data.len(), STRING_TABLE);
+        data.extend(32u32.to_le_bytes());
+        data.extend(b".text$mn\0long_function_name\0");
+        data
+    }
+
+    fn read_symbols(name: &str, data: &[u8]) -> CoffSymbolTable {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, data).unwrap();
+        let mut file = File::open(&path).unwrap();
+        let symbols = CoffSymbolTable::new(&mut file, SYMBOL_TABLE, 4)
+            .unwrap()
+            .unwrap();
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+        symbols
+    }
+
+    #[test]
+    fn test_short_and_long_names() {
+        let symbols = read_symbols("penguin_symbols.obj", &coff_object());
+        assert_eq!(symbols.string_table_size, 32);
+        // 辅助记录不单独成为符号
+        assert_eq!(symbols.symbols.len(), 3);
+        let section = &symbols.symbols[0];
+        assert_eq!(section.name, ".text$mn");
+        assert_eq!(section.get_storage_class_name(), "STATIC");
+        assert_eq!(
+            section.aux_symbols,
+            vec![CoffAuxSymbol::SectionDefinition {
+                length: 0x10,
+                number_of_relocations: 2,
+                number_of_line_numbers: 0,
+                check_sum: 0,
+                number: 0,
+                selection: 2,
+            }]
+        );
+        let function = &symbols.symbols[1];
+        assert_eq!(function.index, 2);
+        assert_eq!(function.name, "long_function_name");
+        assert!(function.is_function());
+        assert_eq!(function.value, 4);
+        let external = &symbols.symbols[2];
+        assert_eq!(external.name, "main");
+        assert_eq!(external.get_section_name(&[]), "UNDEFINED");
+    }
+
+    #[test]
+    fn test_truncated_string_table() {
+        // 字符串表声明32字节，文件只保留到长名称之前
+        let mut data = coff_object();
+        data.truncate(STRING_TABLE + LONG_NAME_OFFSET as usize);
+        let symbols = read_symbols("penguin_symbols_truncated.obj", &data);
+        assert_eq!(symbols.string_table_size, LONG_NAME_OFFSET);
+        assert_eq!(symbols.symbols[0].name, ".text$mn");
+        assert_eq!(symbols.symbols[1].name, "");
+        assert_eq!(symbols.symbols[2].name, "main");
+        // 只剩长度字段的一部分时字符串表为空
+        data.truncate(STRING_TABLE + 2);
+        let symbols = read_symbols("penguin_symbols_no_strings.obj", &data);
+        assert_eq!(symbols.string_table_size, 0);
+        assert_eq!(symbols.symbols[1].name, "");
+    }
+
+    #[test]
+    fn test_long_name_offset() {
+        let string_table = &coff_object()[STRING_TABLE..];
+        assert_eq!(parse_long_name_offset("/4"), Some(4));
+        assert_eq!(parse_long_name_offset("//AAAAAE"), Some(4));
+        assert_eq!(parse_long_name_offset(".text"), None);
+        assert_eq!(parse_long_name_offset("/abc"), None);
+        assert_eq!(get_string(string_table, 4).as_deref(), Some(".text$mn"));
+        // 偏移落在长度字段内或超出字符串表
+        assert_eq!(get_string(string_table, 0), None);
+        assert_eq!(get_string(string_table, 64), None);
+    }
+
+    #[test]
+    fn test_no_symbol_table() {
+        let path = std::env::temp_dir().join("penguin_no_symbols.obj");
+        std::fs::write(&path, coff_object()).unwrap();
+        let mut file = File::open(&path).unwrap();
+        assert!(CoffSymbolTable::new(&mut file, 0, 4).unwrap().is_none());
+        assert!(
+            CoffSymbolTable::new(&mut file, SYMBOL_TABLE, 0)
+                .unwrap()
+                .is_none()
+        );
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
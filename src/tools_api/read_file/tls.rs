@@ -0,0 +1,255 @@
+use crate::tools_api::read_file::nt_header::DIRECTORY_TLS;
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ImageSectionHeaders, ImageTlsDirectory, ImageTlsDirectory64, TlsCallback,
+    TlsInfo, rva_2_fo,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
+use std::mem::{MaybeUninit, size_of};
+
+/// 回调数组以0结尾，防止畸形文件导致读取过多
+const MAX_TLS_CALLBACKS: usize = 1024;
+
+impl ImageTlsDirectory {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut tls_directory = MaybeUninit::<ImageTlsDirectory>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                tls_directory.as_mut_ptr() as *mut u8,
+                size_of::<ImageTlsDirectory>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(tls_directory.assume_init())
+        }
+    }
+}
+
+impl ImageTlsDirectory64 {
+    pub(crate) fn new(file: &mut File, address: u32) -> anyhow::Result<Self> {
+        file.seek(SeekFrom::Start(address as u64))?;
+        unsafe {
+            let mut tls_directory = MaybeUninit::<ImageTlsDirectory64>::uninit();
+            let bytes = std::slice::from_raw_parts_mut(
+                tls_directory.as_mut_ptr() as *mut u8,
+                size_of::<ImageTlsDirectory64>(),
+            );
+            file.read_exact(bytes)?;
+            Ok(tls_directory.assume_init())
+        }
+    }
+}
+
+impl TlsInfo {
+    /// 读取 IMAGE_DIRECTORY_ENTRY_TLS，文件不含TLS目录时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+        is_64_bit: bool,
+    ) -> anyhow::Result<Option<TlsInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let directory_rva = data_dir.get_data_directory_virtual_address(DIRECTORY_TLS);
+        if directory_rva == 0 {
+            return Ok(None);
+        }
+        let file_offset = match rva_2_fo(nt_head, section_headers, directory_rva) {
+            Some(fo) => fo,
+            None => return Ok(None),
+        };
+        let mut tls_info = if is_64_bit {
+            let tls_directory = ImageTlsDirectory64::new(file, file_offset)?;
+            TlsInfo {
+                file_offset,
+                start_address_of_raw_data: tls_directory.start_address_of_raw_data,
+                end_address_of_raw_data: tls_directory.end_address_of_raw_data,
+                address_of_index: tls_directory.address_of_index,
+                address_of_call_backs: tls_directory.address_of_call_backs,
+                size_of_zero_fill: tls_directory.size_of_zero_fill,
+                characteristics: tls_directory.characteristics,
+                callbacks: Vec::new(),
+            }
+        } else {
+            let tls_directory = ImageTlsDirectory::new(file, file_offset)?;
+            TlsInfo {
+                file_offset,
+                start_address_of_raw_data: tls_directory.start_address_of_raw_data as u64,
+                end_address_of_raw_data: tls_directory.end_address_of_raw_data as u64,
+                address_of_index: tls_directory.address_of_index as u64,
+                address_of_call_backs: tls_directory.address_of_call_backs as u64,
+                size_of_zero_fill: tls_directory.size_of_zero_fill,
+                characteristics: tls_directory.characteristics,
+                callbacks: Vec::new(),
+            }
+        };
+        tls_info.callbacks = Self::read_callbacks(
+            file,
+            nt_head,
+            section_headers,
+            tls_info.address_of_call_backs,
+            is_64_bit,
+        );
+        Ok(Some(tls_info))
+    }
+
+    /// 读取AddressOfCallBacks指向的VA数组，并转换为RVA与文件偏移
+    /// 数组被截断时保留已读到的回调，不影响TLS目录本身
+    fn read_callbacks<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        address_of_call_backs: u64,
+        is_64_bit: bool,
+    ) -> Vec<TlsCallback>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let mut callbacks = Vec::new();
+        let image_base = nt_head.get_image_base();
+        if address_of_call_backs < image_base {
+            return callbacks;
+        }
+        let array_rva = (address_of_call_backs - image_base) as u32;
+        let array_fo = match rva_2_fo(nt_head, section_headers, array_rva) {
+            Some(fo) => fo,
+            None => return callbacks,
+        };
+        if file.seek(SeekFrom::Start(array_fo as u64)).is_err() {
+            return callbacks;
+        }
+        for _ in 0..MAX_TLS_CALLBACKS {
+            let virtual_address = if is_64_bit {
+                file.read_u64::<LittleEndian>()
+            } else {
+                file.read_u32::<LittleEndian>().map(u64::from)
+            };
+            let Ok(virtual_address) = virtual_address else {
+                break;
+            };
+            if virtual_address == 0 {
+                break;
+            }
+            let rva = virtual_address.wrapping_sub(image_base) as u32;
+            callbacks.push(TlsCallback {
+                virtual_address,
+                rva,
+                file_offset: rva_2_fo(nt_head, section_headers, rva),
+                section_name: section_headers
+                    .get_section_name_by_rva(rva)
+                    .unwrap_or_default(),
+            });
+        }
+        callbacks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools_api::FileInfo;
+    use crate::tools_api::read_file::TlsInfo;
+    use std::path::PathBuf;
+
+    fn tls_of(path: &str) -> TlsInfo {
+        let file_info = FileInfo::new(PathBuf::from(path)).unwrap();
+        file_info.get_tls().unwrap().unwrap()
+    }
+
+    /// 把回调数组指向 .reloc 节开头并写入两个回调，再把文件截断在 `length` 处
+    fn tls_of_truncated(
+        path: &str,
+        name: &str,
+        callbacks_field_fo: usize,
+        array_rva: u32,
+        array_fo: usize,
+        length: usize,
+    ) -> TlsInfo {
+        let is_64_bit = path.ends_with("64.dll");
+        let image_base = FileInfo::new(PathBuf::from(path))
+            .unwrap()
+            .nt_head
+            .get_image_base();
+        let mut data = std::fs::read(path).unwrap();
+        let callbacks = [image_base + 0x1010, image_base + 0x1020];
+        let field_size = if is_64_bit { 8 } else { 4 };
+        let write = |data: &mut Vec<u8>, offset: usize, value: u64| {
+            data[offset..offset + field_size].copy_from_slice(&value.to_le_bytes()[..field_size]);
+        };
+        write(&mut data, callbacks_field_fo, image_base + array_rva as u64);
+        for (index, callback) in callbacks.iter().enumerate() {
+            write(&mut data, array_fo + index * field_size, *callback);
+        }
+        data.truncate(length);
+
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &data).unwrap();
+        let file_info = FileInfo::new(path.clone()).unwrap();
+        let tls = file_info.get_tls().unwrap().unwrap();
+        drop(file_info);
+        std::fs::remove_file(&path).unwrap();
+        tls
+    }
+
+    #[test]
+    fn test_tls_32() {
+        let tls = tls_of("test_pe/steam_api.dll");
+        assert_eq!(tls.file_offset, 0x2B500);
+        assert_eq!(tls.start_address_of_raw_data, 994232864);
+        assert_eq!(tls.end_address_of_raw_data, 994232872);
+        assert_eq!(tls.address_of_index, 994302000);
+        assert_eq!(tls.address_of_call_backs, 994202012);
+        assert_eq!(tls.characteristics, 0x0030_0000);
+        assert!(tls.callbacks.is_empty());
+    }
+
+    #[test]
+    fn test_tls_64() {
+        let tls = tls_of("test_pe/steam_api64.dll");
+        assert_eq!(tls.file_offset, 0x30280);
+        assert_eq!(tls.start_address_of_raw_data, 5289220800);
+        assert_eq!(tls.end_address_of_raw_data, 5289220808);
+        assert_eq!(tls.address_of_index, 5289299328);
+        assert_eq!(tls.address_of_call_backs, 5289177968);
+        assert_eq!(tls.characteristics, 0x0030_0000);
+        assert!(tls.callbacks.is_empty());
+    }
+
+    #[test]
+    fn test_tls_truncated_callbacks_32() {
+        // 两个回调之后即为文件末尾，没有结尾的0
+        let tls = tls_of_truncated(
+            "test_pe/steam_api.dll",
+            "penguin_tls_truncated_32.dll",
+            0x2B500 + 12,
+            0x40000,
+            0x3C600,
+            0x3C600 + 8,
+        );
+        let rvas: Vec<_> = tls.callbacks.iter().map(|callback| callback.rva).collect();
+        assert_eq!(rvas, [0x1010, 0x1020]);
+        assert_eq!(tls.callbacks[0].section_name, ".text");
+        assert_eq!(tls.callbacks[0].file_offset, Some(0x410));
+    }
+
+    #[test]
+    fn test_tls_truncated_callbacks_64() {
+        // 第二个回调只剩半个，只保留第一个
+        let tls = tls_of_truncated(
+            "test_pe/steam_api64.dll",
+            "penguin_tls_truncated_64.dll",
+            0x30280 + 24,
+            0x4B000,
+            0x45600,
+            0x45600 + 12,
+        );
+        assert_eq!(tls.start_address_of_raw_data, 5289220800);
+        assert_eq!(tls.callbacks.len(), 1);
+        assert_eq!(tls.callbacks[0].rva, 0x1010);
+        assert_eq!(tls.callbacks[0].section_name, ".text");
+    }
+}
@@ -0,0 +1,788 @@
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    Accelerator, AcceleratorTable, DataDirectory, DialogControl, DialogResource,
+    ImageSectionHeaders, MenuItem, MenuResource, ResourceTree, UiResources,
+};
+use std::fs::File;
+
+/// DLGTEMPLATEEX 的 dlgVer 与 signature
+const DIALOG_EX_SIGNATURE: u32 = 0xFFFF_0001;
+const DS_SETFONT: u32 = 0x40;
+/// 预定义控件类的原子
+const PREDEFINED_CLASSES: [(u16, &str); 6] = [
+    (0x80, "Button"),
+    (0x81, "Edit"),
+    (0x82, "Static"),
+    (0x83, "ListBox"),
+    (0x84, "ScrollBar"),
+    (0x85, "ComboBox"),
+];
+const WINDOW_STYLES: [(u32, &str); 14] = [
+    (0x8000_0000, "WS_POPUP"),
+    (0x4000_0000, "WS_CHILD"),
+    (0x2000_0000, "WS_MINIMIZE"),
+    (0x1000_0000, "WS_VISIBLE"),
+    (0x0800_0000, "WS_DISABLED"),
+    (0x0400_0000, "WS_CLIPSIBLINGS"),
+    (0x0200_0000, "WS_CLIPCHILDREN"),
+    (0x0080_0000, "WS_BORDER"),
+    (0x0040_0000, "WS_DLGFRAME"),
+    (0x0020_0000, "WS_VSCROLL"),
+    (0x0010_0000, "WS_HSCROLL"),
+    (0x0008_0000, "WS_SYSMENU"),
+    (0x0002_0000, "WS_GROUP"),
+    (0x0001_0000, "WS_TABSTOP"),
+];
+
+const MF_GRAYED: u32 = 0x0001;
+const MF_DISABLED: u32 = 0x0002;
+const MF_CHECKED: u32 = 0x0008;
+const MF_POPUP: u16 = 0x0010;
+const MF_END: u16 = 0x0080;
+const MF_SEPARATOR: u32 = 0x0800;
+const MFT_SEPARATOR: u32 = 0x0800;
+const MFS_DISABLED: u32 = 0x0003;
+const MFS_CHECKED: u32 = 0x0008;
+/// MENUEX 中 bResInfo 的标志
+const MENUEX_POPUP: u16 = 0x01;
+/// 防止畸形文件导致递归过深
+const MAX_MENU_DEPTH: u32 = 8;
+
+const FVIRTKEY: u16 = 0x01;
+const FSHIFT: u16 = 0x04;
+const FCONTROL: u16 = 0x08;
+const FALT: u16 = 0x10;
+const ACCELERATOR_END: u16 = 0x80;
+const ACCELERATOR_SIZE: usize = 8;
+const VIRTUAL_KEYS: [(u16, &str); 24] = [
+    (0x08, "Backspace"),
+    (0x09, "Tab"),
+    (0x0D, "Enter"),
+    (0x13, "Pause"),
+    (0x1B, "Esc"),
+    (0x20, "Space"),
+    (0x21, "PageUp"),
+    (0x22, "PageDown"),
+    (0x23, "End"),
+    (0x24, "Home"),
+    (0x25, "Left"),
+    (0x26, "Up"),
+    (0x27, "Right"),
+    (0x28, "Down"),
+    (0x2C, "PrintScreen"),
+    (0x2D, "Insert"),
+    (0x2E, "Delete"),
+    (0x6A, "Num*"),
+    (0x6B, "Num+"),
+    (0x6D, "Num-"),
+    (0x6E, "Num."),
+    (0x6F, "Num/"),
+    (0xBB, "="),
+    (0xBD, "-"),
+];
+
+/// 按顺序读取资源模板，越界时返回None
+struct TemplateReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> TemplateReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        TemplateReader { data, offset: 0 }
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.data.get(self.offset..self.offset + 2)?;
+        self.offset += 2;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.data.get(self.offset..self.offset + 4)?;
+        self.offset += 4;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.u16().map(|value| value as i16)
+    }
+
+    fn align_4(&mut self) {
+        self.offset = (self.offset + 3) & !3;
+    }
+
+    /// 以0结尾的UTF-16字符串
+    fn string(&mut self) -> Option<String> {
+        let mut units = Vec::new();
+        loop {
+            match self.u16()? {
+                0 => break,
+                unit => units.push(unit),
+            }
+        }
+        Some(String::from_utf16_lossy(&units))
+    }
+
+    /// sz_Or_Ord：0表示空，0xFFFF之后是序号，否则是字符串
+    fn string_or_ordinal(&mut self) -> Option<Result<String, u16>> {
+        let first = self.u16()?;
+        match first {
+            0 => Some(Ok(String::new())),
+            0xFFFF => Some(Err(self.u16()?)),
+            _ => {
+                self.offset -= 2;
+                Some(Ok(self.string()?))
+            }
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        Some(match self.string_or_ordinal()? {
+            Ok(name) => name,
+            Err(ordinal) => format!("#{}", ordinal),
+        })
+    }
+
+    fn class_name(&mut self) -> Option<String> {
+        Some(match self.string_or_ordinal()? {
+            Ok(name) => name,
+            Err(atom) => PREDEFINED_CLASSES
+                .iter()
+                .find(|(id, _)| *id == atom)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| format!("#0x{:04X}", atom)),
+        })
+    }
+}
+
+/// 将窗口样式转换为 WS_* 名称
+fn get_window_style_names(style: u32) -> String {
+    WINDOW_STYLES
+        .iter()
+        .filter(|(flag, _)| style & flag != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+impl DialogControl {
+    pub fn get_style_names(&self) -> String {
+        get_window_style_names(self.style)
+    }
+}
+
+impl DialogResource {
+    pub fn get_style_names(&self) -> String {
+        get_window_style_names(self.style)
+    }
+
+    pub(crate) fn parse(data: &[u8]) -> Option<DialogResource> {
+        let mut reader = TemplateReader::new(data);
+        let first = reader.u32()?;
+        let extended = first == DIALOG_EX_SIGNATURE;
+        let mut dialog = DialogResource {
+            extended,
+            ..Default::default()
+        };
+        if extended {
+            reader.offset += 4; // helpID
+            dialog.ex_style = reader.u32()?;
+            dialog.style = reader.u32()?;
+        } else {
+            dialog.style = first;
+            dialog.ex_style = reader.u32()?;
+        }
+        let count = reader.u16()?;
+        dialog.x = reader.i16()?;
+        dialog.y = reader.i16()?;
+        dialog.cx = reader.i16()?;
+        dialog.cy = reader.i16()?;
+        dialog.menu = reader.name()?;
+        dialog.class = reader.class_name()?;
+        dialog.title = reader.string()?;
+        if dialog.style & DS_SETFONT != 0 {
+            let point_size = reader.u16()?;
+            if extended {
+                reader.offset += 4; // weight、italic、charset
+            }
+            dialog.font = format!("{}, {}", point_size, reader.string()?);
+        }
+        for _ in 0..count {
+            reader.align_4();
+            let Some(control) = Self::parse_control(&mut reader, extended) else {
+                break;
+            };
+            dialog.controls.push(control);
+        }
+        Some(dialog)
+    }
+
+    fn parse_control(reader: &mut TemplateReader, extended: bool) -> Option<DialogControl> {
+        let mut control = DialogControl::default();
+        if extended {
+            reader.offset += 4; // helpID
+            control.ex_style = reader.u32()?;
+            control.style = reader.u32()?;
+        } else {
+            control.style = reader.u32()?;
+            control.ex_style = reader.u32()?;
+        }
+        control.x = reader.i16()?;
+        control.y = reader.i16()?;
+        control.cx = reader.i16()?;
+        control.cy = reader.i16()?;
+        control.id = if extended {
+            reader.u32()?
+        } else {
+            reader.u16()? as u32
+        };
+        control.class = reader.class_name()?;
+        control.text = reader.name()?;
+        let extra_size = reader.u16()?;
+        reader.offset += extra_size as usize;
+        Some(control)
+    }
+}
+
+impl MenuItem {
+    /// 将标志转换为可读名称
+    pub fn get_flag_names(&self, extended: bool) -> String {
+        let flags: &[(u32, &str)] = if extended {
+            &[(MFT_SEPARATOR, "SEPARATOR")]
+        } else {
+            &[
+                (MF_GRAYED, "GRAYED"),
+                (MF_DISABLED, "DISABLED"),
+                (MF_CHECKED, "CHECKED"),
+                (MF_SEPARATOR, "SEPARATOR"),
+            ]
+        };
+        let mut names: Vec<&str> = flags
+            .iter()
+            .filter(|(flag, _)| self.flags & flag != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        if extended && self.state & MFS_DISABLED != 0 {
+            names.push("DISABLED");
+        }
+        if extended && self.state & MFS_CHECKED != 0 {
+            names.push("CHECKED");
+        }
+        if !extended && self.flags == 0 && self.id == 0 && self.text.is_empty() {
+            names.push("SEPARATOR");
+        }
+        names.join(" | ")
+    }
+
+    fn parse_menu(reader: &mut TemplateReader, depth: u32) -> Option<Vec<MenuItem>> {
+        let mut items = Vec::new();
+        if depth > MAX_MENU_DEPTH {
+            return Some(items);
+        }
+        loop {
+            let flags = reader.u16()?;
+            let mut item = MenuItem {
+                flags: (flags & !(MF_POPUP | MF_END)) as u32,
+                ..Default::default()
+            };
+            if flags & MF_POPUP == 0 {
+                item.id = reader.u16()? as u32;
+            }
+            item.text = reader.string()?;
+            if flags & MF_POPUP != 0 {
+                item.children = Self::parse_menu(reader, depth + 1)?;
+            }
+            items.push(item);
+            if flags & MF_END != 0 {
+                break;
+            }
+        }
+        Some(items)
+    }
+
+    fn parse_menu_ex(reader: &mut TemplateReader, depth: u32) -> Option<Vec<MenuItem>> {
+        let mut items = Vec::new();
+        if depth > MAX_MENU_DEPTH {
+            return Some(items);
+        }
+        loop {
+            reader.align_4();
+            let mut item = MenuItem {
+                flags: reader.u32()?,
+                state: reader.u32()?,
+                id: reader.u32()?,
+                ..Default::default()
+            };
+            let res_info = reader.u16()?;
+            item.text = reader.string()?;
+            if res_info & MENUEX_POPUP != 0 {
+                reader.align_4();
+                reader.offset += 4; // dwHelpId
+                item.children = Self::parse_menu_ex(reader, depth + 1)?;
+            }
+            items.push(item);
+            if res_info & MF_END != 0 {
+                break;
+            }
+        }
+        Some(items)
+    }
+}
+
+impl MenuResource {
+    pub(crate) fn parse(data: &[u8]) -> Option<MenuResource> {
+        let mut reader = TemplateReader::new(data);
+        let version = reader.u16()?;
+        let header_size = reader.u16()?;
+        let extended = version == 1;
+        reader.offset += header_size as usize;
+        let items = if extended {
+            MenuItem::parse_menu_ex(&mut reader, 0)
+        } else {
+            MenuItem::parse_menu(&mut reader, 0)
+        };
+        Some(MenuResource {
+            extended,
+            items: items?,
+            ..Default::default()
+        })
+    }
+}
+
+impl Accelerator {
+    fn get_key_name(flags: u16, key: u16) -> String {
+        let mut names = Vec::new();
+        if flags & FCONTROL != 0 {
+            names.push("Ctrl".to_string());
+        }
+        if flags & FSHIFT != 0 {
+            names.push("Shift".to_string());
+        }
+        if flags & FALT != 0 {
+            names.push("Alt".to_string());
+        }
+        let key_name = if flags & FVIRTKEY == 0 {
+            match key {
+                // 非虚拟键的控制字符表示 Ctrl+字母
+                0x01..=0x1A => format!("Ctrl+{}", (key as u8 + b'@') as char),
+                _ => char::from_u32(key as u32)
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("0x{:04X}", key)),
+            }
+        } else {
+            match key {
+                0x30..=0x39 | 0x41..=0x5A => (key as u8 as char).to_string(),
+                0x60..=0x69 => format!("Num{}", key - 0x60),
+                0x70..=0x87 => format!("F{}", key - 0x6F),
+                _ => VIRTUAL_KEYS
+                    .iter()
+                    .find(|(code, _)| *code == key)
+                    .map(|(_, name)| name.to_string())
+                    .unwrap_or_else(|| format!("VK_0x{:02X}", key)),
+            }
+        };
+        names.push(key_name);
+        names.join("+")
+    }
+
+    pub(crate) fn parse_table(data: &[u8]) -> Vec<Accelerator> {
+        let mut entries = Vec::new();
+        for entry in data.chunks_exact(ACCELERATOR_SIZE) {
+            let flags = u16::from_le_bytes([entry[0], entry[1]]);
+            let key = u16::from_le_bytes([entry[2], entry[3]]);
+            entries.push(Accelerator {
+                flags: flags & !ACCELERATOR_END,
+                key,
+                id: u16::from_le_bytes([entry[4], entry[5]]),
+                key_name: Self::get_key_name(flags, key),
+            });
+            if flags & ACCELERATOR_END != 0 {
+                break;
+            }
+        }
+        entries
+    }
+}
+
+impl UiResources {
+    /// 解析所有对话框、菜单与快捷键表，都不存在时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Option<UiResources>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let resource_rva = data_dir.get_resource_directory_address()?;
+        if resource_rva == 0 {
+            return Ok(None);
+        }
+        let resource_tree = ResourceTree::get_resource_tree(
+            file,
+            resource_rva,
+            nt_head,
+            section_headers,
+            data_dir,
+        )?;
+        let mut ui_resources = UiResources::default();
+        for (name, entry) in resource_tree.get_type_entries("RT_DIALOG") {
+            if let Some(mut dialog) = DialogResource::parse(&entry.read_data(file)?) {
+                dialog.resource_name = name.to_string();
+                dialog.language = ResourceTree::parse_id(&entry.name).unwrap_or(0) as u16;
+                ui_resources.dialogs.push(dialog);
+            }
+        }
+        for (name, entry) in resource_tree.get_type_entries("RT_MENU") {
+            if let Some(mut menu) = MenuResource::parse(&entry.read_data(file)?) {
+                menu.resource_name = name.to_string();
+                menu.language = ResourceTree::parse_id(&entry.name).unwrap_or(0) as u16;
+                ui_resources.menus.push(menu);
+            }
+        }
+        for (name, entry) in resource_tree.get_type_entries("RT_ACCELERATOR") {
+            ui_resources.accelerators.push(AcceleratorTable {
+                resource_name: name.to_string(),
+                language: ResourceTree::parse_id(&entry.name).unwrap_or(0) as u16,
+                entries: Accelerator::parse_table(&entry.read_data(file)?),
+            });
+        }
+        if ui_resources.dialogs.is_empty()
+            && ui_resources.menus.is_empty()
+            && ui_resources.accelerators.is_empty()
+        {
+            return Ok(None);
+        }
+        Ok(Some(ui_resources))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按资源模板的格式拼接测试数据
+    #[derive(Default)]
+    struct Template(Vec<u8>);
+
+    impl Template {
+        fn u16(mut self, value: u16) -> Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn u32(mut self, value: u32) -> Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn rect(self, x: i16, y: i16, cx: i16, cy: i16) -> Self {
+            self.u16(x as u16)
+                .u16(y as u16)
+                .u16(cx as u16)
+                .u16(cy as u16)
+        }
+
+        fn string(mut self, value: &str) -> Self {
+            for unit in value.encode_utf16().chain([0]) {
+                self = self.u16(unit);
+            }
+            self
+        }
+
+        fn ordinal(self, value: u16) -> Self {
+            self.u16(0xFFFF).u16(value)
+        }
+
+        fn align_4(mut self) -> Self {
+            self.0.resize((self.0.len() + 3) & !3, 0);
+            self
+        }
+    }
+
+    fn dialog_template() -> Vec<u8> {
+        Template::default()
+            .u32(0x8008_0040) // WS_POPUP | WS_SYSMENU | DS_SETFONT
+            .u32(0)
+            .u16(2)
+            .rect(10, 20, 200, 100)
+            .u16(0)
+            .u16(0)
+            .string("About")
+            .u16(8)
+            .string("MS Shell Dlg")
+            .align_4()
+            .u32(0x5001_0000)
+            .u32(0)
+            .rect(140, 80, 50, 14)
+            .u16(1)
+            .ordinal(0x80)
+            .string("OK")
+            .u16(0)
+            .align_4()
+            .u32(0x5000_0000)
+            .u32(0)
+            .rect(-1, 7, 32, 32)
+            .u16(0xFFFF)
+            .ordinal(0x82)
+            .ordinal(101)
+            .u16(0)
+            .0
+    }
+
+    #[test]
+    fn test_dialog_template() {
+        let dialog = DialogResource::parse(&dialog_template()).unwrap();
+        assert!(!dialog.extended);
+        assert_eq!(dialog.get_style_names(), "WS_POPUP | WS_SYSMENU");
+        assert_eq!(
+            (dialog.x, dialog.y, dialog.cx, dialog.cy),
+            (10, 20, 200, 100)
+        );
+        assert_eq!((dialog.menu.as_str(), dialog.class.as_str()), ("", ""));
+        assert_eq!(dialog.title, "About");
+        assert_eq!(dialog.font, "8, MS Shell Dlg");
+        assert_eq!(
+            dialog.controls,
+            [
+                DialogControl {
+                    id: 1,
+                    class: "Button".to_string(),
+                    text: "OK".to_string(),
+                    style: 0x5001_0000,
+                    ex_style: 0,
+                    x: 140,
+                    y: 80,
+                    cx: 50,
+                    cy: 14,
+                },
+                DialogControl {
+                    id: 0xFFFF,
+                    class: "Static".to_string(),
+                    text: "#101".to_string(),
+                    style: 0x5000_0000,
+                    ex_style: 0,
+                    x: -1,
+                    y: 7,
+                    cx: 32,
+                    cy: 32,
+                },
+            ]
+        );
+        assert_eq!(
+            dialog.controls[0].get_style_names(),
+            "WS_CHILD | WS_VISIBLE | WS_TABSTOP"
+        );
+    }
+
+    #[test]
+    fn test_dialog_template_ex() {
+        let data = Template::default()
+            .u32(DIALOG_EX_SIGNATURE)
+            .u32(0) // helpID
+            .u32(0x0000_0100) // WS_EX_WINDOWEDGE
+            .u32(0x80C8_0040)
+            .u16(2)
+            .rect(0, 0, 300, 180)
+            .ordinal(102)
+            .string("MyDlgClass")
+            .string("Settings")
+            .u16(9)
+            .u16(400) // weight
+            .u16(0x0100) // italic、charset
+            .string("Segoe UI")
+            .align_4()
+            .u32(0)
+            .u32(0x0000_0200)
+            .u32(0x5081_0080)
+            .rect(7, 7, 100, 12)
+            .u32(0x1_0001)
+            .ordinal(0x81)
+            .u16(0)
+            .u16(4) // 附加数据
+            .u32(0xDEAD_BEEF)
+            .align_4()
+            .u32(0)
+            .u32(0)
+            .u32(0x5001_0000)
+            .rect(7, 24, 286, 140)
+            .u32(1002)
+            .string("SysListView32")
+            .string("list")
+            .u16(0)
+            .0;
+        let dialog = DialogResource::parse(&data).unwrap();
+        assert!(dialog.extended);
+        assert_eq!(dialog.ex_style, 0x100);
+        assert_eq!(dialog.style, 0x80C8_0040);
+        assert_eq!(dialog.menu, "#102");
+        assert_eq!(dialog.class, "MyDlgClass");
+        assert_eq!(dialog.title, "Settings");
+        assert_eq!(dialog.font, "9, Segoe UI");
+        assert_eq!(dialog.controls.len(), 2);
+        let edit = &dialog.controls[0];
+        assert_eq!(
+            (edit.id, edit.class.as_str(), edit.text.as_str()),
+            (0x1_0001, "Edit", "")
+        );
+        assert_eq!(edit.ex_style, 0x200);
+        // 附加数据之后的控件仍能正确对齐
+        let list = &dialog.controls[1];
+        assert_eq!(
+            (list.id, list.class.as_str(), list.text.as_str()),
+            (1002, "SysListView32", "list")
+        );
+        assert_eq!((list.x, list.y, list.cx, list.cy), (7, 24, 286, 140));
+    }
+
+    #[test]
+    fn test_truncated_dialog() {
+        let data = dialog_template();
+        // 头部不完整时无法解析
+        assert_eq!(DialogResource::parse(&data[..16]), None);
+        // 控件列表不完整时保留已解析的控件
+        let dialog = DialogResource::parse(&data[..data.len() - 6]).unwrap();
+        assert_eq!(dialog.title, "About");
+        assert_eq!(dialog.controls.len(), 1);
+        assert_eq!(dialog.controls[0].text, "OK");
+    }
+
+    fn menu_template() -> Vec<u8> {
+        Template::default()
+            .u16(0)
+            .u16(0)
+            .u16(MF_POPUP)
+            .string("&File")
+            .u16(0)
+            .u16(100)
+            .string("&Open")
+            .u16(0)
+            .u16(0)
+            .string("")
+            .u16(MF_POPUP | MF_END)
+            .string("Recent")
+            .u16(MF_END | MF_GRAYED as u16)
+            .u16(200)
+            .string("None")
+            .u16(MF_END)
+            .u16(300)
+            .string("&Help")
+            .0
+    }
+
+    #[test]
+    fn test_nested_popup_menu() {
+        let menu = MenuResource::parse(&menu_template()).unwrap();
+        assert!(!menu.extended);
+        let top: Vec<_> = menu.items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(top, ["&File", "&Help"]);
+        assert_eq!(menu.items[1].id, 300);
+        let file = &menu.items[0].children;
+        assert_eq!(file.len(), 3);
+        assert_eq!((file[0].id, file[0].text.as_str()), (100, "&Open"));
+        assert_eq!(file[1].get_flag_names(false), "SEPARATOR");
+        let recent = &file[2];
+        assert_eq!(recent.text, "Recent");
+        assert_eq!(
+            recent.children,
+            [MenuItem {
+                id: 200,
+                text: "None".to_string(),
+                flags: MF_GRAYED,
+                ..Default::default()
+            }]
+        );
+        assert_eq!(recent.children[0].get_flag_names(false), "GRAYED");
+    }
+
+    #[test]
+    fn test_menu_ex() {
+        let data = Template::default()
+            .u16(1)
+            .u16(4)
+            .u32(0) // dwHelpId
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .u16(MENUEX_POPUP as u16)
+            .string("&Edit")
+            .align_4()
+            .u32(0) // dwHelpId
+            .u32(0)
+            .u32(MFS_CHECKED)
+            .u32(101)
+            .u16(0)
+            .string("Wrap")
+            .align_4()
+            .u32(MFT_SEPARATOR)
+            .u32(0)
+            .u32(0)
+            .u16(MF_END)
+            .string("")
+            .align_4()
+            .u32(0)
+            .u32(MFS_DISABLED)
+            .u32(102)
+            .u16(MF_END)
+            .string("Exit")
+            .0;
+        let menu = MenuResource::parse(&data).unwrap();
+        assert!(menu.extended);
+        assert_eq!(menu.items.len(), 2);
+        let edit = &menu.items[0];
+        assert_eq!(edit.text, "&Edit");
+        assert_eq!(edit.children.len(), 2);
+        assert_eq!(
+            (edit.children[0].id, edit.children[0].state),
+            (101, MFS_CHECKED)
+        );
+        assert_eq!(edit.children[0].get_flag_names(true), "CHECKED");
+        assert_eq!(edit.children[1].get_flag_names(true), "SEPARATOR");
+        assert_eq!(
+            (menu.items[1].id, menu.items[1].text.as_str()),
+            (102, "Exit")
+        );
+        assert_eq!(menu.items[1].get_flag_names(true), "DISABLED");
+    }
+
+    #[test]
+    fn test_truncated_menu() {
+        let data = menu_template();
+        // 缺少结束项时整个菜单无法确定结构
+        assert_eq!(MenuResource::parse(&data[..data.len() - 8]), None);
+        assert_eq!(MenuResource::parse(&data[..2]), None);
+    }
+
+    #[test]
+    fn test_accelerator_table() {
+        let data = Template::default()
+            .u16(FVIRTKEY | FCONTROL)
+            .u16(b'O' as u16)
+            .u16(100)
+            .u16(0)
+            .u16(0)
+            .u16(0x0F)
+            .u16(101)
+            .u16(0)
+            .u16(FVIRTKEY | FALT | ACCELERATOR_END)
+            .u16(0x73)
+            .u16(102)
+            .u16(0)
+            .u16(FVIRTKEY) // 结束项之后的数据不属于该表
+            .u16(0x41)
+            .u16(103)
+            .u16(0)
+            .0;
+        let entries = Accelerator::parse_table(&data);
+        let keys: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.id, entry.key_name.as_str()))
+            .collect();
+        assert_eq!(keys, [(100, "Ctrl+O"), (101, "Ctrl+O"), (102, "Alt+F4")]);
+        assert_eq!(entries[2].flags, FVIRTKEY | FALT);
+        // 不足一项的尾部被忽略
+        assert_eq!(Accelerator::parse_table(&data[..12]).len(), 1);
+    }
+}
@@ -0,0 +1,387 @@
+use crate::tools_api::read_file::nt_header::traits::NtHeaders;
+use crate::tools_api::read_file::{
+    DataDirectory, ImageSectionHeaders, ResourceTree, VersionInfo, VersionString,
+    VersionStringTable, VersionTranslation, VsFixedFileInfo,
+};
+use std::fs::File;
+
+/// VS_FIXEDFILEINFO 的签名
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+const FIXED_FILE_INFO_SIZE: usize = 52;
+/// wLength、wValueLength、wType
+const NODE_HEADER_SIZE: usize = 6;
+/// wType 为1表示值为文本，此时 wValueLength 以字符计
+const NODE_TYPE_TEXT: u16 = 1;
+/// 防止畸形文件导致递归过深
+const MAX_NODE_DEPTH: u32 = 8;
+
+const VS_FF_FLAGS: [(u32, &str); 6] = [
+    (0x01, "DEBUG"),
+    (0x02, "PRERELEASE"),
+    (0x04, "PATCHED"),
+    (0x08, "PRIVATEBUILD"),
+    (0x10, "INFOINFERRED"),
+    (0x20, "SPECIALBUILD"),
+];
+
+/// 版本资源中的一个节点，所有结构都是 wLength/wValueLength/wType/szKey/Value/Children
+struct VersionNode<'a> {
+    key: String,
+    value: &'a [u8],
+    children: Vec<VersionNode<'a>>,
+}
+
+fn align_4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+/// 读取以0结尾的UTF-16字符串，返回字符串与结尾之后的位置
+fn read_utf16(data: &[u8], offset: usize, end: usize) -> (String, usize) {
+    let units: Vec<u16> = data
+        .get(offset..end)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    (
+        String::from_utf16_lossy(&units),
+        offset + (units.len() + 1) * 2,
+    )
+}
+
+impl<'a> VersionNode<'a> {
+    fn parse(data: &'a [u8], offset: usize, depth: u32) -> Option<Self> {
+        let length = read_u16(data, offset)? as usize;
+        if length < NODE_HEADER_SIZE || depth > MAX_NODE_DEPTH {
+            return None;
+        }
+        let end = (offset + length).min(data.len());
+        let value_length = read_u16(data, offset + 2)? as usize;
+        let value_type = read_u16(data, offset + 4)?;
+        let (key, key_end) = read_utf16(data, offset + NODE_HEADER_SIZE, end);
+        let value_start = align_4(key_end).min(end);
+        let value_size = if value_type == NODE_TYPE_TEXT {
+            value_length * 2
+        } else {
+            value_length
+        };
+        let value_end = (value_start + value_size).min(end);
+        let mut children = Vec::new();
+        let mut child_offset = align_4(value_end);
+        while child_offset + NODE_HEADER_SIZE <= end {
+            let Some(child) = Self::parse(&data[..end], child_offset, depth + 1) else {
+                break;
+            };
+            child_offset = align_4(child_offset + read_u16(data, child_offset)? as usize);
+            children.push(child);
+        }
+        Some(VersionNode {
+            key,
+            value: &data[value_start..value_end],
+            children,
+        })
+    }
+
+    /// 文本值，部分编译器写入的 wValueLength 不准确，以0结尾为准
+    fn get_text(&self) -> String {
+        read_utf16(self.value, 0, self.value.len()).0
+    }
+}
+
+impl VsFixedFileInfo {
+    fn new(value: &[u8]) -> Option<Self> {
+        if value.len() < FIXED_FILE_INFO_SIZE {
+            return None;
+        }
+        let field = |index: usize| {
+            let b = &value[index * 4..index * 4 + 4];
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        };
+        let fixed_file_info = VsFixedFileInfo {
+            signature: field(0),
+            struc_version: field(1),
+            file_version_ms: field(2),
+            file_version_ls: field(3),
+            product_version_ms: field(4),
+            product_version_ls: field(5),
+            file_flags_mask: field(6),
+            file_flags: field(7),
+            file_os: field(8),
+            file_type: field(9),
+            file_subtype: field(10),
+            file_date_ms: field(11),
+            file_date_ls: field(12),
+        };
+        (fixed_file_info.signature == FIXED_FILE_INFO_SIGNATURE).then_some(fixed_file_info)
+    }
+
+    pub fn get_file_version(&self) -> String {
+        format!(
+            "{}.{}.{}.{}",
+            self.file_version_ms >> 16,
+            self.file_version_ms & 0xFFFF,
+            self.file_version_ls >> 16,
+            self.file_version_ls & 0xFFFF
+        )
+    }
+
+    pub fn get_product_version(&self) -> String {
+        format!(
+            "{}.{}.{}.{}",
+            self.product_version_ms >> 16,
+            self.product_version_ms & 0xFFFF,
+            self.product_version_ls >> 16,
+            self.product_version_ls & 0xFFFF
+        )
+    }
+
+    /// 只显示 file_flags_mask 中有效的标志
+    pub fn get_file_flag_names(&self) -> String {
+        let flags = self.file_flags & self.file_flags_mask;
+        VS_FF_FLAGS
+            .iter()
+            .filter(|(flag, _)| flags & flag != 0)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+impl VersionInfo {
+    /// 读取第一个 RT_VERSION 资源，文件不含版本资源时返回None
+    pub fn new<T>(
+        file: &mut File,
+        nt_head: &T,
+        section_headers: &ImageSectionHeaders,
+        data_dir: &DataDirectory,
+    ) -> anyhow::Result<Option<VersionInfo>>
+    where
+        T: NtHeaders + ?Sized,
+    {
+        let resource_rva = data_dir.get_resource_directory_address()?;
+        if resource_rva == 0 {
+            return Ok(None);
+        }
+        let resource_tree = ResourceTree::get_resource_tree(
+            file,
+            resource_rva,
+            nt_head,
+            section_headers,
+            data_dir,
+        )?;
+        let Some((_, entry)) = resource_tree
+            .get_type_entries("RT_VERSION")
+            .first()
+            .copied()
+        else {
+            return Ok(None);
+        };
+        let data = entry.read_data(file)?;
+        Ok(Self::parse(&data))
+    }
+
+    fn parse(data: &[u8]) -> Option<VersionInfo> {
+        let root = VersionNode::parse(data, 0, 0)?;
+        let mut version_info = VersionInfo {
+            fixed_file_info: VsFixedFileInfo::new(root.value),
+            ..Default::default()
+        };
+        for child in &root.children {
+            match child.key.as_str() {
+                "StringFileInfo" => {
+                    for table in &child.children {
+                        let language_code = u32::from_str_radix(&table.key, 16).unwrap_or(0);
+                        version_info.string_tables.push(VersionStringTable {
+                            language: (language_code >> 16) as u16,
+                            code_page: language_code as u16,
+                            strings: table
+                                .children
+                                .iter()
+                                .map(|string| VersionString {
+                                    key: string.key.clone(),
+                                    value: string.get_text(),
+                                })
+                                .collect(),
+                        });
+                    }
+                }
+                "VarFileInfo" => {
+                    for var in child.children.iter().filter(|var| var.key == "Translation") {
+                        version_info
+                            .translations
+                            .extend(var.value.chunks_exact(4).map(|c| VersionTranslation {
+                                language: u16::from_le_bytes([c[0], c[1]]),
+                                code_page: u16::from_le_bytes([c[2], c[3]]),
+                            }));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(version_info)
+    }
+
+    /// 在第一个包含该键的 StringTable 中查找
+    pub fn get_string(&self, key: &str) -> Option<&str> {
+        self.string_tables
+            .iter()
+            .flat_map(|table| table.strings.iter())
+            .find(|string| string.key == key)
+            .map(|string| string.value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools_api::FileInfo;
+    use std::path::PathBuf;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect()
+    }
+
+    /// 按 wLength/wValueLength/wType/szKey/Value/Children 拼出一个节点
+    fn build_node(
+        key: &str,
+        value_type: u16,
+        value_length: u16,
+        value: &[u8],
+        children: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut node = vec![0u8; NODE_HEADER_SIZE];
+        node[2..4].copy_from_slice(&value_length.to_le_bytes());
+        node[4..6].copy_from_slice(&value_type.to_le_bytes());
+        node.extend(utf16(key));
+        node.resize(align_4(node.len()), 0);
+        node.extend(value);
+        for child in children {
+            node.resize(align_4(node.len()), 0);
+            node.extend(child);
+        }
+        let length = node.len() as u16;
+        node[..2].copy_from_slice(&length.to_le_bytes());
+        node
+    }
+
+    fn build_string(key: &str, value: &str) -> Vec<u8> {
+        let value = utf16(value);
+        build_node(key, NODE_TYPE_TEXT, (value.len() / 2) as u16, &value, &[])
+    }
+
+    fn build_version_info() -> Vec<u8> {
+        let fixed_file_info: Vec<u8> = [
+            FIXED_FILE_INFO_SIGNATURE,
+            0x0001_0000,
+            0x0001_0002,
+            0x0003_0004,
+            0x0005_0006,
+            0x0007_0008,
+            0x3F,
+            0x05,
+            0x0004_0004,
+            0x01,
+            0,
+            0,
+            0,
+        ]
+        .iter()
+        .flat_map(|field: &u32| field.to_le_bytes())
+        .collect();
+        let string_table = build_node(
+            "040904B0",
+            NODE_TYPE_TEXT,
+            0,
+            &[],
+            &[
+                build_string("CompanyName", "Penguin"),
+                build_string("FileVersion", "1.2.3.4"),
+            ],
+        );
+        let string_file_info =
+            build_node("StringFileInfo", NODE_TYPE_TEXT, 0, &[], &[string_table]);
+        let translation = build_node("Translation", 0, 4, &[0x09, 0x04, 0xB0, 0x04], &[]);
+        let var_file_info = build_node("VarFileInfo", NODE_TYPE_TEXT, 0, &[], &[translation]);
+        build_node(
+            "VS_VERSION_INFO",
+            0,
+            FIXED_FILE_INFO_SIZE as u16,
+            &fixed_file_info,
+            &[string_file_info, var_file_info],
+        )
+    }
+
+    #[test]
+    fn test_parse_version_info() {
+        let version_info = VersionInfo::parse(&build_version_info()).unwrap();
+        let fixed_file_info = version_info.fixed_file_info.as_ref().unwrap();
+        assert_eq!(fixed_file_info.get_file_version(), "1.2.3.4");
+        assert_eq!(fixed_file_info.get_product_version(), "5.6.7.8");
+        assert_eq!(fixed_file_info.get_file_flag_names(), "DEBUG | PATCHED");
+
+        assert_eq!(version_info.string_tables.len(), 1);
+        let string_table = &version_info.string_tables[0];
+        assert_eq!(
+            (string_table.language, string_table.code_page),
+            (0x0409, 0x04B0)
+        );
+        assert_eq!(version_info.get_string("CompanyName"), Some("Penguin"));
+        assert_eq!(version_info.get_string("FileVersion"), Some("1.2.3.4"));
+        assert_eq!(version_info.get_string("ProductName"), None);
+
+        assert_eq!(version_info.translations.len(), 1);
+        let translation = &version_info.translations[0];
+        assert_eq!(
+            (translation.language, translation.code_page),
+            (0x0409, 0x04B0)
+        );
+    }
+
+    #[test]
+    fn test_text_value_length() {
+        // wValueLength 比实际长时以0结尾为准
+        let value = utf16("Penguin");
+        let string = build_node("CompanyName", NODE_TYPE_TEXT, 0x100, &value, &[]);
+        let node = VersionNode::parse(&string, 0, 0).unwrap();
+        assert_eq!(node.key, "CompanyName");
+        assert_eq!(node.get_text(), "Penguin");
+    }
+
+    #[test]
+    fn test_malformed_version_info() {
+        let data = build_version_info();
+        // 截断的数据仍能读出完整的部分
+        let version_info = VersionInfo::parse(&data[..data.len() / 2]).unwrap();
+        assert!(version_info.fixed_file_info.is_some());
+        assert!(version_info.translations.is_empty());
+        // wLength 小于节点头
+        let mut data = data;
+        data[..2].copy_from_slice(&4u16.to_le_bytes());
+        assert!(VersionInfo::parse(&data).is_none());
+    }
+
+    #[test]
+    fn test_read_version_resource() {
+        let file_info = FileInfo::new(PathBuf::from("test_pe/steam_api.dll")).unwrap();
+        let version_info = file_info.version_info.as_ref().unwrap();
+        let fixed_file_info = version_info.fixed_file_info.as_ref().unwrap();
+        assert_eq!(fixed_file_info.get_file_version(), "6.91.21.57");
+        assert_eq!(
+            version_info.get_string("CompanyName"),
+            Some("Valve Corporation")
+        );
+        assert_eq!(
+            version_info.get_string("OriginalFilename"),
+            Some("steam_api.dll")
+        );
+    }
+}
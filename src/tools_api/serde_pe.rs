@@ -1,12 +1,16 @@
 use crate::i18n;
 use crate::tools_api::read_file::{
-    SerializableDataDirectory, SerializableExportTable, SerializableImageSectionHeaders,
-    SerializableImportTable, SerializableNtHeaders,
+    Anomaly, ArchiveInfo, CoffSectionDetail, CoffSymbolTable, DotNetInfo, LoadConfigInfo,
+    ManifestInfo, OverlayInfo, ResourceStrings, RichHeader, SerializableCertificateTable,
+    SerializableDataDirectory, SerializableDebugTable, SerializableExceptionTable,
+    SerializableExportTable, SerializableImageSectionHeaders, SerializableImportTable,
+    SerializableNtHeaders, SerializableRelocationTable, TlsInfo, UiResources, VersionInfo,
 };
-use crate::tools_api::{FileInfo, HashInfo};
+use crate::tools_api::{AuthenticodeHash, FileInfo, HashInfo};
 use anyhow::anyhow;
 use serde_derive::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashMap;
 use std::fs::File;
 use std::fs::write;
 use std::io::{BufReader, Read};
@@ -18,15 +22,35 @@ pub struct SerializableFileInfo {
     pub file_path: PathBuf,
     pub file_hash: Option<HashInfo>,
     pub is_64_bit: bool,
+    pub is_coff_object: bool,
     pub is_little_endian: bool,
     pub file_size: u64,
     pub dos_head: crate::tools_api::read_file::ImageDosHeader,
     pub dos_stub: crate::tools_api::read_file::ImageDosStub,
+    pub rich_header: Option<RichHeader>,
+    pub version_info: Option<VersionInfo>,
     pub nt_headers: SerializableNtHeaders,
     pub data_directory: SerializableDataDirectory,
     pub section_headers: SerializableImageSectionHeaders,
     pub import_dll: SerializableImportTable,
     pub export: SerializableExportTable,
+    pub relocation: SerializableRelocationTable,
+    pub tls: Option<TlsInfo>,
+    pub load_config: Option<LoadConfigInfo>,
+    pub debug: SerializableDebugTable,
+    pub exception: SerializableExceptionTable,
+    pub certificates: SerializableCertificateTable,
+    pub authenticode_hash: Option<AuthenticodeHash>,
+    pub dotnet: Option<DotNetInfo>,
+    pub manifest: Option<ManifestInfo>,
+    pub resource_strings: Option<ResourceStrings>,
+    pub ui_resources: Option<UiResources>,
+    pub overlay: Option<OverlayInfo>,
+    pub symbols: Option<CoffSymbolTable>,
+    pub coff_sections: Option<Vec<CoffSectionDetail>>,
+    pub archive: Option<ArchiveInfo>,
+    #[serde(default)]
+    pub anomalies: Vec<Anomaly>,
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -80,28 +104,97 @@ impl Default for DangerousFunction {
 
 impl SerializableFileInfo {
     pub fn from_file_info(file_info: &mut FileInfo) -> anyhow::Result<Self> {
-        let import_dll_geted = file_info.import_dll.0.borrow().is_empty();
-        if import_dll_geted {
-            file_info.import_dll = file_info.get_imports()?;
+        // 目标文件与静态库没有可选头与数据目录，只读取节与符号相关的信息
+        if file_info.is_pe_image() {
+            let import_dll_geted = file_info.import_dll.0.borrow().is_empty();
+            if import_dll_geted {
+                file_info.import_dll = file_info.get_imports()?;
+            }
+            let export_geted = file_info.export.0.borrow().is_empty();
+            if export_geted {
+                file_info.export = file_info.get_export()?;
+            }
+            let relocation_geted = file_info.relocation.0.borrow().is_empty();
+            if relocation_geted {
+                file_info.relocation = file_info.get_relocations()?;
+            }
+            if file_info.tls.is_none() {
+                file_info.tls = file_info.get_tls()?;
+            }
+            if file_info.load_config.is_none() {
+                file_info.load_config = file_info.get_load_config()?;
+            }
+            let debug_geted = file_info.debug.0.borrow().is_empty();
+            if debug_geted {
+                file_info.debug = file_info.get_debug()?;
+            }
+            let exception_geted = file_info.exception.0.borrow().is_empty();
+            if exception_geted {
+                file_info.exception = file_info.get_exception()?;
+            }
+            if file_info.authenticode_hash.is_none() {
+                file_info.authenticode_hash = Some(file_info.get_authenticode_hash()?);
+            }
+            let certificate_geted = file_info.certificate.0.borrow().is_empty();
+            if certificate_geted {
+                file_info.certificate = file_info.get_certificates()?;
+            }
+            if file_info.dotnet.is_none() {
+                file_info.dotnet = file_info.get_dotnet()?;
+            }
+            if file_info.manifest.is_none() {
+                file_info.manifest = file_info.get_manifest()?;
+            }
+            if file_info.resource_strings.is_none() {
+                file_info.resource_strings = file_info.get_resource_strings()?;
+            }
+            if file_info.ui_resources.is_none() {
+                file_info.ui_resources = file_info.get_ui_resources()?;
+            }
         }
-        let export_geted = file_info.export.0.borrow().is_empty();
-        if export_geted {
-            file_info.export = file_info.get_export()?;
+        file_info.update_file_hash();
+        if file_info.overlay.is_none() {
+            file_info.overlay = file_info.get_overlay()?;
+        }
+        if file_info.symbols.is_none() {
+            file_info.symbols = file_info.get_symbols()?;
+        }
+        if file_info.coff_sections.is_none() {
+            file_info.coff_sections = file_info.get_coff_sections()?;
         }
         Ok(Self {
             file_name: file_info.file_name.clone(),
             file_path: file_info.file_path.clone(),
             file_hash: file_info.file_hash.clone(),
             is_64_bit: file_info.is_64_bit,
+            is_coff_object: file_info.is_coff_object,
             is_little_endian: false, // 暂时使用默认值，因为字段是私有的
             dos_head: *file_info.dos_head.clone(),
             dos_stub: file_info.dos_stub.clone(),
+            rich_header: file_info.rich_header.clone(),
+            version_info: file_info.version_info.clone(),
             nt_headers: file_info.nt_head.serde_serialize(),
             file_size: file_info.file_size,
             data_directory: file_info.data_directory.to_serializable(),
             section_headers: file_info.section_headers.to_serializable(),
             import_dll: file_info.import_dll.to_serializable(),
             export: file_info.export.to_serializable(),
+            relocation: file_info.relocation.to_serializable(),
+            tls: file_info.tls.clone(),
+            load_config: file_info.load_config.clone(),
+            debug: file_info.debug.to_serializable(),
+            exception: file_info.exception.to_serializable(),
+            certificates: file_info.certificate.to_serializable(),
+            authenticode_hash: file_info.authenticode_hash.clone(),
+            dotnet: file_info.dotnet.clone(),
+            manifest: file_info.manifest.clone(),
+            resource_strings: file_info.resource_strings.clone(),
+            ui_resources: file_info.ui_resources.clone(),
+            overlay: file_info.overlay.clone(),
+            symbols: file_info.symbols.clone(),
+            coff_sections: file_info.coff_sections.clone(),
+            archive: file_info.archive.clone(),
+            anomalies: file_info.anomalies.borrow().clone(),
         })
     }
 }
@@ -116,6 +209,69 @@ impl DangerousFunction {
     }
 }
 
+/// 内置的序号导入名称数据库
+pub const BUILTIN_ORDINALS: &str = include_str!("../../config/ordinals.toml");
+
+/// 键为不带扩展名的小写DLL名称，值为序号到函数名的映射
+type OrdinalTable = HashMap<String, HashMap<String, String>>;
+
+/// 序号导入名称数据库，分为内置与用户配置两层
+#[derive(Default, Debug, Clone)]
+pub struct OrdinalDatabase {
+    builtin: OrdinalTable,
+    /// 配置目录下 Ordinals.toml 中的条目，优先于内置条目
+    user: OrdinalTable,
+}
+
+impl OrdinalDatabase {
+    /// 加载内置数据库与配置文件中的条目
+    pub fn from_file_info(file_path: &PathBuf) -> Self {
+        let user = File::open(file_path)
+            .ok()
+            .and_then(|file| {
+                let mut reader_str = String::new();
+                BufReader::new(file).read_to_string(&mut reader_str).ok()?;
+                Some(Self::parse_table(&reader_str))
+            })
+            .unwrap_or_default();
+        OrdinalDatabase {
+            builtin: Self::parse_table(BUILTIN_ORDINALS),
+            user,
+        }
+    }
+
+    fn parse_table(toml_str: &str) -> OrdinalTable {
+        toml::from_str::<OrdinalTable>(toml_str)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(dll, names)| (dll.to_ascii_lowercase(), names))
+            .collect()
+    }
+
+    /// 按DLL名称与序号查找函数名，DLL名称忽略大小写与扩展名
+    pub fn get_name(&self, dll_name: &str, ordinal: u16) -> Option<&str> {
+        Self::lookup(&self.user, dll_name, ordinal)
+            .or_else(|| Self::lookup(&self.builtin, dll_name, ordinal))
+    }
+
+    /// 只查找内置条目，imphash 不能受用户配置影响
+    pub fn get_builtin_name(&self, dll_name: &str, ordinal: u16) -> Option<&str> {
+        Self::lookup(&self.builtin, dll_name, ordinal)
+    }
+
+    fn lookup<'a>(table: &'a OrdinalTable, dll_name: &str, ordinal: u16) -> Option<&'a str> {
+        let dll_name = dll_name.to_ascii_lowercase();
+        let dll_name = match dll_name.rsplit_once('.') {
+            Some((stem, _)) => stem,
+            None => &dll_name,
+        };
+        table
+            .get(dll_name)?
+            .get(&ordinal.to_string())
+            .map(String::as_str)
+    }
+}
+
 pub fn pe_info_to_toml(file_info: &mut FileInfo) -> anyhow::Result<String> {
     let serializable_info = SerializableFileInfo::from_file_info(file_info)?;
     let toml_string = toml::to_string_pretty(&serializable_info).map_err(|e| {
@@ -165,3 +321,48 @@ pub fn save_to_file(
         .map_err(|e| anyhow!("{}", i18n::SAVE_FAILED_ERROR.replace("{}", &e.to_string())))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_ordinals() {
+        toml::from_str::<OrdinalTable>(BUILTIN_ORDINALS).unwrap();
+        let database =
+            OrdinalDatabase::from_file_info(&std::env::temp_dir().join("penguin_no_ordinals.toml"));
+        assert_eq!(database.get_name("WS2_32.dll", 115), Some("WSAStartup"));
+        assert_eq!(database.get_name("ws2_32.dll", 24), Some("GetAddrInfoW"));
+        assert_eq!(database.get_name("wsock32.dll", 29), Some("WSAAccept"));
+        assert_eq!(
+            database.get_name("oleaut32.dll", 411),
+            Some("SafeArrayCreateVector")
+        );
+        assert_eq!(
+            database.get_name("MFC42.DLL", 800),
+            Some("??1CString@@QAE@XZ")
+        );
+        assert_eq!(database.get_name("mfc42", 1), None);
+        assert_eq!(database.get_name("unknown.dll", 1), None);
+    }
+
+    #[test]
+    fn test_user_ordinals_override_builtin() {
+        let path = std::env::temp_dir().join("penguin_user_ordinals.toml");
+        std::fs::write(
+            &path,
+            "[WS2_32]\n115 = \"Startup\"\n[mydll]\n1 = \"MyFunction\"\n",
+        )
+        .unwrap();
+        let database = OrdinalDatabase::from_file_info(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(database.get_name("ws2_32.dll", 115), Some("Startup"));
+        assert_eq!(database.get_name("ws2_32.dll", 116), Some("WSACleanup"));
+        assert_eq!(database.get_name("MyDll.dll", 1), Some("MyFunction"));
+        assert_eq!(
+            database.get_builtin_name("ws2_32.dll", 115),
+            Some("WSAStartup")
+        );
+        assert_eq!(database.get_builtin_name("mydll.dll", 1), None);
+    }
+}